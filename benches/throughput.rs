@@ -0,0 +1,432 @@
+//! Throughput and latency of ringbeam compared to `crossbeam-channel` and `std::sync::mpsc`, and
+//! the cost of the ways to receive a batch.
+//!
+//! Run with `cargo bench --bench throughput`, a single group with for example
+//! `cargo bench --bench throughput -- mpsc`.
+//!
+//! Every throughput measurement creates a new channel and new threads, so nothing is left in the
+//! channel from a previous measurement. The threads are pinned to different cores when possible and
+//! wait on a barrier, so the clock only starts once all of them are running. Criterion runs a warm
+//! up before every measurement. The ringbeam threads wait with a [`Backoff`], like the blocking
+//! methods do, so the results are still meaningful when there are more threads than cores.
+#![allow(
+    clippy::missing_panics_doc,
+    clippy::missing_inline_in_public_items,
+    clippy::unwrap_used,
+    missing_docs,
+    reason = "It's a benchmark"
+)]
+
+use core::hint::black_box;
+use core::time::Duration;
+use criterion::{BenchmarkGroup, BenchmarkId, Criterion, Throughput, measurement::WallTime};
+use ringbeam::{
+    Backoff, Error,
+    custom::{
+        Receiver, Sender,
+        modes::{HeadTailSync, Mode, Multi, RelaxedTailSync, Single},
+    },
+};
+use std::{
+    sync::{Arc, Barrier},
+    thread,
+    time::Instant,
+};
+
+/// The amount of messages sent for one throughput measurement.
+const MESSAGES: usize = 100_000;
+
+/// The amount of values sent or received with one call.
+const BATCHES: [usize; 3] = [1, 16, 64];
+
+/// The senders and receivers of one channel.
+type Handles<const N: usize, P, C> = (Vec<Sender<N, usize, P, C>>, Vec<Receiver<N, usize, P, C>>);
+
+/// Start `f` on a new thread pinned to core `index`, if there are enough cores.
+fn spawn_pinned<F>(index: usize, f: F) -> thread::JoinHandle<()>
+where
+    F: FnOnce() + Send + 'static,
+{
+    thread::spawn(move || {
+        if let Some(cores) = core_affinity::get_core_ids()
+            && let Some(core) = cores.get(index)
+        {
+            core_affinity::set_for_current(*core);
+        }
+        f();
+    })
+}
+
+/// Send and receive [`MESSAGES`] over the handles, with `batch` values per call.
+///
+/// Returns the time between all threads being ready and the last value being received.
+fn run_ringbeam<const N: usize, P, C>(
+    senders: Vec<Sender<N, usize, P, C>>,
+    receivers: Vec<Receiver<N, usize, P, C>>,
+    batch: usize,
+) -> Duration
+where
+    P: Mode + 'static,
+    C: Mode + 'static,
+{
+    let per_sender = MESSAGES / senders.len();
+    let barrier = Arc::new(Barrier::new(senders.len() + receivers.len() + 1));
+    let mut handles = Vec::new();
+    for (index, sender) in senders.into_iter().enumerate() {
+        let barrier = barrier.clone();
+        handles.push(spawn_pinned(index, move || {
+            let values: Vec<usize> = (0..batch).collect();
+            barrier.wait();
+            let mut backoff = Backoff::new();
+            let mut sent = 0;
+            while sent < per_sender {
+                let todo = batch.min(per_sender - sent);
+                match sender.try_send_slice_burst(&values[..todo]) {
+                    Ok(0) | Err(Error::Full) => backoff.snooze(),
+                    Ok(n) => {
+                        sent += n;
+                        backoff.reset();
+                    }
+                    Err(err) => panic!("{err:?}"),
+                }
+            }
+        }));
+    }
+    let offset = handles.len();
+    for (index, receiver) in receivers.into_iter().enumerate() {
+        let barrier = barrier.clone();
+        handles.push(spawn_pinned(offset + index, move || {
+            let mut backoff = Backoff::new();
+            barrier.wait();
+            loop {
+                match receiver.try_recv_burst(batch) {
+                    Ok(values) => {
+                        values.for_each(|value| {
+                            black_box(value);
+                        });
+                        backoff.reset();
+                    }
+                    Err(Error::Empty) => backoff.snooze(),
+                    Err(Error::Closed) => break,
+                    Err(err) => panic!("{err:?}"),
+                }
+            }
+        }));
+    }
+    barrier.wait();
+    let start = Instant::now();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    start.elapsed()
+}
+
+/// Send and receive [`MESSAGES`] over a `crossbeam-channel` with a capacity of `cap`.
+fn run_crossbeam(senders: usize, receivers: usize, cap: usize) -> Duration {
+    let (sender, receiver) = crossbeam_channel::bounded(cap);
+    let per_sender = MESSAGES / senders;
+    let barrier = Arc::new(Barrier::new(senders + receivers + 1));
+    let mut handles = Vec::new();
+    for index in 0..senders {
+        let (sender, barrier) = (sender.clone(), barrier.clone());
+        handles.push(spawn_pinned(index, move || {
+            barrier.wait();
+            for value in 0..per_sender {
+                sender.send(value).unwrap();
+            }
+        }));
+    }
+    drop(sender);
+    for index in 0..receivers {
+        let (receiver, barrier) = (receiver.clone(), barrier.clone());
+        handles.push(spawn_pinned(senders + index, move || {
+            barrier.wait();
+            for value in receiver {
+                black_box(value);
+            }
+        }));
+    }
+    drop(receiver);
+    barrier.wait();
+    let start = Instant::now();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    start.elapsed()
+}
+
+/// Send and receive [`MESSAGES`] over a `std::sync::mpsc::sync_channel` with a capacity of `cap`.
+fn run_std(senders: usize, cap: usize) -> Duration {
+    let (sender, receiver) = std::sync::mpsc::sync_channel(cap);
+    let per_sender = MESSAGES / senders;
+    let barrier = Arc::new(Barrier::new(senders + 2));
+    let mut handles = Vec::new();
+    for index in 0..senders {
+        let (sender, barrier) = (sender.clone(), barrier.clone());
+        handles.push(spawn_pinned(index, move || {
+            barrier.wait();
+            for value in 0..per_sender {
+                sender.send(value).unwrap();
+            }
+        }));
+    }
+    drop(sender);
+    let barrier2 = barrier.clone();
+    handles.push(spawn_pinned(senders, move || {
+        barrier2.wait();
+        for value in receiver {
+            black_box(value);
+        }
+    }));
+    barrier.wait();
+    let start = Instant::now();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    start.elapsed()
+}
+
+/// Measure a ringbeam channel of size `N` for every batch size, creating the handles with `make`.
+fn bench_ringbeam<const N: usize, P, C, F>(
+    group: &mut BenchmarkGroup<'_, WallTime>,
+    name: &str,
+    make: F,
+) where
+    P: Mode + 'static,
+    C: Mode + 'static,
+    F: Fn() -> Handles<N, P, C>,
+{
+    for batch in BATCHES {
+        group.bench_with_input(
+            BenchmarkId::new(format!("ringbeam/{name}/{N}"), batch),
+            &batch,
+            |b, &batch| {
+                b.iter_custom(|iters| {
+                    (0..iters)
+                        .map(|_| {
+                            let (senders, receivers) = make();
+                            run_ringbeam(senders, receivers, batch)
+                        })
+                        .sum()
+                });
+            },
+        );
+    }
+}
+
+/// Measure the other channels with a capacity of `cap`, which don't have a batch size.
+fn bench_others(
+    group: &mut BenchmarkGroup<'_, WallTime>,
+    senders: usize,
+    receivers: usize,
+    cap: usize,
+) {
+    group.bench_with_input(
+        BenchmarkId::new(format!("crossbeam/{cap}"), 1),
+        &cap,
+        |b, &cap| {
+            b.iter_custom(|iters| {
+                (0..iters)
+                    .map(|_| run_crossbeam(senders, receivers, cap))
+                    .sum()
+            });
+        },
+    );
+    if receivers == 1 {
+        group.bench_with_input(
+            BenchmarkId::new(format!("std/{cap}"), 1),
+            &cap,
+            |b, &cap| {
+                b.iter_custom(|iters| (0..iters).map(|_| run_std(senders, cap)).sum());
+            },
+        );
+    }
+}
+
+/// Create a channel with `senders` senders and one receiver.
+fn many_senders<const N: usize, P>(senders: usize) -> Handles<N, P, Single>
+where
+    P: Mode + Sync,
+{
+    let (sender, receiver) = ringbeam::custom::bounded::<N, usize, P, Single>();
+    let mut all = vec![sender.clone(); senders - 1];
+    all.push(sender);
+    (all, vec![receiver])
+}
+
+/// Create a channel with `senders` senders and `receivers` receivers.
+fn many_both<const N: usize, M>(senders: usize, receivers: usize) -> Handles<N, M, M>
+where
+    M: Mode + Sync,
+{
+    let (sender, receiver) = ringbeam::custom::bounded::<N, usize, M, M>();
+    let mut all_senders = vec![sender.clone(); senders - 1];
+    all_senders.push(sender);
+    let mut all_receivers = vec![receiver.clone(); receivers - 1];
+    all_receivers.push(receiver);
+    (all_senders, all_receivers)
+}
+
+/// Measure the single-producer single-consumer channels for a ring of size `N`.
+fn spsc<const N: usize>(group: &mut BenchmarkGroup<'_, WallTime>) {
+    bench_ringbeam::<N, Single, Single, _>(group, "single", || {
+        let (sender, receiver) = ringbeam::spsc::bounded::<N, usize>();
+        (vec![sender], vec![receiver])
+    });
+    bench_others(group, 1, 1, N - 1);
+}
+
+/// Measure the multi-producer single-consumer channels for a ring of size `N`.
+fn mpsc<const N: usize>(group: &mut BenchmarkGroup<'_, WallTime>) {
+    bench_ringbeam::<N, Multi, Single, _>(group, "multi", || many_senders(4));
+    bench_ringbeam::<N, HeadTailSync, Single, _>(group, "hts", || many_senders(4));
+    bench_ringbeam::<N, RelaxedTailSync, Single, _>(group, "rts", || many_senders(4));
+    bench_others(group, 4, 1, N - 1);
+}
+
+/// Measure the multi-producer multi-consumer channels for a ring of size `N`.
+fn mpmc<const N: usize>(group: &mut BenchmarkGroup<'_, WallTime>) {
+    bench_ringbeam::<N, Multi, Multi, _>(group, "multi", || many_both(2, 2));
+    bench_ringbeam::<N, HeadTailSync, HeadTailSync, _>(group, "hts", || many_both(2, 2));
+    bench_ringbeam::<N, RelaxedTailSync, RelaxedTailSync, _>(group, "rts", || many_both(2, 2));
+    bench_others(group, 2, 2, N - 1);
+}
+
+/// Measure the throughput for the different amounts of senders and receivers.
+fn throughput(c: &mut Criterion) {
+    for (name, bench) in [
+        ("spsc", [spsc::<64>, spsc::<1024>]),
+        ("mpsc", [mpsc::<64>, mpsc::<1024>]),
+        ("mpmc", [mpmc::<64>, mpmc::<1024>]),
+    ] {
+        let mut group = c.benchmark_group(name);
+        group
+            .throughput(Throughput::Elements(MESSAGES as u64))
+            .warm_up_time(Duration::from_secs(1))
+            .sample_size(20);
+        for bench in bench {
+            bench(&mut group);
+        }
+        group.finish();
+    }
+}
+
+/// Measure `iters` round trips of one value between two threads over the channels.
+fn round_trips<const N: usize, M>(iters: u64) -> Duration
+where
+    M: Mode + 'static,
+{
+    let (request, requests) = ringbeam::custom::bounded::<N, u64, M, M>();
+    let (reply, replies) = ringbeam::custom::bounded::<N, u64, M, M>();
+    let barrier = Arc::new(Barrier::new(2));
+    let barrier2 = barrier.clone();
+    let handle = spawn_pinned(1, move || {
+        barrier2.wait();
+        while let Ok(value) = requests.recv() {
+            reply.send(value).unwrap();
+        }
+    });
+    barrier.wait();
+    let start = Instant::now();
+    for i in 0..iters {
+        request.send(i).unwrap();
+        black_box(replies.recv().unwrap());
+    }
+    let elapsed = start.elapsed();
+    drop(request);
+    handle.join().unwrap();
+    elapsed
+}
+
+/// Measure the round trip latency of every mode, and of the other channels.
+fn latency(c: &mut Criterion) {
+    let mut group = c.benchmark_group("latency");
+    group.warm_up_time(Duration::from_secs(1));
+    group.bench_function("ringbeam/single", |b| {
+        b.iter_custom(round_trips::<64, Single>);
+    });
+    group.bench_function("ringbeam/multi", |b| {
+        b.iter_custom(round_trips::<64, Multi>);
+    });
+    group.bench_function("ringbeam/hts", |b| {
+        b.iter_custom(round_trips::<64, HeadTailSync>);
+    });
+    group.bench_function("ringbeam/rts", |b| {
+        b.iter_custom(round_trips::<64, RelaxedTailSync>);
+    });
+    group.bench_function("crossbeam", |b| {
+        b.iter_custom(|iters| {
+            let (request, requests) = crossbeam_channel::bounded(63);
+            let (reply, replies) = crossbeam_channel::bounded(63);
+            let handle = spawn_pinned(1, move || {
+                for value in requests {
+                    reply.send(value).unwrap();
+                }
+            });
+            let start = Instant::now();
+            for i in 0..iters {
+                request.send(i).unwrap();
+                black_box(replies.recv().unwrap());
+            }
+            let elapsed = start.elapsed();
+            drop(request);
+            handle.join().unwrap();
+            elapsed
+        });
+    });
+    group.bench_function("std", |b| {
+        b.iter_custom(|iters| {
+            let (request, requests) = std::sync::mpsc::sync_channel(63);
+            let (reply, replies) = std::sync::mpsc::sync_channel(63);
+            let handle = spawn_pinned(1, move || {
+                for value in requests {
+                    reply.send(value).unwrap();
+                }
+            });
+            let start = Instant::now();
+            for i in 0..iters {
+                request.send(i).unwrap();
+                black_box(replies.recv().unwrap());
+            }
+            let elapsed = start.elapsed();
+            drop(request);
+            handle.join().unwrap();
+            elapsed
+        });
+    });
+    group.finish();
+}
+
+/// Measure receiving a batch with [`try_recv_each`](Receiver::try_recv_each) compared to
+/// iterating over [`try_recv_burst`](Receiver::try_recv_burst), on a single thread.
+fn recv_each(c: &mut Criterion) {
+    let mut group = c.benchmark_group("recv_each");
+    group.warm_up_time(Duration::from_secs(1));
+    for batch in BATCHES {
+        let values: Vec<usize> = (0..batch).collect();
+        let (sender, receiver) = ringbeam::spsc::bounded::<1024, usize>();
+        group.throughput(Throughput::Elements(batch as u64));
+        group.bench_with_input(BenchmarkId::new("each", batch), &batch, |b, &batch| {
+            b.iter(|| {
+                sender.try_send_slice(&values).unwrap();
+                receiver
+                    .try_recv_each(batch, |value| {
+                        black_box(value);
+                    })
+                    .unwrap()
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("iter", batch), &batch, |b, &batch| {
+            b.iter(|| {
+                sender.try_send_slice(&values).unwrap();
+                for value in receiver.try_recv_burst(batch).unwrap() {
+                    black_box(value);
+                }
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion::criterion_group!(benches, throughput, latency, recv_each);
+criterion::criterion_main!(benches);
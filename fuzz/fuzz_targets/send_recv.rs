@@ -0,0 +1,99 @@
+//! Interleaved sends and receives on a small channel from a single thread.
+//!
+//! Every byte of the input is one operation, the lowest two bits choose the operation and the
+//! other six bits the amount of values. The amounts go up to 63, so the operations regularly ask
+//! for more than fits in the channel. The channel is compared to a [`VecDeque`] after every
+//! operation, so values must come out in the order they went in.
+//!
+//! The values are boxed, so a value that is dropped twice or read before it's written is caught
+//! even without the `_safe_maybeuninit` feature. Run it with:
+//! ```text
+//! cargo +nightly fuzz run send_recv
+//! ```
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ringbeam::Error;
+use std::collections::VecDeque;
+
+/// The size of the ring, small so the operations wrap around the end of the ring often.
+const N: usize = 8;
+
+/// The amount of values that fit in the channel.
+const CAPACITY: usize = N - 1;
+
+fuzz_target!(|data: &[u8]| {
+    let (sender, receiver) = ringbeam::spsc::bounded::<N, Box<usize>>();
+    let mut expected = VecDeque::with_capacity(CAPACITY);
+    let mut next = 0;
+
+    for &byte in data {
+        let amount = usize::from(byte >> 2);
+        let room = CAPACITY - expected.len();
+        match byte & 0b11 {
+            0 => match sender.try_send(Box::new(next)) {
+                Ok(None) => {
+                    assert!(room > 0, "Sent a value to a full channel");
+                    expected.push_back(next);
+                    next += 1;
+                }
+                Ok(Some(value)) => {
+                    assert_eq!(room, 0, "The channel has room but refused the value");
+                    assert_eq!(*value, next, "Got another value back than was sent");
+                }
+                Err(err) => panic!("Unexpected error {err:?}"),
+            },
+            1 => match receiver.try_recv() {
+                Ok(value) => assert_eq!(Some(*value), expected.pop_front(), "Out of order"),
+                Err(Error::Empty) => assert!(expected.is_empty(), "Channel empty too soon"),
+                Err(err) => panic!("Unexpected error {err:?}"),
+            },
+            2 => {
+                let mut values = (next..next + amount).map(Box::new);
+                match sender.try_send_bulk(&mut values) {
+                    Ok(sent) => {
+                        assert_eq!(sent, amount, "Sent only part of the bulk");
+                        assert!(amount <= room, "Sent more values than fit");
+                        expected.extend(next..next + amount);
+                        next += amount;
+                    }
+                    Err(Error::Full | Error::NotEnoughSpace) => {
+                        assert!(amount > room, "The bulk fits but was refused");
+                        assert_eq!(values.len(), amount, "A refused bulk took values");
+                    }
+                    Err(err) => panic!("Unexpected error {err:?}"),
+                }
+            }
+            _ => match receiver.try_recv_burst(amount) {
+                Ok(values) => {
+                    assert!(
+                        values.len() <= amount,
+                        "Received more values than requested"
+                    );
+                    assert_eq!(
+                        values.len(),
+                        amount.min(expected.len()),
+                        "Received less values than available"
+                    );
+                    for value in values {
+                        assert_eq!(Some(*value), expected.pop_front(), "Out of order");
+                    }
+                }
+                Err(Error::Empty) => {
+                    assert!(
+                        amount == 0 || expected.is_empty(),
+                        "Channel empty too soon"
+                    );
+                }
+                Err(err) => panic!("Unexpected error {err:?}"),
+            },
+        }
+    }
+
+    // The values that are left can still be received once the sender is gone
+    drop(sender);
+    for left in expected {
+        assert_eq!(receiver.try_recv().map(|value| *value), Ok(left), "Out of order");
+    }
+    assert_eq!(receiver.try_recv(), Err(Error::Closed), "Channel not closed");
+});
@@ -0,0 +1,77 @@
+//! Waiting for the channel to become available.
+use crate::std::{hint::spin_loop, thread::yield_now};
+
+/// The strategy for waiting in the blocking operations of the channel.
+///
+/// `Backoff` starts by spinning with [`spin_loop`](core::hint::spin_loop), doubling the amount
+/// of spins every step. Once the spin limit is reached, it yields to the scheduler with
+/// [`yield_now`](std::thread::yield_now) instead. This keeps the latency low when the channel
+/// becomes available quickly, while not wasting cycles when it takes longer.
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    /// The current step.
+    step: u32,
+    /// The step after which it yields instead of spinning.
+    spin_limit: u32,
+}
+
+impl Default for Backoff {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Backoff {
+    /// The default spin limit, spinning at most `2^6` times in a row.
+    pub(crate) const SPIN_LIMIT: u32 = 6;
+
+    /// Create a new backoff with the default spin limit.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self::with_spin_limit(Self::SPIN_LIMIT)
+    }
+
+    /// Create a new backoff that spins for `spin_limit` steps before it starts yielding.
+    ///
+    /// Step `n` spins `2^n` times, so the limit is clamped to 16. A limit of 0 spins only
+    /// once before yielding.
+    #[must_use]
+    #[inline]
+    pub const fn with_spin_limit(spin_limit: u32) -> Self {
+        Self {
+            step: 0,
+            spin_limit: if spin_limit > 16 { 16 } else { spin_limit },
+        }
+    }
+
+    /// Wait a bit, waiting longer every time this is called.
+    #[inline]
+    pub fn snooze(&mut self) {
+        // Every spin is a yield for the model checkers, so spinning only adds interleavings
+        if cfg!(any(feature = "_loom", feature = "_shuttle")) {
+            yield_now();
+        } else if self.step <= self.spin_limit {
+            for _ in 0..1u32 << self.step {
+                spin_loop();
+            }
+            self.step += 1;
+        } else {
+            yield_now();
+        }
+    }
+
+    /// Has the backoff stopped spinning and started yielding.
+    #[must_use]
+    #[inline]
+    pub const fn is_yielding(&self) -> bool {
+        self.step > self.spin_limit
+    }
+
+    /// Start spinning again from the first step.
+    #[inline]
+    pub const fn reset(&mut self) {
+        self.step = 0;
+    }
+}
@@ -0,0 +1,396 @@
+//! A broadcast channel, where every receiver gets a clone of every value.
+//!
+//! The other channels hand every value to one receiver. Here every receiver has its own position
+//! in the ring, and a value is only overwritten once every receiver has read it. The sender can
+//! therefore be only as fast as the slowest receiver.
+//!
+//! ```
+//! let (sender, receiver) = ringbeam::broadcast::bounded::<8, u32>(4);
+//! let receiver2 = receiver.clone();
+//! sender.try_send(1).unwrap();
+//! assert_eq!(receiver.try_recv(), Ok(1));
+//! assert_eq!(receiver2.try_recv(), Ok(1));
+//! ```
+use crate::{
+    Backoff, Error,
+    cache_padded::CachePadded,
+    std::{
+        cell::UnsafeCell,
+        hint::cold_path,
+        sync::{
+            Arc,
+            atomic::{
+                AtomicU32, AtomicU64,
+                Ordering::{Acquire, Relaxed, Release, SeqCst},
+            },
+        },
+    },
+};
+use core::{cell::Cell, marker::PhantomData};
+
+/// The bit of a cursor that is set when it belongs to a receiver.
+const IN_USE: u64 = 1 << 32;
+
+/// The state shared by the sender and the receivers.
+struct Shared<const N: usize, T> {
+    /// The position up to which values are sent, marked as finished when the sender is dropped.
+    tail: CachePadded<AtomicU32>,
+    /// The position of every receiver, or 0 if the cursor is free.
+    ///
+    /// A receiver stores its position with [`IN_USE`] set, so a receiver at position 0 is not
+    /// mistaken for a free cursor.
+    cursors: Box<[CachePadded<AtomicU64>]>,
+    /// Incremented by every [`register`](Self::register).
+    ///
+    /// A receiver can register a cursor that the sender already checked, and then move past the
+    /// entries the new receiver still has to read. The sender checks the cursors again if this
+    /// changed while it checked them.
+    epoch: CachePadded<AtomicU32>,
+    /// The values of the ring.
+    ///
+    /// # Safety
+    /// An entry is only written by the sender, when every receiver is past it. Between a receiver
+    /// and the tail the entries are `Some` and only read.
+    data: Box<[UnsafeCell<Option<T>>]>,
+}
+
+// SAFETY: The values of `T` are moved to other threads, and the receivers share references to them.
+//         The entries are only accessed as described at `data`.
+unsafe impl<const N: usize, T> Send for Shared<N, T> where T: Send + Sync {}
+// SAFETY: See above.
+unsafe impl<const N: usize, T> Sync for Shared<N, T> where T: Send + Sync {}
+
+impl<const N: usize, T> Shared<N, T> {
+    /// Claim a free cursor, starting at `position`.
+    ///
+    /// # Errors
+    /// Returns [`Error::TooManyConsumers`] if every cursor belongs to a receiver.
+    fn register(&self, position: u32) -> Result<usize, Error> {
+        // Sequentially consistent, so the sender sees the cursor in the first check of the
+        // receivers that starts after this
+        let cursor = self
+            .cursors
+            .iter()
+            .position(|cursor| {
+                cursor
+                    .compare_exchange(0, IN_USE | u64::from(position), SeqCst, Relaxed)
+                    .is_ok()
+            })
+            .ok_or_else(|| {
+                cold_path();
+                Error::TooManyConsumers
+            })?;
+        // Release the cursor, a sender that sees the new epoch also sees the cursor
+        self.epoch.fetch_add(1, Release);
+        Ok(cursor)
+    }
+
+    /// Check the cursors, returning if there are any receivers and how far the slowest receiver
+    /// is behind `tail`.
+    fn behind(&self, tail: u32) -> (bool, u32) {
+        let mut receivers = false;
+        let mut behind = 0;
+        for cursor in &self.cursors {
+            // Sync with the store of the position by the receiver, so it's done reading the
+            // entries before it
+            let cursor = cursor.load(Acquire);
+            if cursor & IN_USE != 0 {
+                receivers = true;
+                behind = behind.max(tail.wrapping_sub(cursor as u32) & 0x7FFF_FFFF);
+            }
+        }
+        (receivers, behind)
+    }
+}
+
+/// Create a broadcast channel with space for `N - 1` values of `T`, for at most `max_receivers`
+/// receivers at the same time.
+///
+/// Every receiver needs its own cursor, which are allocated together with the channel. The
+/// channel starts with one receiver.
+///
+/// # Panics
+/// Will panic if `max_receivers` is 0.
+#[must_use]
+#[inline]
+pub fn bounded<const N: usize, T>(max_receivers: usize) -> (Sender<N, T>, Receiver<N, T>)
+where
+    T: Clone,
+{
+    const {
+        assert!(
+            N >= 2 && N.is_power_of_two() && N <= 1 << 31,
+            "The size of the ring `N` must be a power of two from 2 up to and including 2^31"
+        );
+    }
+    assert!(max_receivers > 0, "A channel needs at least one receiver");
+    let shared = Arc::new(Shared {
+        tail: CachePadded::new(AtomicU32::new(0)),
+        cursors: (0..max_receivers)
+            .map(|_| CachePadded::new(AtomicU64::new(0)))
+            .collect(),
+        epoch: CachePadded::new(AtomicU32::new(0)),
+        data: (0..N).map(|_| UnsafeCell::new(None)).collect(),
+    });
+    let cursor = shared.register(0).unwrap_or_else(|_| unreachable!());
+    let receiver = Receiver {
+        shared: shared.clone(),
+        cursor,
+        not_sync: PhantomData,
+    };
+    (
+        Sender {
+            shared,
+            not_sync: PhantomData,
+        },
+        receiver,
+    )
+}
+
+/// The sending half of a broadcast channel.
+///
+/// There is only one sender, as the entries are written without a claim. It's not [`Sync`] for
+/// the same reason.
+pub struct Sender<const N: usize, T> {
+    /// The channel.
+    shared: Arc<Shared<N, T>>,
+    /// Only one thread may send at a time.
+    not_sync: PhantomData<Cell<()>>,
+}
+
+impl<const N: usize, T> Sender<N, T>
+where
+    T: Clone,
+{
+    /// Try to put the value in the channel for every receiver.
+    ///
+    /// # Errors
+    /// Returns [`Ok(Some(T))`](Option::Some) when full, which is when the slowest receiver
+    /// still has `N - 1` values to read. Returns [`Error::Closed`] if there are no receivers,
+    /// the value is dropped in that case.
+    #[inline]
+    pub fn try_send(&self, value: T) -> Result<Option<T>, Error> {
+        // Only the sender stores the tail, and it's not finished while the sender exists
+        let tail = self.shared.tail.load(Relaxed);
+        let (receivers, behind) = loop {
+            // Sync with the increment of `register`. If a receiver registered a cursor that was
+            // already checked, it did so before it moved. If the check saw it move, the second
+            // load sees the new epoch and the cursors are checked again.
+            let epoch = self.shared.epoch.load(Acquire);
+            let behind = self.shared.behind(tail);
+            if self.shared.epoch.load(Acquire) == epoch {
+                break behind;
+            }
+            cold_path();
+        };
+        if !receivers {
+            cold_path();
+            return Err(Error::Closed);
+        }
+        if behind as usize >= N - 1 {
+            cold_path();
+            return Ok(Some(value));
+        }
+        // SAFETY: Every receiver has read the entry at `tail` if it was written before, so
+        //         nothing else is accessing it.
+        self.shared.data[tail as usize & (N - 1)].with_mut(|p| unsafe { *p = Some(value) });
+        self.shared
+            .tail
+            .store(tail.wrapping_add(1) & 0x7FFF_FFFF, Release);
+        Ok(None)
+    }
+
+    /// Put the value in the channel for every receiver, waiting while the channel is full.
+    ///
+    /// # Errors
+    /// Returns [`Error::Closed`] if there are no receivers, the value is dropped in that case.
+    #[inline]
+    pub fn send(&self, mut value: T) -> Result<(), Error> {
+        let mut backoff = Backoff::new();
+        while let Some(returned) = self.try_send(value)? {
+            value = returned;
+            backoff.snooze();
+        }
+        Ok(())
+    }
+
+    /// Create a receiver that gets the values sent from now on.
+    ///
+    /// # Errors
+    /// Returns [`Error::TooManyConsumers`] if there are already `max_receivers` receivers, see
+    /// [`bounded`].
+    #[inline]
+    pub fn subscribe(&self) -> Result<Receiver<N, T>, Error> {
+        let tail = self.shared.tail.load(Relaxed);
+        let cursor = self.shared.register(tail)?;
+        Ok(Receiver {
+            shared: self.shared.clone(),
+            cursor,
+            not_sync: PhantomData,
+        })
+    }
+
+    /// The amount of values the slowest receiver can hold, which is `N - 1`.
+    #[must_use]
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N - 1
+    }
+}
+
+impl<const N: usize, T> core::fmt::Debug for Sender<N, T> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Sender")
+            .field("tail", &self.shared.tail.load(Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<const N: usize, T> Drop for Sender<N, T> {
+    #[inline]
+    fn drop(&mut self) {
+        // Release the last values, so a receiver that sees the mark also sees them
+        self.shared.tail.fetch_or(0x8000_0000, Release);
+    }
+}
+
+/// The receiving half of a broadcast channel.
+///
+/// Cloning a receiver creates a new receiver at the same position, which gets the same values.
+/// A receiver is not [`Sync`], as its position can only be moved by one thread.
+pub struct Receiver<const N: usize, T> {
+    /// The channel.
+    shared: Arc<Shared<N, T>>,
+    /// The index of the cursor of this receiver.
+    cursor: usize,
+    /// Only one thread may move the position at a time.
+    not_sync: PhantomData<Cell<()>>,
+}
+
+impl<const N: usize, T> Receiver<N, T>
+where
+    T: Clone,
+{
+    /// The position of this receiver.
+    #[inline]
+    fn position(&self) -> u32 {
+        // Only this receiver stores its cursor
+        self.shared.cursors[self.cursor].load(Relaxed) as u32
+    }
+
+    /// Try to get a clone of the next value.
+    ///
+    /// If cloning the value panics, the value stays in the channel for this receiver.
+    ///
+    /// # Errors
+    /// Returns [`Error::Empty`] if this receiver has read every value, or [`Error::Closed`] if
+    /// the sender is also dropped.
+    #[inline]
+    pub fn try_recv(&self) -> Result<T, Error> {
+        let position = self.position();
+        // Sync with the store of the tail by the sender, so the entries before it are written
+        let tail = self.shared.tail.load(Acquire);
+        if position == tail & 0x7FFF_FFFF {
+            cold_path();
+            return Err(if tail & 0x8000_0000 == 0 {
+                Error::Empty
+            } else {
+                Error::Closed
+            });
+        }
+        // SAFETY: The entry is before the tail, so it's written and the sender won't write it
+        //         again until this receiver is past it.
+        let value = self.shared.data[position as usize & (N - 1)]
+            .with(|p| unsafe { (*p).as_ref().unwrap_or_else(|| unreachable!()).clone() });
+        // Release the read of the entry, so the sender can write it again
+        self.shared.cursors[self.cursor].store(
+            IN_USE | u64::from(position.wrapping_add(1) & 0x7FFF_FFFF),
+            Release,
+        );
+        Ok(value)
+    }
+
+    /// Get a clone of the next value, waiting while there is none.
+    ///
+    /// # Errors
+    /// Returns [`Error::Closed`] once the sender is dropped and this receiver has read every value.
+    #[inline]
+    pub fn recv(&self) -> Result<T, Error> {
+        let mut backoff = Backoff::new();
+        loop {
+            match self.try_recv() {
+                Err(Error::Empty) => backoff.snooze(),
+                result => return result,
+            }
+        }
+    }
+
+    /// Create a new receiver at the same position as this receiver.
+    ///
+    /// # Errors
+    /// Returns [`Error::TooManyConsumers`] if there are already `max_receivers` receivers, see
+    /// [`bounded`].
+    #[inline]
+    pub fn subscribe(&self) -> Result<Self, Error> {
+        // This receiver doesn't move while registering, so the sender can't overwrite entries
+        // after the position. A check of the sender that misses the new cursor but sees this
+        // receiver move afterwards is repeated, see `Shared::epoch`.
+        let cursor = self.shared.register(self.position())?;
+        Ok(Self {
+            shared: self.shared.clone(),
+            cursor,
+            not_sync: PhantomData,
+        })
+    }
+
+    /// The amount of values this receiver has not read yet.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        let tail = self.shared.tail.load(Acquire);
+        (tail.wrapping_sub(self.position()) & 0x7FFF_FFFF) as usize
+    }
+
+    /// Has this receiver read every value.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The amount of values a receiver can hold, which is `N - 1`.
+    #[must_use]
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N - 1
+    }
+}
+
+impl<const N: usize, T> Clone for Receiver<N, T>
+where
+    T: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        self.subscribe().expect("Failed to clone receiver!")
+    }
+}
+
+impl<const N: usize, T> core::fmt::Debug for Receiver<N, T> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Receiver")
+            .field("tail", &self.shared.tail.load(Relaxed))
+            .field("cursor", &self.shared.cursors[self.cursor].load(Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<const N: usize, T> Drop for Receiver<N, T> {
+    #[inline]
+    fn drop(&mut self) {
+        // Give up the cursor, the sender no longer waits for this receiver
+        self.shared.cursors[self.cursor].store(0, Release);
+    }
+}
@@ -0,0 +1,188 @@
+//! Optional accounting of the bytes buffered in a channel.
+//!
+//! Bounding a channel by the amount of values doesn't bound its memory usage when the values own
+//! a variable amount of memory. A ring with a budget measures every value with [`Measure`] and
+//! rejects values that would bring the buffered bytes over the limit.
+use crate::{
+    Error,
+    std::{
+        hint::cold_path,
+        sync::atomic::{AtomicUsize, Ordering::Relaxed},
+    },
+};
+
+/// Measure how many bytes a value accounts for in the byte budget of a channel.
+///
+/// See [`bounded_with_budget`](crate::custom::bounded_with_budget).
+pub trait Measure {
+    /// The amount of bytes `self` accounts for.
+    ///
+    /// This **must** return the same value every time it's called on the same value.
+    fn measure(&self) -> usize;
+}
+
+impl<T> Measure for Vec<T> {
+    #[inline]
+    fn measure(&self) -> usize {
+        size_of_val(self.as_slice())
+    }
+}
+
+impl<T> Measure for Box<[T]> {
+    #[inline]
+    fn measure(&self) -> usize {
+        size_of_val(&**self)
+    }
+}
+
+impl Measure for String {
+    #[inline]
+    fn measure(&self) -> usize {
+        self.len()
+    }
+}
+
+/// The byte budget of a ring.
+pub struct Budget<T> {
+    /// How to measure a value, `None` if the ring doesn't have a budget.
+    measure: Option<fn(&T) -> usize>,
+    /// The maximum amount of bytes that can be buffered.
+    limit: usize,
+    /// The amount of bytes currently buffered or reserved by a producer.
+    ///
+    /// Producers add to this before they publish their values and consumers subtract from it
+    /// after taking a value out of the ring, so it never underflows.
+    bytes: AtomicUsize,
+}
+
+impl<T> Budget<T> {
+    /// A budget that doesn't limit anything and doesn't measure the values.
+    #[inline]
+    pub fn unlimited() -> Self {
+        Self {
+            measure: None,
+            limit: usize::MAX,
+            bytes: AtomicUsize::new(0),
+        }
+    }
+
+    /// A budget that allows at most `limit` bytes to be buffered.
+    #[inline]
+    pub fn limited(limit: usize) -> Self
+    where
+        T: Measure,
+    {
+        Self {
+            measure: Some(T::measure),
+            limit,
+            bytes: AtomicUsize::new(0),
+        }
+    }
+
+    /// Does this budget measure the values.
+    #[inline]
+    pub const fn is_limited(&self) -> bool {
+        self.measure.is_some()
+    }
+
+    /// Measure `value`, returns 0 if the budget is unlimited.
+    #[inline]
+    pub fn measure(&self, value: &T) -> usize {
+        self.measure.map_or(0, |measure| measure(value))
+    }
+
+    /// The amount of bytes currently buffered.
+    #[inline]
+    pub fn bytes(&self) -> usize {
+        self.bytes.load(Relaxed)
+    }
+
+    /// Overwrite the amount of bytes currently buffered.
+    ///
+    /// This is used when recovering a poisoned ring, where the values that were lost are still
+    /// counted.
+    #[inline]
+    pub fn set_bytes(&self, bytes: usize) {
+        self.bytes.store(bytes, Relaxed);
+    }
+
+    /// Reserve `bytes` in the budget.
+    ///
+    /// # Errors
+    /// Returns [`Error::OverBudget`] if reserving `bytes` would exceed the limit.
+    #[inline]
+    pub fn reserve(&self, bytes: usize) -> Result<(), Error> {
+        if !self.is_limited() {
+            return Ok(());
+        }
+        self.bytes
+            .fetch_update(Relaxed, Relaxed, |current| {
+                current
+                    .checked_add(bytes)
+                    .filter(|&total| total <= self.limit)
+            })
+            .map(|_old| ())
+            .map_err(|_old| {
+                cold_path();
+                Error::OverBudget
+            })
+    }
+
+    /// Reserve the bytes of the longest prefix of values that fits in the budget.
+    ///
+    /// `sizes` returns the sizes of the values in order, it's called again if another thread
+    /// changed the budget in the meantime. Returns the amount of values and their bytes that
+    /// were reserved.
+    #[inline]
+    pub fn reserve_prefix<F, I>(&self, sizes: F) -> (usize, usize)
+    where
+        F: Fn() -> I,
+        I: Iterator<Item = usize>,
+    {
+        let mut reserved = (0, 0);
+        // Returning `None` for an empty prefix doesn't store anything, which is what we want
+        let _ = self.bytes.fetch_update(Relaxed, Relaxed, |current| {
+            let room = self.limit.saturating_sub(current);
+            let mut prefix: (usize, usize) = (0, 0);
+            for size in sizes() {
+                match prefix.1.checked_add(size).filter(|&bytes| bytes <= room) {
+                    Some(bytes) => prefix = (prefix.0 + 1, bytes),
+                    None => break,
+                }
+            }
+            reserved = prefix;
+            (prefix.1 != 0).then(|| current + prefix.1)
+        });
+        reserved
+    }
+
+    /// Are `bytes` more than the limit, so they don't even fit in an empty budget.
+    #[inline]
+    pub const fn exceeds_limit(&self, bytes: usize) -> bool {
+        self.is_limited() && bytes > self.limit
+    }
+
+    /// Check that the limit has not been reached yet.
+    ///
+    /// This is used to fail early when the size of the values isn't known yet.
+    ///
+    /// # Errors
+    /// Returns [`Error::OverBudget`] if the limit has been reached.
+    #[inline]
+    pub fn check(&self) -> Result<(), Error> {
+        if self.is_limited() && self.bytes() >= self.limit {
+            cold_path();
+            Err(Error::OverBudget)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Return `bytes` to the budget.
+    #[inline]
+    pub fn release(&self, bytes: usize) {
+        if bytes != 0 {
+            self.bytes.fetch_sub(bytes, Relaxed);
+        }
+    }
+}
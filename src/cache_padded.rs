@@ -158,6 +158,55 @@ pub struct CachePadded<T> {
     value: T,
 }
 
+/// The cache line length `CachePadded` aligns to on this architecture, see [`CachePadded`].
+#[cfg(any(
+    target_arch = "x86_64",
+    target_arch = "aarch64",
+    target_arch = "arm64ec",
+    target_arch = "powerpc64",
+))]
+const CACHE_LINE: usize = 128;
+/// The cache line length `CachePadded` aligns to on this architecture, see [`CachePadded`].
+#[cfg(any(
+    target_arch = "arm",
+    target_arch = "mips",
+    target_arch = "mips32r6",
+    target_arch = "mips64",
+    target_arch = "mips64r6",
+    target_arch = "sparc",
+    target_arch = "hexagon",
+))]
+const CACHE_LINE: usize = 32;
+/// The cache line length `CachePadded` aligns to on this architecture, see [`CachePadded`].
+#[cfg(target_arch = "m68k")]
+const CACHE_LINE: usize = 16;
+/// The cache line length `CachePadded` aligns to on this architecture, see [`CachePadded`].
+#[cfg(target_arch = "s390x")]
+const CACHE_LINE: usize = 256;
+/// The cache line length `CachePadded` aligns to on this architecture, see [`CachePadded`].
+#[cfg(not(any(
+    target_arch = "x86_64",
+    target_arch = "aarch64",
+    target_arch = "arm64ec",
+    target_arch = "powerpc64",
+    target_arch = "arm",
+    target_arch = "mips",
+    target_arch = "mips32r6",
+    target_arch = "mips64",
+    target_arch = "mips64r6",
+    target_arch = "sparc",
+    target_arch = "hexagon",
+    target_arch = "m68k",
+    target_arch = "s390x",
+)))]
+const CACHE_LINE: usize = 64;
+
+// Catch the `repr(align)` attributes and the list above getting out of sync.
+const _: () = assert!(
+    align_of::<CachePadded<u8>>() == CACHE_LINE,
+    "CachePadded is not aligned to the cache line of this architecture"
+);
+
 // SAFETY: This type is just an align wrapper, no safety requirements.
 unsafe impl<T: Send> Send for CachePadded<T> {}
 // SAFETY: This type is just an align wrapper, no safety requirements.
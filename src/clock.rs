@@ -0,0 +1,28 @@
+//! Time sources for waiting with a deadline.
+
+/// A source of the current time, used to wait with a deadline.
+///
+/// With the `std` feature [`StdClock`] implements this using [`std::time::Instant`]. On other
+/// targets this can be implemented using a hardware timer.
+pub trait Clock {
+    /// A point in time of this clock.
+    type Instant: PartialOrd;
+
+    /// Get the current time.
+    fn now(&self) -> Self::Instant;
+}
+
+/// A [`Clock`] using [`std::time::Instant`].
+#[cfg(feature = "std")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StdClock;
+
+#[cfg(feature = "std")]
+impl Clock for StdClock {
+    type Instant = std::time::Instant;
+
+    #[inline]
+    fn now(&self) -> Self::Instant {
+        std::time::Instant::now()
+    }
+}
@@ -1,12 +1,25 @@
 //! The user facing consumer implementation.
 
+#[cfg(not(feature = "_safe_maybeuninit"))]
+use crate::ring::peek::PeekGuard;
+#[cfg(feature = "debug")]
+use crate::ring::stats::RingStats;
 use crate::{
-    Error,
-    modes::Mode,
-    ring::{Ring, active::Last, recv_values::RecvValues},
-    std::hint::cold_path,
+    Backoff, Clock, Error,
+    modes::{Mode, Multi, Rewind, Single},
+    producer::Sender,
+    ring::{
+        Ring,
+        active::Last,
+        peek::{Ack, Peek},
+        recv_values::RecvValues,
+    },
+    std::{hint::cold_path, sync::atomic::Ordering::SeqCst, thread::panicking},
+};
+use core::{
+    mem::ManuallyDrop,
+    sync::atomic::{AtomicBool, Ordering::Relaxed},
 };
-use std::thread::panicking;
 
 /// The receiving-half of the channel.
 ///
@@ -15,6 +28,28 @@ use std::thread::panicking;
 /// - `T`: the type being sent over the channel.
 /// - `P`: the synchronisation mode of the sender, see [`Mode`].
 /// - `C`: the synchronisation mode of the receiver, see [`Mode`].
+///
+/// # Cloning
+/// A receiver can be cloned if the consumer mode `C` supports multiple threads, independent of
+/// the producer mode `P`:
+/// ```
+/// let (_sender, receiver) = ringbeam::spmc::bounded::<4, u8>();
+/// let _receiver2 = receiver.clone();
+/// ```
+///
+/// A receiver of a single consumer channel can't be cloned:
+/// ```compile_fail,E0599
+/// let (_sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+/// let _receiver2 = receiver.clone();
+/// ```
+///
+/// # Threads
+/// A receiver can only be sent to another thread if `T` is [`Send`], as the values are moved between
+/// threads through the channel. A channel of values that aren't [`Send`] stays on one thread:
+/// ```compile_fail,E0277
+/// let (_sender, receiver) = ringbeam::spsc::bounded::<4, std::rc::Rc<u8>>();
+/// std::thread::spawn(move || drop(receiver));
+/// ```
 pub struct Receiver<const N: usize, T, P, C>
 where
     P: Mode,
@@ -93,6 +128,183 @@ where
         }
     }
 
+    /// Get one item from the channel, waiting while the channel is empty.
+    ///
+    /// This waits using the default [`Backoff`], see [`recv_with_backoff`](Self::recv_with_backoff)
+    /// to configure it.
+    ///
+    /// # Errors
+    /// Returns [`Error::Closed`] once the channel is closed and empty, and [`Error::Poisoned`]
+    /// if the ring is poisoned. It can also return [`Error::TooManyConsumers`] if there are
+    /// already `u16::MAX - 1` instances of `Receiver`s and [`RecvValues`].
+    #[inline]
+    pub fn recv(&self) -> Result<T, Error> {
+        self.recv_with_backoff(&mut Backoff::new())
+    }
+
+    /// Get one item from the channel, waiting with `backoff` while the channel is empty.
+    ///
+    /// # Errors
+    /// Returns [`Error::Closed`] once the channel is closed and empty, and [`Error::Poisoned`]
+    /// if the ring is poisoned. It can also return [`Error::TooManyConsumers`] if there are
+    /// already `u16::MAX - 1` instances of `Receiver`s and [`RecvValues`].
+    #[inline]
+    pub fn recv_with_backoff(&self, backoff: &mut Backoff) -> Result<T, Error> {
+        loop {
+            match self.try_recv() {
+                Err(Error::Empty) => backoff.snooze(),
+                result => return result,
+            }
+        }
+    }
+
+    /// Get one item from the channel, waiting while the channel is empty for at most `timeout`.
+    ///
+    /// This waits using the default [`Backoff`] and [`StdClock`](crate::StdClock), see
+    /// [`recv_deadline`](Self::recv_deadline) to configure those.
+    ///
+    /// # Errors
+    /// Returns [`Error::Timeout`] if the channel is still empty after `timeout`. It returns
+    /// [`Error::Closed`] once the channel is closed and empty, and [`Error::Poisoned`] if the ring
+    /// is poisoned, without waiting for the timeout. It can also return [`Error::TooManyConsumers`]
+    /// if there are already `u16::MAX - 1` instances of `Receiver`s and [`RecvValues`].
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn recv_timeout(&self, timeout: core::time::Duration) -> Result<T, Error> {
+        let clock = crate::StdClock;
+        // A timeout that doesn't fit in an `Instant` will never pass
+        let Some(deadline) = clock.now().checked_add(timeout) else {
+            cold_path();
+            return self.recv();
+        };
+        self.recv_deadline(&clock, &deadline, &mut Backoff::new())
+    }
+
+    /// Get one item from the channel, waiting with `backoff` while the channel is empty until
+    /// `clock` reaches `deadline`.
+    ///
+    /// # Errors
+    /// Returns [`Error::Timeout`] if the channel is still empty at `deadline`. It returns
+    /// [`Error::Closed`] once the channel is closed and empty, and [`Error::Poisoned`] if the ring
+    /// is poisoned, without waiting for the deadline. It can also return [`Error::TooManyConsumers`]
+    /// if there are already `u16::MAX - 1` instances of `Receiver`s and [`RecvValues`].
+    #[inline]
+    pub fn recv_deadline<K>(
+        &self,
+        clock: &K,
+        deadline: &K::Instant,
+        backoff: &mut Backoff,
+    ) -> Result<T, Error>
+    where
+        K: Clock,
+    {
+        loop {
+            match self.try_recv() {
+                Err(Error::Empty) => {
+                    if clock.now() >= *deadline {
+                        cold_path();
+                        return Err(Error::Timeout);
+                    }
+                    backoff.snooze();
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Get one item from the channel, waiting while the channel is empty until `cancel` is set.
+    ///
+    /// This is still a busy-wait using the default [`Backoff`], the thread is never parked. The
+    /// backoff yields to the scheduler between checks once it stops spinning, so `cancel` is
+    /// checked at least once per yield. Setting `cancel` doesn't wake the thread, but it notices
+    /// the flag the next time it checks.
+    ///
+    /// ```
+    /// # use core::sync::atomic::AtomicBool;
+    /// # use ringbeam::Error;
+    /// let (_sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+    /// let cancel = AtomicBool::new(true);
+    /// assert_eq!(receiver.recv_cancellable(&cancel), Err(Error::Cancelled));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`Error::Cancelled`] if `cancel` is set while the channel is empty. It returns
+    /// [`Error::Closed`] once the channel is closed and empty, and [`Error::Poisoned`] if the ring
+    /// is poisoned, regardless of `cancel`. It can also return [`Error::TooManyConsumers`] if there
+    /// are already `u16::MAX - 1` instances of `Receiver`s and [`RecvValues`].
+    #[inline]
+    pub fn recv_cancellable(&self, cancel: &AtomicBool) -> Result<T, Error> {
+        let mut backoff = Backoff::new();
+        loop {
+            match self.try_recv() {
+                Err(Error::Empty) => {
+                    if cancel.load(Relaxed) {
+                        cold_path();
+                        return Err(Error::Cancelled);
+                    }
+                    backoff.snooze();
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Try to fill `out` with items from the channel.
+    ///
+    /// This copies the items directly out of the channel, without going through [`RecvValues`].
+    /// Less than `out.len()` items are copied if the channel doesn't have enough items.
+    ///
+    /// # Returns
+    /// The amount of items copied into the start of `out`.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful.
+    #[inline]
+    pub fn try_recv_slice(&self, out: &mut [T]) -> Result<usize, Error>
+    where
+        T: Copy,
+    {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.try_dequeue_slices(&mut [out])
+    }
+
+    /// Try to fill the slices of `bufs` in order with items from the channel, like a vectored read.
+    ///
+    /// The items are taken as one contiguous run, so no items of other receivers are in between.
+    /// Where the ring wraps around is independent of where one slice ends and the next starts.
+    /// Fewer items are copied if the channel doesn't have enough items, filling the first slices
+    /// first.
+    ///
+    /// ```
+    /// let (sender, receiver) = ringbeam::spsc::bounded::<8, u8>();
+    /// sender.try_send_slice(b"hello").unwrap();
+    /// let (mut head, mut body) = ([0; 2], [0; 8]);
+    /// assert_eq!(receiver.try_recv_vectored(&mut [&mut head, &mut body]), Ok(5));
+    /// assert_eq!((&head, &body[..3]), (b"he", &b"llo"[..]));
+    /// ```
+    ///
+    /// # Returns
+    /// The total amount of items copied into `bufs`.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful.
+    #[inline]
+    pub fn try_recv_vectored(&self, bufs: &mut [&mut [T]]) -> Result<usize, Error>
+    where
+        T: Copy,
+    {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.try_dequeue_slices(bufs)
+    }
+
     /// Try to get `n` items from the channel or none at all.
     ///
     /// To get at most `n` items, see [`try_recv_burst`](Self::try_recv_burst).
@@ -116,7 +328,7 @@ where
         //         No mutable aliasing in the ring except for inside the UnsafeCell.
         let ring = unsafe { &*self.ring };
 
-        ring.try_dequeue::<true>(n)
+        ring.try_dequeue::<true, false>(n)
     }
 
     /// Try to get at most `n` items from the channel.
@@ -138,74 +350,1144 @@ where
         //         No mutable aliasing in the ring except for inside the UnsafeCell.
         let ring = unsafe { &*self.ring };
 
-        ring.try_dequeue::<false>(n)
+        ring.try_dequeue::<false, false>(n)
     }
-}
 
-impl<const N: usize, T, P, C> Clone for Receiver<N, T, P, C>
-where
-    P: Mode,
-    C: Mode + Sync,
-{
+    /// Try to get at most `max` items from the channel, without wrapping around the end of the ring.
+    ///
+    /// The items are one run in the ring, so [`RecvValues::as_slices`] returns them all in the
+    /// first slice and the second slice is empty. This can return fewer items than are available,
+    /// the items at the start of the ring are left for the next call.
+    ///
+    /// ```
+    /// let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+    /// sender.try_send_slice(&[0, 0, 0]).unwrap();
+    /// assert_eq!(receiver.try_recv_burst(3).unwrap().count(), 3);
+    /// sender.try_send_slice(&[1, 2]).unwrap();
+    /// assert_eq!(receiver.try_recv_contiguous(8).unwrap().collect::<Vec<_>>(), [1]);
+    /// assert_eq!(receiver.try_recv_contiguous(8).unwrap().collect::<Vec<_>>(), [2]);
+    /// ```
+    ///
+    /// # Returns
+    /// An iterator over the items. This iterator is allowed to outlive the receiver.
+    /// Dropping the iterator while it still has items, will also drop those items.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful. It can also
+    /// return [`Error::TooManyConsumers`] if there are already `u16::MAX - 1` instances of `Receiver`s
+    /// and [`RecvValues`].
     #[inline]
-    fn clone(&self) -> Self {
-        // SAFETY: because `self` is valid, `ring` is initialized and aligned.
-        unsafe { Self::new(self.ring).expect("Failed to clone consumer!") }
+    pub fn try_recv_contiguous(&self, max: usize) -> Result<RecvValues<N, T, P, C>, Error> {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.try_dequeue::<false, true>(max)
     }
-}
 
-impl<const N: usize, T, P, C> Drop for Receiver<N, T, P, C>
-where
-    P: Mode,
-    C: Mode,
-{
-    #[expect(
-        clippy::missing_inline_in_public_items,
-        reason = "This function is too large too inline"
-    )]
-    fn drop(&mut self) {
-        if panicking() {
+    /// Try to get at most `max` items from the channel, calling `f` for every item.
+    ///
+    /// This is the same as calling `f` for every item of [`try_recv_burst`](Self::try_recv_burst),
+    /// but it takes the items in at most two runs, one up to the end of the ring and one from the
+    /// start, instead of checking the claim for every item.
+    ///
+    /// If `f` panics, the items that are left are dropped and the items are given back to the
+    /// channel, which stays usable.
+    ///
+    /// ```
+    /// let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+    /// sender.try_send_slice(&[1, 2, 3]).unwrap();
+    /// let mut sum = 0;
+    /// assert_eq!(receiver.try_recv_each(8, |v| sum += v), Ok(3));
+    /// assert_eq!(sum, 6);
+    /// ```
+    ///
+    /// # Returns
+    /// The amount of items passed to `f`.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful. It can also
+    /// return [`Error::TooManyConsumers`] if there are already `u16::MAX - 1` instances of `Receiver`s
+    /// and [`RecvValues`].
+    #[inline]
+    pub fn try_recv_each<F>(&self, max: usize, f: F) -> Result<usize, Error>
+    where
+        F: FnMut(T),
+    {
+        let values = self.try_recv_burst(max)?;
+        let n = values.len();
+        values.for_each(f);
+        Ok(n)
+    }
+
+    /// Get at most `max` items from the channel, waiting at most `timeout` for a full batch.
+    ///
+    /// This returns as soon as there are `max` items in the channel. Otherwise it waits until
+    /// `timeout` passes and returns the items that arrived in the meantime. This waits using the
+    /// default [`Backoff`] and [`StdClock`](crate::StdClock), see [`recv_bulk_deadline`](Self::recv_bulk_deadline)
+    /// to configure those.
+    ///
+    /// # Returns
+    /// An iterator over the items, see [`try_recv_burst`](Self::try_recv_burst).
+    ///
+    /// # Errors
+    /// Returns [`Error::Timeout`] if the channel is still empty after `timeout`. It returns
+    /// [`Error::Closed`] once the channel is closed and empty, and [`Error::Poisoned`] if the ring
+    /// is poisoned. If the channel is closed while waiting, the items that are left are returned
+    /// without waiting for the timeout. It can also return [`Error::TooManyConsumers`] if there
+    /// are already `u16::MAX - 1` instances of `Receiver`s and [`RecvValues`].
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn recv_bulk_timeout(
+        &self,
+        max: usize,
+        timeout: core::time::Duration,
+    ) -> Result<RecvValues<N, T, P, C>, Error> {
+        let clock = crate::StdClock;
+        // A timeout that doesn't fit in an `Instant` will never pass
+        let Some(deadline) = clock.now().checked_add(timeout) else {
             cold_path();
-            // SAFETY: Ring is valid before we call unregister_consumer
-            unsafe {
-                (*self.ring).poison();
-            }
-        } else {
-            // SAFETY: Ring is valid before we call unregister_consumer
-            match unsafe {
-                (*self.ring)
-                    .active()
-                    .unregister_consumer()
-                    .expect("Ring is poisoned!")
-            } {
-                Last::InCategory => {
-                    // SAFETY: Even if another thread starts the ring cleanup, the cleanup will
-                    // wait for the tail being marked.
-                    unsafe {
-                        (*self.ring).mark_cons_finished();
+            return self.recv_bulk_until(max, &mut Backoff::new(), || false);
+        };
+        self.recv_bulk_deadline(max, &clock, &deadline, &mut Backoff::new())
+    }
+
+    /// Get at most `max` items from the channel, waiting with `backoff` for a full batch until
+    /// `clock` reaches `deadline`.
+    ///
+    /// This returns as soon as there are `max` items in the channel, or at the deadline with the
+    /// items that arrived in the meantime. A `max` larger than the [`capacity`](Self::capacity)
+    /// waits for a full channel instead.
+    ///
+    /// # Returns
+    /// An iterator over the items, see [`try_recv_burst`](Self::try_recv_burst).
+    ///
+    /// # Errors
+    /// Returns [`Error::Timeout`] if the channel is still empty at `deadline`. It returns
+    /// [`Error::Closed`] once the channel is closed and empty, and [`Error::Poisoned`] if the ring
+    /// is poisoned. If the channel is closed while waiting, the items that are left are returned
+    /// without waiting for the deadline. It can also return [`Error::TooManyConsumers`] if there
+    /// are already `u16::MAX - 1` instances of `Receiver`s and [`RecvValues`].
+    #[inline]
+    pub fn recv_bulk_deadline<K>(
+        &self,
+        max: usize,
+        clock: &K,
+        deadline: &K::Instant,
+        backoff: &mut Backoff,
+    ) -> Result<RecvValues<N, T, P, C>, Error>
+    where
+        K: Clock,
+    {
+        self.recv_bulk_until(max, backoff, || clock.now() >= *deadline)
+    }
+
+    /// Get at most `max` items from the channel, waiting with `backoff` for a full batch until
+    /// `passed` returns `true`.
+    ///
+    /// See [`recv_bulk_deadline`](Self::recv_bulk_deadline).
+    ///
+    /// # Errors
+    /// The same as [`recv_bulk_deadline`](Self::recv_bulk_deadline).
+    #[inline]
+    fn recv_bulk_until<F>(
+        &self,
+        max: usize,
+        backoff: &mut Backoff,
+        mut passed: F,
+    ) -> Result<RecvValues<N, T, P, C>, Error>
+    where
+        F: FnMut() -> bool,
+    {
+        let max = max.min(self.capacity());
+        loop {
+            match self.try_recv_bulk(max) {
+                Err(Error::Empty | Error::NotEnoughItems) => {
+                    if passed() {
+                        cold_path();
+                        return match self.try_recv_burst(max) {
+                            Err(Error::Empty) => Err(Error::Timeout),
+                            result => result,
+                        };
                     }
+                    backoff.snooze();
                 }
-                Last::InRing => {
-                    // SAFETY: `Last::InRing` guarantees that we're the last
-                    unsafe { Ring::cleanup(self.ring) }
+                Err(Error::NotEnoughItemsAndClosed) => {
+                    cold_path();
+                    return self.try_recv_burst(max);
                 }
-                Last::NotLast => {}
+                result => return result,
             }
         }
     }
-}
 
-// SAFETY: The ring is designed to be accessed from different threads.
-unsafe impl<const N: usize, T, P, C> Send for Receiver<N, T, P, C>
-where
-    P: Mode,
-    C: Mode,
-{
-}
+    /// Try to move `n` items from the channel to the end of `out`, or none at all.
+    ///
+    /// Unlike [`try_recv_bulk`](Self::try_recv_bulk), this doesn't allocate when `out` already
+    /// has room for the items, so one buffer can be reused for every batch. To move at most `n`
+    /// items, see [`try_recv_burst_into`](Self::try_recv_burst_into).
+    ///
+    /// # Returns
+    /// The amount of items moved into `out`.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful.
+    ///
+    /// It can also return [`Error::NotEnoughItems`], which can also be successful on
+    /// a retry. It can also return [`Error::NotEnoughItemsAndClosed`] indicating that this will
+    /// keep failing with `try_recv_bulk_into` as there won't be new items.
+    #[inline]
+    pub fn try_recv_bulk_into(&self, n: usize, out: &mut Vec<T>) -> Result<usize, Error> {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
 
-// SAFETY: Mutable access to the consumer head is guarded by atomics, but only for those that implement Sync.
-unsafe impl<const N: usize, T, P, C> Sync for Receiver<N, T, P, C>
+        ring.try_dequeue_into::<true>(n, out)
+    }
+
+    /// Try to move at most `n` items from the channel to the end of `out`.
+    ///
+    /// To move exactly `n` items or none at all, see [`try_recv_bulk_into`](Self::try_recv_bulk_into).
+    ///
+    /// # Returns
+    /// The amount of items moved into `out`.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful.
+    #[inline]
+    pub fn try_recv_burst_into(&self, n: usize, out: &mut Vec<T>) -> Result<usize, Error> {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.try_dequeue_into::<false>(n, out)
+    }
+
+    /// Try to move exactly `n` items from the channel into a newly allocated boxed slice.
+    ///
+    /// The slice is owned and has no lifetime tied to the channel, so a batch can be handed to
+    /// another thread. It's allocated before the items are claimed, so every call allocates even
+    /// when the channel doesn't have enough items. To avoid allocating, see
+    /// [`try_recv_bulk`](Self::try_recv_bulk) or [`try_recv_bulk_into`](Self::try_recv_bulk_into).
+    ///
+    /// ```
+    /// let (sender, receiver) = ringbeam::spsc::bounded::<8, u8>();
+    /// sender.try_send_slice(&[1, 2, 3]).unwrap();
+    /// let batch = receiver.try_recv_boxed(2).unwrap();
+    /// std::thread::spawn(move || assert_eq!(*batch, [1, 2])).join().unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful.
+    ///
+    /// It can also return [`Error::NotEnoughItems`], which can also be successful on
+    /// a retry. It can also return [`Error::NotEnoughItemsAndClosed`] indicating that this will
+    /// keep failing with `try_recv_boxed` as there won't be new items.
+    #[inline]
+    pub fn try_recv_boxed(&self, n: usize) -> Result<Box<[T]>, Error> {
+        // Allocate exactly what's needed, so converting to a boxed slice doesn't reallocate
+        let mut out = Vec::with_capacity(n.min(self.capacity()));
+        self.try_recv_bulk_into(n, &mut out)?;
+        Ok(out.into_boxed_slice())
+    }
+
+    /// Try to move exactly `K` items from the channel into an array.
+    ///
+    /// This is meant for consumers that process the items in fixed-size chunks, like a SIMD
+    /// kernel. The items are moved directly into the array, without an iterator or allocation.
+    /// `K` must be smaller than `N`, otherwise this fails to compile.
+    ///
+    /// # Panics
+    /// Will panic if the channel has a size chosen at runtime and `K` isn't smaller than it.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful.
+    ///
+    /// It can also return [`Error::NotEnoughItems`], which can also be successful on
+    /// a retry. It can also return [`Error::NotEnoughItemsAndClosed`] indicating that this will
+    /// keep failing with `try_recv_array` as there won't be new items.
+    #[inline]
+    pub fn try_recv_array<const K: usize>(&self) -> Result<[T; K], Error> {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.try_dequeue_array::<K>()
+    }
+
+    /// Get all items that are currently in the channel.
+    ///
+    /// The amount of items is determined while claiming them, so it's exactly what was in the
+    /// channel at that moment. Collect the iterator to get the items as a [`Vec`]:
+    /// ```
+    /// let (sender, receiver) = ringbeam::spsc::bounded::<8, u8>();
+    /// sender.try_send_bulk(&mut [1, 2, 3].into_iter()).unwrap();
+    /// let items: Vec<u8> = receiver.drain().unwrap().collect();
+    /// assert_eq!(items, [1, 2, 3]);
+    /// ```
+    ///
+    /// # Returns
+    /// An iterator over the items. This iterator is allowed to outlive the receiver.
+    /// Dropping the iterator while it still has items, will also drop those items.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful. It can also
+    /// return [`Error::TooManyConsumers`] if there are already `u16::MAX - 1` instances of `Receiver`s
+    /// and [`RecvValues`].
+    #[inline]
+    pub fn drain(&self) -> Result<RecvValues<N, T, P, C>, Error> {
+        self.try_recv_burst(self.capacity())
+    }
+
+    /// Move all items that are currently in the channel into a new [`Vec`].
+    ///
+    /// Like [`drain`](Self::drain), this is a snapshot: items sent while draining can be missing.
+    /// An empty channel gives an empty `Vec`.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`] once the channel is closed and empty, and [`Error::Poisoned`]
+    /// if the ring is poisoned.
+    #[inline]
+    pub fn drain_to_vec(&self) -> Result<Vec<T>, Error> {
+        let mut out = Vec::new();
+        match self.try_recv_burst_into(self.capacity(), &mut out) {
+            Ok(_) | Err(Error::Empty) => Ok(out),
+            Err(error) => {
+                cold_path();
+                Err(error)
+            }
+        }
+    }
+
+    /// Close the channel for the senders and move all items that are left into a new [`Vec`].
+    ///
+    /// This closes the channel like [`Sender::close`] first, so no new items can arrive while
+    /// draining. Unlike [`drain_to_vec`](Self::drain_to_vec) the `Vec` therefore has every item
+    /// that was sent before closing, except the items of senders that were still writing them
+    /// at that moment. Those may or may not be included. Other receivers of the channel get
+    /// [`Error::Closed`] afterwards, but can still take items that this receiver didn't.
+    ///
+    /// ```
+    /// let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+    /// sender.try_send_slice(&[1, 2, 3]).unwrap();
+    /// assert_eq!(receiver.close_and_drain(), [1, 2, 3]);
+    /// assert_eq!(sender.try_send(4), Err(ringbeam::Error::Closed));
+    /// ```
+    ///
+    /// If the ring is poisoned, the items that were received before are returned.
+    #[must_use]
+    #[inline]
+    pub fn close_and_drain(self) -> Vec<T> {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.close_prod();
+        let mut out = Vec::new();
+        // With the senders closed, an empty channel returns `Error::Closed`
+        while self.try_recv_burst_into(self.capacity(), &mut out).is_ok() {}
+        out
+    }
+
+    /// A blocking iterator over the items in the channel.
+    ///
+    /// Every call to [`next`](Iterator::next) waits for an item with [`recv`](Self::recv). The
+    /// iterator ends once the channel is closed and all items are received, or when the ring is
+    /// poisoned. Like [`std::sync::mpsc::Receiver::iter`]:
+    /// ```
+    /// let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+    /// sender.try_send_slice(&[1, 2, 3]).unwrap();
+    /// drop(sender);
+    /// assert_eq!(receiver.iter().collect::<Vec<_>>(), [1, 2, 3]);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn iter(&self) -> Iter<'_, N, T, P, C> {
+        Iter { receiver: self }
+    }
+
+    /// Look at the next item in the channel without consuming it.
+    ///
+    /// The item stays in the channel until it's taken with [`Peek::take`]. This is only
+    /// available for consumer modes that implement [`Rewind`], those are [`Single`](crate::custom::modes::Single)
+    /// and [`HeadTailSync`](crate::custom::modes::HeadTailSync). For `HeadTailSync` other
+    /// receivers can't receive anything while the [`Peek`] exists.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful.
+    #[inline]
+    pub fn peek(&mut self) -> Result<Peek<'_, N, T, P, C>, Error>
+    where
+        C: Rewind,
+    {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.try_peek()
+    }
+
+    /// Receive the next item, but only remove it from the channel once it's acknowledged.
+    ///
+    /// The item is removed with [`Ack::ack`], after it's handled. If the [`Ack`] is dropped
+    /// instead, the item stays in the channel and is received again. This includes a panic while
+    /// handling the item, so the item is delivered again instead of lost. Like
+    /// [`peek`](Self::peek), this is only available for consumer modes that implement [`Rewind`].
+    ///
+    /// ```
+    /// let (sender, mut receiver) = ringbeam::spsc::bounded::<4, u8>();
+    /// sender.try_send(1).unwrap();
+    /// drop(receiver.recv_ack().unwrap());
+    /// let ack = receiver.recv_ack().unwrap();
+    /// assert_eq!(*ack, 1);
+    /// assert_eq!(ack.ack(), 1);
+    /// assert_eq!(receiver.try_recv(), Err(ringbeam::Error::Empty));
+    /// ```
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful.
+    #[inline]
+    pub fn recv_ack(&mut self) -> Result<Ack<'_, N, T, P, C>, Error>
+    where
+        C: Rewind,
+    {
+        self.peek().map(Ack::new)
+    }
+
+    /// Receive the next item, but only if `pred` returns `true` for it.
+    ///
+    /// If `pred` returns `false` the item stays in the channel and `Ok(None)` is returned. The
+    /// item is only borrowed for the duration of `pred`, and is also left in the channel if `pred`
+    /// panics. Like [`peek`](Self::peek), this is only available for consumer modes that implement
+    /// [`Rewind`]. While `pred` runs no other receiver can receive from the channel, so it should
+    /// not wait on another receiver.
+    ///
+    /// ```
+    /// let (sender, mut receiver) = ringbeam::spsc::bounded::<4, u8>();
+    /// sender.try_send_slice(&[1, 5]).unwrap();
+    /// assert_eq!(receiver.try_recv_if(|v| *v < 3), Ok(Some(1)));
+    /// assert_eq!(receiver.try_recv_if(|v| *v < 3), Ok(None));
+    /// assert_eq!(receiver.try_recv(), Ok(5));
+    /// ```
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful.
+    #[inline]
+    pub fn try_recv_if<F>(&mut self, pred: F) -> Result<Option<T>, Error>
+    where
+        C: Rewind,
+        F: FnOnce(&T) -> bool,
+    {
+        let peek = self.peek()?;
+        if pred(&peek) {
+            Ok(Some(peek.take()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Look at up to `n` items in the channel and consume only some of them.
+    ///
+    /// The items are looked at with [`PeekGuard::as_slices`], after which the first `k` can be
+    /// consumed with [`PeekGuard::consume`]. The other items stay in the channel. This is useful
+    /// for parsers that need to look ahead before they know how many items they need.
+    ///
+    /// Like [`peek`](Self::peek), this is only available for consumer modes that implement
+    /// [`Rewind`], those are [`Single`](crate::custom::modes::Single) and [`HeadTailSync`](crate::custom::modes::HeadTailSync).
+    /// Other modes can't give back items without stranding them behind the head of another
+    /// consumer. For `HeadTailSync` other receivers can't receive anything while the
+    /// [`PeekGuard`] exists.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful. If `n` is
+    /// zero an empty [`PeekGuard`] is returned.
+    #[cfg(not(feature = "_safe_maybeuninit"))]
+    #[inline]
+    pub fn peek_bulk(&mut self, n: usize) -> Result<PeekGuard<'_, N, T, P, C>, Error>
+    where
+        C: Rewind,
+    {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.try_peek_bulk(n)
+    }
+
+    /// Create a weak handle to the channel, which doesn't keep it open.
+    ///
+    /// The channel is closed once every [`Receiver`] is dropped, even if there are still weak
+    /// handles. A weak handle can be upgraded to a receiver again as long as the channel is open,
+    /// see [`ReceiverWeak::upgrade`]. This is only available for consumer modes that can be cloned.
+    ///
+    /// # Panics
+    /// Will panic if there are already `u16::MAX` weak handles.
+    #[must_use]
+    #[inline]
+    pub fn downgrade(&self) -> ReceiverWeak<N, T, P, C>
+    where
+        C: Sync,
+    {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.active().register_weak();
+        ReceiverWeak { ring: self.ring }
+    }
+
+    /// Do `self` and `other` receive from the same channel.
+    ///
+    /// This compares the channels, not the values in them. Receivers are the same channel if one
+    /// is a clone of the other, or if they're created from the same pointer with
+    /// [`from_raw`](Self::from_raw).
+    ///
+    /// ```
+    /// let (_sender, receiver) = ringbeam::mpmc::bounded::<4, u8>();
+    /// let (_other_sender, other) = ringbeam::mpmc::bounded::<4, u8>();
+    /// assert!(receiver.same_channel(&receiver.clone()));
+    /// assert!(!receiver.same_channel(&other));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn same_channel(&self, other: &Self) -> bool {
+        core::ptr::eq(self.ring, other.ring)
+    }
+
+    /// Get a pointer to the channel, to pass it through FFI.
+    ///
+    /// The pointer can be turned into a new receiver with [`from_raw`](Self::from_raw), or into a
+    /// new sender with [`Sender::from_raw`]. It doesn't keep the channel alive, so it's only valid
+    /// for as long as a handle of the channel exists.
+    #[must_use]
+    #[inline]
+    pub const fn as_raw(&self) -> *const () {
+        self.ring.cast()
+    }
+
+    /// Create a new receiver from a pointer returned by [`as_raw`](Self::as_raw).
+    ///
+    /// The new receiver is registered like a clone, and must be dropped like any other receiver.
+    /// This is only available for consumer modes that can be cloned. See [`Sender::from_raw`] for
+    /// an example.
+    ///
+    /// # Safety
+    /// - `ptr` must be returned by `as_raw` of a [`Sender`] or [`Receiver`] with exactly the same
+    ///   `N`, `T`, `P`, and `C`. Any other generics are undefined behaviour, even if the types
+    ///   have the same size.
+    /// - A handle of the channel must exist for the duration of this call, so the channel isn't
+    ///   deallocated. This includes weak handles.
+    /// - `ptr` must be used in the same process it was created in.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`] or [`Error::Poisoned`] when the ring is in that state, which
+    /// includes every receiver being dropped already. It can return [`Error::TooManyConsumers`] if
+    /// there are already `u16::MAX - 1` consumers.
+    #[inline]
+    pub unsafe fn from_raw(ptr: *const ()) -> Result<Self, Error>
+    where
+        C: Sync,
+    {
+        // SAFETY: The caller guarantees `ptr` points to a live ring with the same generics.
+        unsafe { Self::new(ptr.cast()) }
+    }
+
+    /// Close the channel for every receiver, without dropping them.
+    ///
+    /// Afterwards sending and receiving return [`Error::Closed`], for every sender and receiver.
+    /// The values that are still in the channel are dropped together with the channel. Closing
+    /// an already closed channel does nothing.
+    ///
+    /// ```
+    /// let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+    /// sender.try_send(1).unwrap();
+    /// receiver.close();
+    /// assert!(receiver.is_closed());
+    /// assert_eq!(receiver.try_recv(), Err(ringbeam::Error::Closed));
+    /// assert_eq!(sender.try_send(2), Err(ringbeam::Error::Closed));
+    /// ```
+    #[inline]
+    pub fn close(&self) {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.close_cons();
+    }
+
+    /// Is the channel closed.
+    ///
+    /// This is `true` once every [`Sender`](crate::custom::Sender) is dropped, when either side
+    /// closed the channel with [`close`](Self::close) or [`Sender::close`], or when the ring is
+    /// poisoned, and stays `true` from then on (unless the poison is cleared with
+    /// [`try_clear_poison_with`](Self::try_clear_poison_with)). Unless this receiver closed the
+    /// channel, it can still have values that were sent before, so keep receiving until
+    /// [`Error::Closed`]. A `false` is only a snapshot, the last sender can be dropped right after.
+    #[must_use]
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        // See `Sender::is_closed`.
+        ring.active().check_producer().is_err() || ring.is_prod_finished()
+    }
+
+    /// Is the ring poisoned.
+    ///
+    /// The ring is poisoned when a handle panics while it has access to the channel, after which
+    /// every operation returns [`Error::Poisoned`]. This checks for that without trying an
+    /// operation, and is the same for every handle of the channel, like [`Sender::is_poisoned`].
+    /// It stays `true` until the poison is cleared with [`try_clear_poison`](Self::try_clear_poison).
+    #[must_use]
+    #[inline]
+    pub fn is_poisoned(&self) -> bool {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.active().is_poisoned()
+    }
+
+    /// Recover a poisoned channel of which this is the last handle.
+    ///
+    /// The channel is poisoned when a handle panics, after which everything returns
+    /// [`Error::Poisoned`] and the channel is leaked. Once every other [`Sender`] and
+    /// [`Receiver`] is dropped, this clears the poison. No values can be sent anymore, but the
+    /// values that are still in the channel can be received and the channel is freed when this
+    /// receiver is dropped. To keep sending, see [`try_clear_poison_with`](Self::try_clear_poison_with).
+    ///
+    /// Values that were being sent or received while the channel was poisoned are lost. They're
+    /// leaked instead of dropped, as it's unknown which ones were already moved out.
+    ///
+    /// Does nothing if the channel isn't poisoned.
+    ///
+    /// # Errors
+    /// Returns [`Error::Poisoned`] if there are still other handles, including [`RecvValues`].
+    #[inline]
+    pub fn try_clear_poison(&mut self) -> Result<(), Error> {
+        self.clear_poison(false)
+    }
+
+    /// Recover a poisoned channel of which this and `sender` are the last handles.
+    ///
+    /// Like [`try_clear_poison`](Self::try_clear_poison), but the channel stays open for
+    /// `sender` so it can be used like before.
+    ///
+    /// # Errors
+    /// Returns [`Error::Poisoned`] if there are still other handles, including [`RecvValues`].
+    ///
+    /// # Panics
+    /// Will panic if `sender` is of another channel.
+    #[inline]
+    pub fn try_clear_poison_with(&mut self, sender: &mut Sender<N, T, P, C>) -> Result<(), Error> {
+        assert!(
+            core::ptr::eq(sender.ring(), self.ring),
+            "The sender is of another channel"
+        );
+        self.clear_poison(true)
+    }
+
+    /// Clear the poison if this is the only consumer and there are only `has_producer` producers.
+    ///
+    /// # Errors
+    /// Returns [`Error::Poisoned`] if there are other handles.
+    fn clear_poison(&mut self, has_producer: bool) -> Result<(), Error> {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        let active = ring.active().load(SeqCst);
+        if !active.poisoned {
+            return Ok(());
+        }
+        if active.consumers != 1 || active.producers != u16::from(has_producer) {
+            cold_path();
+            return Err(Error::Poisoned);
+        }
+        // SAFETY: There are no other handles, and the mutable references guarantee the remaining
+        //         handles are not in use. The handles that could hold a claim borrow them, so
+        //         there are no claims either. The caller passes a sender if `has_producer`.
+        unsafe {
+            ring.clear_poison(has_producer);
+        }
+        Ok(())
+    }
+
+    /// Get access to the ring.
+    #[cfg(feature = "async")]
+    #[inline]
+    pub(crate) const fn ring(&self) -> &Ring<N, T, P, C> {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        unsafe { &*self.ring }
+    }
+
+    /// The amount of values that fit in the channel, `N - 1`.
+    ///
+    /// The same as [`capacity`](Self::capacity), for when there is no handle, see
+    /// [Capacity](crate#capacity). Fails to compile for a channel with a size chosen at runtime.
+    pub const CAPACITY: usize = {
+        assert!(N != 0, "The capacity of the channel is chosen at runtime");
+        N - 1
+    };
+
+    /// The amount of slots in the ring, `N`.
+    ///
+    /// This is one more than [`CAPACITY`](Self::CAPACITY), as one slot is always kept free.
+    /// Fails to compile for a channel with a size chosen at runtime.
+    pub const RAW_CAPACITY: usize = {
+        assert!(N != 0, "The capacity of the channel is chosen at runtime");
+        N
+    };
+
+    /// The amount of values that fit in the channel.
+    ///
+    /// One slot of the ring is used to tell a full ring apart from an empty one, so this is `N - 1`
+    /// and not `N`, see [Capacity](crate#capacity).
+    #[must_use]
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+        ring.capacity()
+    }
+
+    /// The amount of values currently in the channel.
+    ///
+    /// Values that are still being written by a [`Sender`](crate::custom::Sender) are not
+    /// included, values that are still being read by a [`RecvValues`](crate::custom::RecvValues)
+    /// are. Under concurrent access this is only an approximation, as the channel can change
+    /// before the value is returned.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.len()
+    }
+
+    /// Is the channel empty.
+    ///
+    /// Under concurrent access this is only an approximation, see [`len`](Self::len).
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Is the channel full.
+    ///
+    /// Under concurrent access this is only an approximation, see [`len`](Self::len).
+    #[must_use]
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+
+    /// The total amount of values received from the channel, by all receivers.
+    ///
+    /// Like [`Sender::sent_total`], the counter is updated once per operation and is meant for
+    /// statistics. Under concurrent access it can lag behind the values that are actually
+    /// received.
+    #[cfg(feature = "metrics")]
+    #[must_use]
+    #[inline]
+    pub fn recv_total(&self) -> u64 {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.recv_total()
+    }
+
+    /// Take a snapshot of the positions in the ring and the amount of handles.
+    ///
+    /// This is meant for debugging a channel that seems stuck, like a receiver that keeps getting
+    /// [`Error::Empty`] while the senders think they're sending.
+    #[cfg(feature = "debug")]
+    #[must_use]
+    #[inline]
+    pub fn ring_stats(&self) -> RingStats {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        RingStats::new(ring)
+    }
+
+    /// The amount of bytes currently buffered in the channel.
+    ///
+    /// This is always 0 if the channel doesn't have a byte budget, see
+    /// [`bounded_with_budget`](crate::custom::bounded_with_budget).
+    #[must_use]
+    #[inline]
+    pub fn len_bytes(&self) -> usize {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.budget().bytes()
+    }
+}
+
+impl<const N: usize, T, P> Receiver<N, T, P, Multi>
+where
+    P: Mode,
+{
+    /// Turn the last receiver of a multi consumer channel into a single consumer receiver.
+    ///
+    /// See [`Sender::try_downgrade_to_single`], this is the same for the consumer side.
+    ///
+    /// ```
+    /// let (sender, receiver) = ringbeam::mpmc::bounded::<4, u8>();
+    /// let weak = receiver.downgrade();
+    /// let receiver = receiver.try_downgrade_to_single().unwrap_err();
+    /// drop(weak);
+    /// let receiver = receiver.try_downgrade_to_single().unwrap();
+    /// sender.try_send(1).unwrap();
+    /// assert_eq!(receiver.try_recv(), Ok(1));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `self` if there are other receivers or weak handles, or the channel is poisoned.
+    #[inline]
+    pub fn try_downgrade_to_single(self) -> Result<Receiver<N, T, P, Single>, Self> {
+        const {
+            assert!(
+                Ring::<N, T, P, Multi>::layout().size() == Ring::<N, T, P, Single>::layout().size()
+                    && Ring::<N, T, P, Multi>::layout().align()
+                        == Ring::<N, T, P, Single>::layout().align(),
+                "Multi and Single must have the same layout"
+            );
+        };
+
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        // Other receivers can only be created from this receiver, from a weak handle, or from a raw
+        // pointer. Once the consumers are downgraded none of them can register anymore. The last
+        // receiver that was dropped released its claims, which this acquires.
+        if ring.active().downgrade_consumers().is_err() {
+            return Err(self);
+        }
+
+        let this = ManuallyDrop::new(self);
+        // The registration of this receiver is moved to the new receiver.
+        // SAFETY: `Multi` and `Single` lay out and store the head and tail in the same way, and the
+        //         rest of `Multi` is only read by its own handles. So the ring is valid for either
+        //         mode. Every claim of the other receivers is
+        //         returned, and no thread can access the consumer head anymore except through
+        //         the new receiver.
+        Ok(Receiver {
+            ring: this.ring.cast::<Ring<N, T, P, Single>>(),
+        })
+    }
+}
+
+#[cfg(not(feature = "_safe_maybeuninit"))]
+impl<const N: usize, P, C> Receiver<N, u8, P, C>
+where
+    P: Mode,
+    C: Rewind,
+{
+    /// Find the first `byte` in at most the next `max` bytes of the channel, without receiving them.
+    ///
+    /// This is for framed protocols, like finding the end of a line before receiving it. Only the
+    /// bytes that are in the channel right now are scanned, bytes that arrive during or after the
+    /// scan are not. Like [`peek_bulk`](Self::peek_bulk), this is only available for consumer
+    /// modes that implement [`Rewind`].
+    ///
+    /// ```
+    /// let (sender, mut receiver) = ringbeam::spsc::bounded::<16, u8>();
+    /// sender.try_send_slice(b"GET /\r\nHost").unwrap();
+    /// let end = receiver.peek_until(b'\n', 16).unwrap().unwrap();
+    /// let mut line = [0; 16];
+    /// assert_eq!(receiver.try_recv_slice(&mut line[..=end]), Ok(end + 1));
+    /// assert_eq!(&line[..=end], b"GET /\r\n");
+    /// assert_eq!(receiver.peek_until(b'\n', 16), Ok(None));
+    /// ```
+    ///
+    /// # Returns
+    /// The offset of the first `byte` from the next byte to receive, or `None` if it's not in the
+    /// scanned bytes.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful. If `max` is
+    /// zero nothing is scanned and `None` is returned.
+    #[inline]
+    pub fn peek_until(&mut self, byte: u8, max: usize) -> Result<Option<usize>, Error> {
+        let guard = self.peek_bulk(max)?;
+        let (first, second) = guard.as_slices();
+        Ok(first.iter().position(|&b| b == byte).or_else(|| {
+            second
+                .iter()
+                .position(|&b| b == byte)
+                .map(|i| first.len() + i)
+        }))
+    }
+}
+
+impl<const N: usize, T, P, C> Clone for Receiver<N, T, P, C>
+where
+    P: Mode,
+    C: Mode + Sync,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        // SAFETY: because `self` is valid, `ring` is initialized and aligned.
+        unsafe { Self::new(self.ring).expect("Failed to clone consumer!") }
+    }
+}
+
+impl<const N: usize, T, P, C> core::fmt::Debug for Receiver<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.fmt_handle("Receiver", f)
+    }
+}
+
+impl<const N: usize, T, P, C> Drop for Receiver<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    #[expect(
+        clippy::missing_inline_in_public_items,
+        reason = "This function is too large too inline"
+    )]
+    fn drop(&mut self) {
+        if panicking() {
+            cold_path();
+            // SAFETY: Ring is valid before we call unregister_consumer
+            unsafe {
+                (*self.ring).poison();
+            }
+        }
+        // SAFETY: Ring is valid before we call unregister_consumer
+        match unsafe { (*self.ring).active().unregister_consumer() } {
+            // The ring is leaked, unless the poison is cleared by the last handle.
+            Err(_) => cold_path(),
+            Ok(Last::InCategory) => {
+                // SAFETY: Even if another thread starts the ring cleanup, the cleanup will
+                // wait for the tail being marked.
+                unsafe {
+                    (*self.ring).mark_cons_finished();
+                }
+            }
+            Ok(Last::InRing) => {
+                // SAFETY: `Last::InRing` guarantees that we're the last
+                unsafe { Ring::cleanup(self.ring) }
+            }
+            Ok(Last::NotLast) => {}
+        }
+    }
+}
+
+// SAFETY: The ring is designed to be accessed from different threads. The values are moved to
+//         the thread of the sender, so they must be `Send`.
+unsafe impl<const N: usize, T, P, C> Send for Receiver<N, T, P, C>
+where
+    T: Send,
+    P: Mode,
+    C: Mode,
+{
+}
+
+// SAFETY: Mutable access to the consumer head is guarded by atomics, but only for those that implement Sync.
+unsafe impl<const N: usize, T, P, C> Sync for Receiver<N, T, P, C>
+where
+    T: Send,
+    P: Mode,
+    C: Mode + Sync,
+{
+}
+
+/// A blocking iterator over the items of a [`Receiver`], created with [`Receiver::iter`].
+pub struct Iter<'a, const N: usize, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    /// The receiver to receive the items from.
+    receiver: &'a Receiver<N, T, P, C>,
+}
+
+impl<const N: usize, T, P, C> Iterator for Iter<'_, N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        // `recv` only returns an error when it can't receive anything anymore
+        self.receiver.recv().ok()
+    }
+}
+
+impl<const N: usize, T, P, C> core::fmt::Debug for Iter<'_, N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Iter")
+            .field("receiver", self.receiver)
+            .finish()
+    }
+}
+
+impl<'a, const N: usize, T, P, C> IntoIterator for &'a Receiver<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    type Item = T;
+    type IntoIter = Iter<'a, N, T, P, C>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A weak handle to the receiving-half of the channel.
+///
+/// Unlike a [`Receiver`], this doesn't keep the channel open. It only keeps the channel allocated,
+/// so it can be upgraded to a [`Receiver`] while there are still other receivers. Created with
+/// [`Receiver::downgrade`].
+///
+/// ```
+/// let (sender, receiver) = ringbeam::spmc::bounded::<4, u8>();
+/// let weak = receiver.downgrade();
+/// assert!(weak.upgrade().is_some());
+/// drop(receiver);
+/// assert!(weak.upgrade().is_none());
+/// assert!(sender.is_closed());
+/// ```
+pub struct ReceiverWeak<const N: usize, T, P, C>
+where
+    P: Mode,
+    C: Mode + Sync,
+{
+    /// The actual ring.
+    ///
+    /// This pointer is valid and aligned for the entire lifetime of [`ReceiverWeak`].
+    ring: *const Ring<N, T, P, C>,
+}
+
+impl<const N: usize, T, P, C> ReceiverWeak<N, T, P, C>
+where
+    P: Mode,
+    C: Mode + Sync,
+{
+    /// Try to get a [`Receiver`] for the channel.
+    ///
+    /// Returns `None` if every [`Receiver`] is already dropped, or if the ring is poisoned.
+    #[must_use]
+    #[inline]
+    pub fn upgrade(&self) -> Option<Receiver<N, T, P, C>> {
+        // SAFETY: because `self` is valid, `ring` is initialized and aligned.
+        unsafe { Receiver::new(self.ring).ok() }
+    }
+}
+
+impl<const N: usize, T, P, C> Clone for ReceiverWeak<N, T, P, C>
+where
+    P: Mode,
+    C: Mode + Sync,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        unsafe {
+            (*self.ring).active().register_weak();
+        }
+        Self { ring: self.ring }
+    }
+}
+
+impl<const N: usize, T, P, C> core::fmt::Debug for ReceiverWeak<N, T, P, C>
+where
+    P: Mode,
+    C: Mode + Sync,
+{
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.fmt_handle("ReceiverWeak", f)
+    }
+}
+
+impl<const N: usize, T, P, C> Drop for ReceiverWeak<N, T, P, C>
+where
+    P: Mode,
+    C: Mode + Sync,
+{
+    #[inline]
+    fn drop(&mut self) {
+        // SAFETY: Ring is valid before we call unregister_weak
+        match unsafe { (*self.ring).active().unregister_weak() } {
+            // The ring is leaked, unless the poison is cleared by the last handle.
+            Err(_) => cold_path(),
+            Ok(Last::InRing) => {
+                // SAFETY: `Last::InRing` guarantees that we're the last, and the tails are marked
+                //         before the cleanup.
+                unsafe {
+                    (*self.ring).wait_until_finished();
+                    Ring::cleanup(self.ring);
+                }
+            }
+            Ok(Last::InCategory | Last::NotLast) => {}
+        }
+    }
+}
+
+// SAFETY: A weak handle only gives access to the ring by upgrading to a `Receiver`, which is `Send`
+//         and `Sync` under the same bounds.
+unsafe impl<const N: usize, T, P, C> Send for ReceiverWeak<N, T, P, C>
+where
+    T: Send,
+    P: Mode,
+    C: Mode + Sync,
+{
+}
+
+// SAFETY: See `Send`.
+unsafe impl<const N: usize, T, P, C> Sync for ReceiverWeak<N, T, P, C>
 where
+    T: Send,
     P: Mode,
     C: Mode + Sync,
 {
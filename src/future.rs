@@ -0,0 +1,371 @@
+//! Sending and receiving from async code.
+//!
+//! [`AsyncSender`] and [`AsyncReceiver`] wrap a [`Sender`] and [`Receiver`]. When the channel is
+//! full or empty, the task registers its waker with the ring and is woken once the other side has
+//! made room or sent a value, or when the other side is gone.
+//!
+//! This module doesn't depend on an async runtime. With the `futures` feature [`AsyncReceiver`]
+//! implements `Stream`, which ends once the channel is closed and empty. The poll methods of
+//! [`AsyncSender`] have the same shape as `Sink::poll_ready`, so implementing that trait on a
+//! wrapper only takes a few lines.
+use crate::{Error, consumer::Receiver, modes::Mode, producer::Sender, std::hint::cold_path};
+use core::{
+    mem::ManuallyDrop,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+/// The sending-half of the channel for async code.
+///
+/// See [`Sender`] for the generics.
+pub struct AsyncSender<const N: usize, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    /// The wrapped sender.
+    sender: Sender<N, T, P, C>,
+    /// The id of this task in the waker list of the ring.
+    id: usize,
+}
+
+impl<const N: usize, T, P, C> AsyncSender<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    /// Wrap `sender` for use in async code.
+    #[must_use]
+    #[inline]
+    pub fn new(sender: Sender<N, T, P, C>) -> Self {
+        let id = sender.ring().send_wakers().next_id();
+        Self { sender, id }
+    }
+
+    /// Get the wrapped sender back.
+    #[must_use]
+    #[inline]
+    pub fn into_inner(self) -> Sender<N, T, P, C> {
+        let this = ManuallyDrop::new(self);
+        this.sender.ring().send_wakers().unregister(this.id);
+        // SAFETY: `this` is never used or dropped again, so the sender is moved out exactly once.
+        unsafe { core::ptr::read(&raw const this.sender) }
+    }
+
+    /// Get access to the wrapped sender.
+    #[must_use]
+    #[inline]
+    pub const fn get_ref(&self) -> &Sender<N, T, P, C> {
+        &self.sender
+    }
+
+    /// Put the value in the channel, waiting while the channel is full.
+    ///
+    /// # Errors
+    /// The future returns [`Error::Closed`] when closed and [`Error::Poisoned`] when the ring is
+    /// poisoned, the value is dropped in that case. It also waits while the value doesn't fit in
+    /// the byte budget, but returns [`Error::OverBudget`] if it's larger than the whole budget.
+    #[inline]
+    pub const fn send(&mut self, value: T) -> SendFuture<'_, N, T, P, C> {
+        SendFuture {
+            sender: self,
+            value: Some(value),
+        }
+    }
+
+    /// Wait until the channel has room for a value.
+    ///
+    /// Like `Sink::poll_ready`, this only registers the task when the channel is full. With
+    /// multiple producers, another producer can still fill the channel before the value is sent.
+    ///
+    /// # Errors
+    /// Returns [`Error::Closed`] when closed and [`Error::Poisoned`] when the ring is poisoned.
+    #[inline]
+    pub fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        let ring = self.sender.ring();
+        if !self.sender.is_full() && ring.budget().check().is_ok() {
+            return Poll::Ready(ring.active().consumers().map(|_| ()));
+        }
+        ring.send_wakers().register(self.id, cx.waker());
+        if !self.sender.is_full() && ring.budget().check().is_ok() {
+            return Poll::Ready(ring.active().consumers().map(|_| ()));
+        }
+        // A closed channel wakes the tasks after it's marked closed.
+        if let Err(error) = ring.active().check_producer() {
+            cold_path();
+            return Poll::Ready(Err(error));
+        }
+        match ring.active().consumers() {
+            Ok(0) => {
+                cold_path();
+                Poll::Ready(Err(Error::Closed))
+            }
+            Ok(_) => Poll::Pending,
+            Err(error) => {
+                cold_path();
+                Poll::Ready(Err(error))
+            }
+        }
+    }
+
+    /// Try to put the value in the channel, registering the task if it's full.
+    ///
+    /// Returns the value if the task should wait.
+    ///
+    /// # Errors
+    /// Returns [`Error::Closed`] when closed and [`Error::Poisoned`] when the ring is poisoned.
+    /// Returns [`Error::OverBudget`] if the value is larger than the whole byte budget.
+    fn poll_send(&self, waker: &Waker, value: T) -> Result<Option<T>, Error> {
+        let value = match self.sender.try_send_or_return(value) {
+            Ok(()) => return Ok(None),
+            Err((Error::Full, value)) => value,
+            Err((Error::OverBudget, value)) if !self.sender.exceeds_budget(&value) => value,
+            Err((error, _)) => {
+                cold_path();
+                return Err(error);
+            }
+        };
+        let ring = self.sender.ring();
+        ring.send_wakers().register(self.id, waker);
+        // Check again, the consumers could have made room before the task was registered.
+        let value = match self.sender.try_send_or_return(value) {
+            Ok(()) => return Ok(None),
+            Err((Error::Full, value)) => value,
+            Err((Error::OverBudget, value)) if !self.sender.exceeds_budget(&value) => value,
+            Err((error, _)) => {
+                cold_path();
+                return Err(error);
+            }
+        };
+        // The last consumer wakes the tasks before marking the channel closed.
+        if ring.active().consumers()? == 0 {
+            cold_path();
+            return Err(Error::Closed);
+        }
+        Ok(Some(value))
+    }
+}
+
+impl<const N: usize, T, P, C> From<Sender<N, T, P, C>> for AsyncSender<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    #[inline]
+    fn from(sender: Sender<N, T, P, C>) -> Self {
+        Self::new(sender)
+    }
+}
+
+impl<const N: usize, T, P, C> Drop for AsyncSender<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    #[inline]
+    fn drop(&mut self) {
+        self.sender.ring().send_wakers().unregister(self.id);
+    }
+}
+
+/// The future returned by [`AsyncSender::send`].
+#[must_use = "futures do nothing unless polled"]
+pub struct SendFuture<'a, const N: usize, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    /// The sender to send the value with.
+    sender: &'a mut AsyncSender<N, T, P, C>,
+    /// The value to send, `None` once it's sent.
+    value: Option<T>,
+}
+
+// The value is never pinned, it's moved in and out of the option.
+impl<const N: usize, T, P, C> Unpin for SendFuture<'_, N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+}
+
+impl<const N: usize, T, P, C> Future for SendFuture<'_, N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    type Output = Result<(), Error>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let value = this
+            .value
+            .take()
+            .expect("SendFuture was polled after completion");
+        match this.sender.poll_send(cx.waker(), value) {
+            Ok(None) => Poll::Ready(Ok(())),
+            Ok(Some(value)) => {
+                this.value = Some(value);
+                Poll::Pending
+            }
+            Err(error) => Poll::Ready(Err(error)),
+        }
+    }
+}
+
+/// The receiving-half of the channel for async code.
+///
+/// See [`Receiver`] for the generics.
+pub struct AsyncReceiver<const N: usize, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    /// The wrapped receiver.
+    receiver: Receiver<N, T, P, C>,
+    /// The id of this task in the waker list of the ring.
+    id: usize,
+}
+
+impl<const N: usize, T, P, C> AsyncReceiver<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    /// Wrap `receiver` for use in async code.
+    #[must_use]
+    #[inline]
+    pub fn new(receiver: Receiver<N, T, P, C>) -> Self {
+        let id = receiver.ring().recv_wakers().next_id();
+        Self { receiver, id }
+    }
+
+    /// Get the wrapped receiver back.
+    #[must_use]
+    #[inline]
+    pub fn into_inner(self) -> Receiver<N, T, P, C> {
+        let this = ManuallyDrop::new(self);
+        this.receiver.ring().recv_wakers().unregister(this.id);
+        // SAFETY: `this` is never used or dropped again, so the receiver is moved out exactly once.
+        unsafe { core::ptr::read(&raw const this.receiver) }
+    }
+
+    /// Get access to the wrapped receiver.
+    #[must_use]
+    #[inline]
+    pub const fn get_ref(&self) -> &Receiver<N, T, P, C> {
+        &self.receiver
+    }
+
+    /// Get one item from the channel, waiting while the channel is empty.
+    ///
+    /// # Errors
+    /// The future returns [`Error::Closed`] once the channel is closed and empty, and
+    /// [`Error::Poisoned`] if the ring is poisoned. It can also return [`Error::TooManyConsumers`]
+    /// if there are already `u16::MAX - 1` instances of `Receiver`s and [`RecvValues`](crate::custom::RecvValues).
+    #[inline]
+    pub const fn recv(&mut self) -> RecvFuture<'_, N, T, P, C> {
+        RecvFuture { receiver: self }
+    }
+
+    /// Try to get one item from the channel, registering the task if it's empty.
+    ///
+    /// Like `Stream::poll_next`, but the end of the stream is [`Error::Closed`].
+    ///
+    /// # Errors
+    /// Returns [`Error::Closed`] once the channel is closed and empty, and [`Error::Poisoned`]
+    /// if the ring is poisoned. It can also return [`Error::TooManyConsumers`] if there are
+    /// already `u16::MAX - 1` instances of `Receiver`s and [`RecvValues`](crate::custom::RecvValues).
+    #[inline]
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, Error>> {
+        match self.receiver.try_recv() {
+            Err(Error::Empty) => {}
+            result => return Poll::Ready(result),
+        }
+        let ring = self.receiver.ring();
+        ring.recv_wakers().register(self.id, cx.waker());
+        // Check again, the producers could have sent a value before the task was registered.
+        match self.receiver.try_recv() {
+            Err(Error::Empty) => {}
+            result => return Poll::Ready(result),
+        }
+        // The last producer wakes the tasks before marking the channel closed.
+        match ring.active().producers() {
+            Ok(0) => {
+                cold_path();
+                Poll::Ready(Err(Error::Closed))
+            }
+            Ok(_) => Poll::Pending,
+            Err(error) => {
+                cold_path();
+                Poll::Ready(Err(error))
+            }
+        }
+    }
+}
+
+impl<const N: usize, T, P, C> From<Receiver<N, T, P, C>> for AsyncReceiver<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    #[inline]
+    fn from(receiver: Receiver<N, T, P, C>) -> Self {
+        Self::new(receiver)
+    }
+}
+
+impl<const N: usize, T, P, C> Drop for AsyncReceiver<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    #[inline]
+    fn drop(&mut self) {
+        self.receiver.ring().recv_wakers().unregister(self.id);
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<const N: usize, T, P, C> futures_core::Stream for AsyncReceiver<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    /// The errors other than [`Error::Closed`], like [`Error::Poisoned`], are yielded as items.
+    type Item = Result<T, Error>;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match self.get_mut().poll_recv(cx) {
+            Poll::Ready(Err(Error::Closed)) => Poll::Ready(None),
+            Poll::Ready(result) => Poll::Ready(Some(result)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// The future returned by [`AsyncReceiver::recv`].
+#[must_use = "futures do nothing unless polled"]
+pub struct RecvFuture<'a, const N: usize, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    /// The receiver to receive the value with.
+    receiver: &'a mut AsyncReceiver<N, T, P, C>,
+}
+
+impl<const N: usize, T, P, C> Future for RecvFuture<'_, N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    type Output = Result<T, Error>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.get_mut().receiver.poll_recv(cx)
+    }
+}
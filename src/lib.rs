@@ -5,24 +5,42 @@
 #[cfg(all(feature = "_loom", feature = "_shuttle"))]
 compile_error!("Features '_loom' and '_shuttle' cannot be enabled at the same time");
 
+mod backoff;
+pub mod broadcast;
+mod budget;
 mod cache_padded;
+mod clock;
 mod consumer;
+#[cfg(feature = "async")]
+pub mod future;
 mod modes;
+mod notify;
 mod producer;
 mod ring;
+pub mod select;
+#[cfg(feature = "serde")]
+pub mod snapshot;
 mod std;
 
+pub use backoff::Backoff;
+pub use budget::Measure;
+pub use clock::Clock;
+#[cfg(feature = "std")]
+pub use clock::StdClock;
+pub use notify::Notify;
+
 // TODO: Use consistent naming for producer/consumer or sender/receiver throughout.
 // TODO: Use consistent naming for enqueue/dequeue or send/recv throughout.
-// TODO: Implement peek for single/multi_hts
-// TODO: Make testing with loom and shuttle actually work
-// TODO: Maybe repr(c) on Ring, take an extra look at cache alignment.
-// TODO: WFE/SEV on ARM
+// TODO: WFE on ARM for the spin loops of HeadTailSync and RelaxedTailSync
 // TODO: Document the inner workings of the various modes in their module documentation.
 
 /// All errors that can be returned when accessing the channel.
 #[derive(Debug, PartialEq, Eq)]
 pub enum Error {
+    /// The cancel flag was set while waiting for the channel.
+    ///
+    /// See [`Receiver::recv_cancellable`](custom::Receiver::recv_cancellable).
+    Cancelled,
     /// The channel is closed.
     Closed,
     /// The channel is empty.
@@ -35,8 +53,18 @@ pub enum Error {
     NotEnoughItemsAndClosed,
     /// The caller wants to put exactly `n` items in the channel, but there is not enough room.
     NotEnoughSpace,
+    /// The channel has room, but the values would exceed its byte budget.
+    ///
+    /// See [`bounded_with_budget`](custom::bounded_with_budget).
+    OverBudget,
     /// A panic occurred while holding access to the channel, so the channel is in an undefined state.
     Poisoned,
+    /// The channel stayed full, and the consumers didn't take any values for too long.
+    ///
+    /// See [`Sender::send_with_progress_timeout`](custom::Sender::send_with_progress_timeout).
+    Stalled,
+    /// The deadline passed while waiting for the channel.
+    Timeout,
     /// There are too many consumers, a new one can't be added.
     ///
     /// The current limit is `u16::MAX - 1`
@@ -45,10 +73,49 @@ pub enum Error {
     ///
     /// The current limit is `u16::MAX - 1`
     TooManyProducers,
+    /// The operation would have to wait for the channel, but the caller asked not to wait.
+    WouldBlock,
 }
 
 impl core::error::Error for Error {}
 
+impl Error {
+    /// Can retrying the operation be successful.
+    ///
+    /// This is `true` for the errors caused by the current state of the channel, like
+    /// [`Error::Empty`] and [`Error::Full`], and `false` when the channel can't be used anymore
+    /// or can't take more handles. [`Error::NotEnoughItemsAndClosed`] is retryable, but only when
+    /// asking for fewer items. [`Error::Cancelled`] is not, as the caller asked to stop waiting.
+    ///
+    /// ```
+    /// # use ringbeam::Error;
+    /// assert!(Error::Empty.retryable());
+    /// assert!(Error::WouldBlock.retryable());
+    /// assert!(!Error::Closed.retryable());
+    /// assert!(!Error::Cancelled.retryable());
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn retryable(&self) -> bool {
+        match self {
+            Self::Empty
+            | Self::Full
+            | Self::NotEnoughItems
+            | Self::NotEnoughItemsAndClosed
+            | Self::NotEnoughSpace
+            | Self::OverBudget
+            | Self::Stalled
+            | Self::Timeout
+            | Self::WouldBlock => true,
+            Self::Cancelled
+            | Self::Closed
+            | Self::Poisoned
+            | Self::TooManyConsumers
+            | Self::TooManyProducers => false,
+        }
+    }
+}
+
 impl core::fmt::Display for Error {
     #[expect(
         clippy::missing_inline_in_public_items,
@@ -56,6 +123,7 @@ impl core::fmt::Display for Error {
     )]
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
+            Self::Cancelled => f.write_str("Cancelled waiting for the channel"),
             Self::Closed => f.write_str("Channel is closed"),
             Self::Empty => f.write_str("Channel is empty"),
             Self::Full => f.write_str("Channel is full"),
@@ -66,31 +134,138 @@ impl core::fmt::Display for Error {
             Self::NotEnoughSpace => {
                 f.write_str("Channel had room, but not enough room for all the items")
             }
+            Self::OverBudget => {
+                f.write_str("Channel has room, but not enough bytes left in its budget")
+            }
             Self::Poisoned => f.write_str("Channel is poisoned"),
+            Self::Stalled => f.write_str("Channel is full and the consumers made no progress"),
+            Self::Timeout => f.write_str("Timed out waiting for the channel"),
             Self::TooManyConsumers => {
                 f.write_str("Maximum amount of consumers in channel has been reached")
             }
             Self::TooManyProducers => {
                 f.write_str("Maximum amount of producers in channel has been reached")
             }
+            Self::WouldBlock => f.write_str("Operation would have to wait for the channel"),
         }
     }
 }
 
+/// The smallest ring size `N` that holds at least `desired` values.
+///
+/// A ring of size `N` holds `N - 1` values and `N` must be a power of two from 2 up to and
+/// including 2^31, see [Capacity](crate#capacity). This is a `const fn`, so it can be used for `N`:
+/// ```
+/// let (sender, _receiver) = ringbeam::spsc::bounded::<{ ringbeam::next_capacity(1000) }, u8>();
+/// assert_eq!(sender.capacity(), 1023);
+/// assert_eq!(ringbeam::next_capacity(0), 2);
+/// assert_eq!(ringbeam::next_capacity(1024), 2048);
+/// ```
+///
+/// # Panics
+/// Will panic if `desired` is larger than `2^31 - 1`, which doesn't fit in any ring. In a const
+/// context this fails to compile:
+/// ```compile_fail,E0080
+/// let (_sender, _receiver) = ringbeam::spsc::bounded::<{ ringbeam::next_capacity(1 << 31) }, u8>();
+/// ```
+#[must_use]
+#[inline]
+pub const fn next_capacity(desired: usize) -> usize {
+    assert!(desired < 1 << 31, "A ring holds at most 2^31 - 1 values");
+    let size = (desired + 1).next_power_of_two();
+    if size < 2 { 2 } else { size }
+}
+
+/// Define a function that creates a channel in a `static`, without allocating.
+///
+/// The function returns the sender and receiver of a channel in a
+/// [`RingStorage`](custom::RingStorage) that is placed in a `static`, like
+/// [`bounded_in`](custom::bounded_in). The memory of a `static` can only be used for one channel,
+/// so the function returns `None` when it's called again. This makes it safe, unlike borrowing a
+/// `static mut` directly.
+///
+/// The channel is defined as `<N, T, P, C>`, see [`bounded`](custom::bounded). The types are
+/// resolved where the macro is used.
+///
+/// ```
+/// use ringbeam::custom::modes::{Multi, Single};
+///
+/// ringbeam::static_channel! {
+///     /// The channel of the events.
+///     pub fn events: <8, u32, Multi, Single>;
+/// }
+///
+/// let (sender, receiver) = events().unwrap();
+/// assert!(events().is_none());
+/// assert_eq!(sender.try_send(1), Ok(None));
+/// assert_eq!(receiver.try_recv(), Ok(1));
+/// ```
+#[macro_export]
+macro_rules! static_channel {
+    ($(#[$attr:meta])* $vis:vis fn $name:ident: <$n:expr, $t:ty, $p:ty, $c:ty>;) => {
+        $(#[$attr])*
+        $vis fn $name() -> ::core::option::Option<(
+            $crate::custom::Sender<$n, $t, $p, $c>,
+            $crate::custom::Receiver<$n, $t, $p, $c>,
+        )> {
+            static TAKEN: ::core::sync::atomic::AtomicBool =
+                ::core::sync::atomic::AtomicBool::new(false);
+            static mut STORAGE: $crate::custom::RingStorage<$n, $t, $p, $c> =
+                $crate::custom::RingStorage::new();
+
+            if TAKEN.swap(true, ::core::sync::atomic::Ordering::Relaxed) {
+                return ::core::option::Option::None;
+            }
+            // SAFETY: `TAKEN` was still `false`, so this is the only borrow of `STORAGE` ever.
+            let storage = unsafe { &mut *&raw mut STORAGE };
+            ::core::option::Option::Some($crate::custom::bounded_in(storage))
+        }
+    };
+}
+
 /// A channel with a custom configuration.
 pub mod custom {
-    pub use crate::{consumer::Receiver, producer::Sender, ring::recv_values::RecvValues};
-    use crate::{modes::Mode, ring::Ring};
+    #[cfg(feature = "debug")]
+    pub use crate::ring::stats::RingStats;
+    #[cfg(not(feature = "_safe_maybeuninit"))]
+    pub use crate::ring::{peek::PeekGuard, write_guard::WriteGuard};
+    use crate::{Measure, Notify, budget::Budget, modes::Mode, ring::Ring};
+    pub use crate::{
+        consumer::{Iter, Receiver, ReceiverWeak},
+        producer::{Sender, SenderWeak},
+        ring::{
+            peek::{Ack, Peek},
+            permit::Permit,
+            recv_values::{RecvValues, RevRecvValues},
+            storage::RingStorage,
+        },
+    };
 
     /// The synchronisation modes that can be used with the custom channel.
     pub mod modes {
-        pub use crate::modes::{HeadTailSync, Mode, Multi, RelaxedTailSync, Single};
+        pub use crate::modes::{
+            Advance, DynMode, DynModeSettings, Fairness, HeadTailSync, MaxHeadTailDistance, Mode,
+            Multi, RelaxedTailSync, Rewind, Single, SpinLimit,
+        };
     }
 
-    /// Create a custom channel with space for `N` values of `T`.
+    /// Create a custom channel with space for `N - 1` values of `T`.
+    ///
+    /// # Size
+    /// `N` must be a power of two from 2 up to and including 2^31. Other sizes fail to compile, for
+    /// every constructor of a channel. For a size chosen at runtime, use [`bounded_dyn`]:
+    /// ```compile_fail,E0080
+    /// let (_sender, _receiver) = ringbeam::spsc::bounded::<0, u8>();
+    /// ```
+    /// ```compile_fail,E0080
+    /// let (_sender, _receiver) = ringbeam::spsc::bounded::<1, u8>();
+    /// ```
+    /// ```compile_fail,E0080
+    /// let (_sender, _receiver) = ringbeam::spsc::bounded::<3, u8>();
+    /// ```
     ///
     /// # Type parameters
-    /// - N: the size of the channel,
+    /// - N: the size of the ring, the channel holds `N - 1` values (see [Capacity](crate#capacity)),
     /// - T: the type that will be sent over the channel,
     /// - P: the sync mode of the producer head and tail (see [`Mode`]),
     /// - C: the sync mode of the consumer head and tail (see [`Mode`]),
@@ -104,10 +279,52 @@ pub mod custom {
         Ring::new()
     }
 
-    /// Create a custom channel with space for `N` values of `T`.
+    /// Create a custom channel with space for at least `capacity` values of `T`, with the size
+    /// chosen at runtime.
+    ///
+    /// The size of the ring is [`next_capacity(capacity)`](crate::next_capacity), so the channel
+    /// can hold more than `capacity` values. The handles have `N` set to 0.
+    /// ```
+    /// use ringbeam::custom::modes::{Multi, Single};
+    ///
+    /// let (sender, receiver) = ringbeam::custom::bounded_dyn::<u8, Multi, Single>(5);
+    /// assert_eq!(sender.capacity(), 7);
+    /// assert_eq!(sender.try_send(1), Ok(None));
+    /// assert_eq!(receiver.try_recv(), Ok(1));
+    /// ```
+    ///
+    /// # Panics
+    /// Will panic if `capacity` is larger than `2^31 - 1`.
+    ///
+    /// # Type parameters
+    /// - T: the type that will be sent over the channel,
+    /// - P: the sync mode of the producer head and tail (see [`Mode`]),
+    /// - C: the sync mode of the consumer head and tail (see [`Mode`]),
+    #[must_use]
+    #[inline]
+    pub fn bounded_dyn<T, P, C>(capacity: usize) -> (Sender<0, T, P, C>, Receiver<0, T, P, C>)
+    where
+        P: Mode,
+        C: Mode,
+    {
+        Ring::new_dyn(
+            crate::next_capacity(capacity),
+            P::Settings::default(),
+            C::Settings::default(),
+            Budget::unlimited(),
+        )
+    }
+
+    /// Create a custom channel with space for `N - 1` values of `T`, configuring the sync modes.
+    ///
+    /// The settings are passed to [`Mode::new_with`] of the producer and consumer mode.
+    /// [`RelaxedTailSync`](modes::RelaxedTailSync) takes a [`MaxHeadTailDistance`](modes::MaxHeadTailDistance),
+    /// [`HeadTailSync`](modes::HeadTailSync) a [`Fairness`](modes::Fairness), and
+    /// [`Multi`](modes::Multi) a [`SpinLimit`](modes::SpinLimit). For [`Single`](modes::Single)
+    /// the settings are `()`.
     ///
     /// # Type parameters
-    /// - N: the size of the channel,
+    /// - N: the size of the ring, the channel holds `N - 1` values (see [Capacity](crate#capacity)),
     /// - T: the type that will be sent over the channel,
     /// - P: the sync mode of the producer head and tail (see [`Mode`]),
     /// - C: the sync mode of the consumer head and tail (see [`Mode`]),
@@ -123,6 +340,268 @@ pub mod custom {
     {
         Ring::new_with_config(producer_settings, consumer_settings)
     }
+
+    /// Create a custom channel with space for `N - 1` values of `T`, filled with the values of `iter`.
+    ///
+    /// At most the capacity of the channel, `N - 1` values, are taken from `iter`. The values that
+    /// don't fit are not taken from `iter`, pass `iter.by_ref()` to keep them:
+    /// ```
+    /// use ringbeam::custom::{bounded_prefilled, modes::Single};
+    /// let mut iter = 0..10;
+    /// let (_sender, receiver, stored) = bounded_prefilled::<4, _, Single, Single, _>(iter.by_ref());
+    /// assert_eq!(stored, 3);
+    /// assert_eq!(iter.next(), Some(3));
+    /// assert_eq!(receiver.try_recv(), Ok(0));
+    /// ```
+    ///
+    /// # Returns
+    /// The sender, the receiver, and the amount of values stored in the channel.
+    ///
+    /// # Type parameters
+    /// - N: the size of the ring, the channel holds `N - 1` values (see [Capacity](crate#capacity)),
+    /// - T: the type that will be sent over the channel,
+    /// - P: the sync mode of the producer head and tail (see [`Mode`]),
+    /// - C: the sync mode of the consumer head and tail (see [`Mode`]),
+    /// - I: the iterator with the initial values.
+    #[must_use]
+    #[inline]
+    pub fn bounded_prefilled<const N: usize, T, P, C, I>(
+        iter: I,
+    ) -> (Sender<N, T, P, C>, Receiver<N, T, P, C>, usize)
+    where
+        P: Mode,
+        C: Mode,
+        I: IntoIterator<Item = T>,
+    {
+        let (sender, receiver) = Ring::new();
+        let mut values = iter
+            .into_iter()
+            .take(sender.capacity())
+            .collect::<Vec<_>>()
+            .into_iter();
+        // Nobody else has a handle to the empty channel yet, so all values fit
+        let stored = sender
+            .try_send_burst(&mut values)
+            .unwrap_or_else(|_| unreachable!());
+        (sender, receiver, stored)
+    }
+
+    /// Create a custom channel with space for `N - 1` values of `T` and at most `limit` bytes.
+    ///
+    /// Every value is measured with [`Measure`], and the channel keeps track of the total bytes
+    /// that are currently buffered. A value that would bring that total over `limit` isn't sent,
+    /// [`Sender::try_send`] returns it like when the channel is full. The total never exceeds
+    /// `limit`.
+    ///
+    /// The values of an iterator can only be measured after they're taken from it, and a value
+    /// that doesn't fit would then be lost. So [`Sender::try_send_bulk`], [`Sender::try_send_burst`],
+    /// and [`Sender::try_send_from`] always fail with [`Error::OverBudget`](crate::Error::OverBudget)
+    /// on a channel with a byte budget. Use [`Sender::try_send_prefix`], [`Sender::push_iter`], or
+    /// [`Sender::try_send_slice_burst`] instead, which only send the values that fit.
+    ///
+    /// # Type parameters
+    /// - N: the size of the ring, the channel holds `N - 1` values (see [Capacity](crate#capacity)),
+    /// - T: the type that will be sent over the channel,
+    /// - P: the sync mode of the producer head and tail (see [`Mode`]),
+    /// - C: the sync mode of the consumer head and tail (see [`Mode`]),
+    #[must_use]
+    #[inline]
+    pub fn bounded_with_budget<const N: usize, T, P, C>(
+        limit: usize,
+    ) -> (Sender<N, T, P, C>, Receiver<N, T, P, C>)
+    where
+        T: Measure,
+        P: Mode,
+        C: Mode,
+    {
+        Ring::new_with_budget(
+            P::Settings::default(),
+            C::Settings::default(),
+            Budget::limited(limit),
+        )
+    }
+
+    /// Create a custom channel with space for `N - 1` values of `T` that calls `notify` after values
+    /// are sent.
+    ///
+    /// The hook is called by the sender after every successful send, with the amount of values
+    /// that became visible to the receivers. This can be used to wake receivers that block on
+    /// something else than the channel:
+    /// ```
+    /// use ringbeam::custom::{bounded_with_notify, modes::Single};
+    /// use std::sync::{Arc, atomic::{AtomicUsize, Ordering}};
+    ///
+    /// let sent = Arc::new(AtomicUsize::new(0));
+    /// let counter = sent.clone();
+    /// let (sender, _receiver) = bounded_with_notify::<4, u8, Single, Single>(move |n| {
+    ///     counter.fetch_add(n, Ordering::Relaxed);
+    /// });
+    /// sender.try_send_slice(&[1, 2]).unwrap();
+    /// sender.try_send(3).unwrap();
+    /// assert_eq!(sent.load(Ordering::Relaxed), 3);
+    /// ```
+    ///
+    /// # Type parameters
+    /// - N: the size of the ring, the channel holds `N - 1` values (see [Capacity](crate#capacity)),
+    /// - T: the type that will be sent over the channel,
+    /// - P: the sync mode of the producer head and tail (see [`Mode`]),
+    /// - C: the sync mode of the consumer head and tail (see [`Mode`]),
+    #[must_use]
+    #[inline]
+    pub fn bounded_with_notify<const N: usize, T, P, C>(
+        notify: impl Notify + 'static,
+    ) -> (Sender<N, T, P, C>, Receiver<N, T, P, C>)
+    where
+        P: Mode,
+        C: Mode,
+    {
+        Ring::new_with_notify(
+            P::Settings::default(),
+            C::Settings::default(),
+            Budget::unlimited(),
+            Some(Box::new(notify)),
+        )
+    }
+
+    /// Create a custom channel with space for `N - 1` values of `T` that calls `on_close` when the
+    /// last handle is dropped.
+    ///
+    /// The hook is called exactly once, by the thread that drops the last [`Sender`],
+    /// [`Receiver`], or weak handle, right before the channel is deallocated. This can be used to
+    /// release resources that belong to the channel, like file descriptors or shared mappings.
+    /// If the hook panics, the memory of the channel is leaked.
+    /// ```
+    /// use ringbeam::custom::{bounded_with_on_close, modes::Single};
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    ///
+    /// static CLOSED: AtomicBool = AtomicBool::new(false);
+    /// let (sender, receiver) = bounded_with_on_close::<4, u8, Single, Single>(|| {
+    ///     CLOSED.store(true, Ordering::Relaxed);
+    /// });
+    /// drop(sender);
+    /// assert!(!CLOSED.load(Ordering::Relaxed));
+    /// drop(receiver);
+    /// assert!(CLOSED.load(Ordering::Relaxed));
+    /// ```
+    ///
+    /// # Type parameters
+    /// - N: the size of the ring, the channel holds `N - 1` values (see [Capacity](crate#capacity)),
+    /// - T: the type that will be sent over the channel,
+    /// - P: the sync mode of the producer head and tail (see [`Mode`]),
+    /// - C: the sync mode of the consumer head and tail (see [`Mode`]),
+    #[must_use]
+    #[inline]
+    pub fn bounded_with_on_close<const N: usize, T, P, C>(
+        on_close: fn(),
+    ) -> (Sender<N, T, P, C>, Receiver<N, T, P, C>)
+    where
+        P: Mode,
+        C: Mode,
+    {
+        Ring::new_with_hooks(
+            P::Settings::default(),
+            C::Settings::default(),
+            Budget::unlimited(),
+            None,
+            Some(on_close),
+        )
+    }
+
+    /// Create a custom channel with space for `N - 1` values of `T` in `storage`.
+    ///
+    /// The channel is not allocated, so this can be used without a global allocator. When the
+    /// last handle is dropped, the channel is cleaned up but `storage` is not deallocated. As the
+    /// handles can be sent to other threads, `storage` must be borrowed forever. If `storage` is
+    /// shared with another process, the channel can only be polled from there, see
+    /// [Shared memory](crate#shared-memory).
+    ///
+    /// ```
+    /// use ringbeam::custom::{RingStorage, bounded_in, modes::Single};
+    /// static mut STORAGE: RingStorage<8, u32, Single, Single> = RingStorage::new();
+    ///
+    /// // SAFETY: This is the only place `STORAGE` is used.
+    /// let storage = unsafe { &mut *&raw mut STORAGE };
+    /// let (sender, receiver) = bounded_in(storage);
+    /// assert_eq!(sender.try_send(1), Ok(None));
+    /// assert_eq!(receiver.try_recv(), Ok(1));
+    /// ```
+    ///
+    /// # Type parameters
+    /// - N: the size of the ring, the channel holds `N - 1` values (see [Capacity](crate#capacity)),
+    /// - T: the type that will be sent over the channel,
+    /// - P: the sync mode of the producer head and tail (see [`Mode`]),
+    /// - C: the sync mode of the consumer head and tail (see [`Mode`]),
+    #[must_use]
+    #[inline]
+    pub fn bounded_in<const N: usize, T, P, C>(
+        storage: &'static mut RingStorage<N, T, P, C>,
+    ) -> (Sender<N, T, P, C>, Receiver<N, T, P, C>)
+    where
+        P: Mode,
+        C: Mode,
+    {
+        Ring::new_in(
+            storage,
+            P::Settings::default(),
+            C::Settings::default(),
+            Budget::unlimited(),
+        )
+    }
+
+    /// The amount of bytes that is allocated for a channel.
+    ///
+    /// Besides the `N` values of `T`, the channel has some bookkeeping. Every part of the
+    /// channel is padded to its own cache line to avoid false sharing, so this is larger than
+    /// `N * size_of::<T>()`. All parts are allocated together, once, when creating the channel.
+    /// This is also the size of a [`RingStorage`].
+    /// This fails to compile if `N` is 0, as the size of the channel is then chosen at runtime.
+    ///
+    /// ```
+    /// use ringbeam::custom::{allocation_size, modes::Single};
+    /// let size = const { allocation_size::<1024, u64, Single, Single>() };
+    /// assert!(size >= 1024 * size_of::<u64>());
+    /// ```
+    ///
+    /// # Type parameters
+    /// - N: the size of the ring, the channel holds `N - 1` values (see [Capacity](crate#capacity)),
+    /// - T: the type that will be sent over the channel,
+    /// - P: the sync mode of the producer head and tail (see [`Mode`]),
+    /// - C: the sync mode of the consumer head and tail (see [`Mode`]),
+    #[must_use]
+    #[inline]
+    pub const fn allocation_size<const N: usize, T, P, C>() -> usize
+    where
+        P: Mode,
+        C: Mode,
+    {
+        const {
+            assert!(N != 0, "The size of the ring is chosen at runtime");
+        }
+        Ring::<N, T, P, C>::layout().size()
+    }
+
+    /// The alignment of the allocation for a channel.
+    ///
+    /// This is at least the alignment of `T` and of a cache line.
+    /// This fails to compile if `N` is 0, like [`allocation_size`].
+    ///
+    /// # Type parameters
+    /// - N: the size of the ring, the channel holds `N - 1` values (see [Capacity](crate#capacity)),
+    /// - T: the type that will be sent over the channel,
+    /// - P: the sync mode of the producer head and tail (see [`Mode`]),
+    /// - C: the sync mode of the consumer head and tail (see [`Mode`]),
+    #[must_use]
+    #[inline]
+    pub const fn allocation_align<const N: usize, T, P, C>() -> usize
+    where
+        P: Mode,
+        C: Mode,
+    {
+        const {
+            assert!(N != 0, "The size of the ring is chosen at runtime");
+        }
+        Ring::<N, T, P, C>::layout().align()
+    }
 }
 
 /// A single-producer single-consumer channel.
@@ -135,16 +614,66 @@ pub mod spsc {
     /// The sending half of a bounded single-producer single-consumer channel.
     pub type Sender<const N: usize, T> = crate::producer::Sender<N, T, Single, Single>;
 
+    /// A reserved slot in a [`Sender`], see [`Sender::reserve`](crate::custom::Sender::reserve).
+    pub type Permit<'a, const N: usize, T> = crate::ring::permit::Permit<'a, N, T, Single, Single>;
+
     /// An iterator over the values read by a [`Receiver`].
     pub type RecvValues<const N: usize, T> =
         crate::ring::recv_values::RecvValues<N, T, Single, Single>;
 
-    /// Create a single-producer single-consumer channel with space for `N` values of `T`.
+    /// An iterator over the values read by a [`Receiver`] from the last to the first, see
+    /// [`RecvValues::into_rev`](crate::custom::RecvValues::into_rev).
+    pub type RevRecvValues<const N: usize, T> =
+        crate::ring::recv_values::RevRecvValues<N, T, Single, Single>;
+
+    /// A blocking iterator over the values of a [`Receiver`], see [`Receiver::iter`](crate::custom::Receiver::iter).
+    pub type Iter<'a, const N: usize, T> = crate::consumer::Iter<'a, N, T, Single, Single>;
+
+    /// A view of the next value in a [`Receiver`], see [`Receiver::peek`](crate::custom::Receiver::peek).
+    pub type Peek<'a, const N: usize, T> = crate::ring::peek::Peek<'a, N, T, Single, Single>;
+
+    /// The next value in a [`Receiver`] until it's acknowledged, see [`Receiver::recv_ack`](crate::custom::Receiver::recv_ack).
+    pub type Ack<'a, const N: usize, T> = crate::ring::peek::Ack<'a, N, T, Single, Single>;
+
+    /// A view of the next values in a [`Receiver`], see [`Receiver::peek_bulk`](crate::custom::Receiver::peek_bulk).
+    #[cfg(not(feature = "_safe_maybeuninit"))]
+    pub type PeekGuard<'a, const N: usize, T> =
+        crate::ring::peek::PeekGuard<'a, N, T, Single, Single>;
+
+    /// Create a single-producer single-consumer channel with space for `N - 1` values of `T`.
     #[must_use]
     #[inline]
     pub fn bounded<const N: usize, T>() -> (Sender<N, T>, Receiver<N, T>) {
         Ring::new()
     }
+
+    /// Create a single-producer single-consumer channel with space for at least `capacity` values of `T`, with
+    /// the size chosen at runtime.
+    ///
+    /// See [`bounded_dyn`](crate::custom::bounded_dyn).
+    ///
+    /// # Panics
+    /// Will panic if `capacity` is larger than `2^31 - 1`.
+    #[must_use]
+    #[inline]
+    pub fn bounded_dyn<T>(capacity: usize) -> (Sender<0, T>, Receiver<0, T>) {
+        crate::custom::bounded_dyn(capacity)
+    }
+
+    /// Create a single-producer single-consumer channel with space for `N - 1` values of `T`, filled with the
+    /// values of `iter`.
+    ///
+    /// Returns the amount of values stored together with the handles, see
+    /// [`bounded_prefilled`](crate::custom::bounded_prefilled) for what happens with the values
+    /// that don't fit.
+    #[must_use]
+    #[inline]
+    pub fn bounded_prefilled<const N: usize, T, I>(iter: I) -> (Sender<N, T>, Receiver<N, T>, usize)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        crate::custom::bounded_prefilled(iter)
+    }
 }
 
 /// A single-producer multi-consumer channel.
@@ -160,16 +689,58 @@ pub mod spmc {
     /// The sending half of a bounded single-producer multi-consumer channel.
     pub type Sender<const N: usize, T> = crate::producer::Sender<N, T, Single, Multi>;
 
+    /// A reserved slot in a [`Sender`], see [`Sender::reserve`](crate::custom::Sender::reserve).
+    pub type Permit<'a, const N: usize, T> = crate::ring::permit::Permit<'a, N, T, Single, Multi>;
+
+    /// A weak handle to a [`Receiver`], see [`Receiver::downgrade`](crate::custom::Receiver::downgrade).
+    pub type ReceiverWeak<const N: usize, T> = crate::consumer::ReceiverWeak<N, T, Single, Multi>;
+
     /// An iterator over the values read by a [`Receiver`].
     pub type RecvValues<const N: usize, T> =
         crate::ring::recv_values::RecvValues<N, T, Single, Multi>;
 
-    /// Create a single-producer multi-consumer channel with space for `N` values of `T`.
+    /// An iterator over the values read by a [`Receiver`] from the last to the first, see
+    /// [`RecvValues::into_rev`](crate::custom::RecvValues::into_rev).
+    pub type RevRecvValues<const N: usize, T> =
+        crate::ring::recv_values::RevRecvValues<N, T, Single, Multi>;
+
+    /// A blocking iterator over the values of a [`Receiver`], see [`Receiver::iter`](crate::custom::Receiver::iter).
+    pub type Iter<'a, const N: usize, T> = crate::consumer::Iter<'a, N, T, Single, Multi>;
+
+    /// Create a single-producer multi-consumer channel with space for `N - 1` values of `T`.
     #[must_use]
     #[inline]
     pub fn bounded<const N: usize, T>() -> (Sender<N, T>, Receiver<N, T>) {
         Ring::new()
     }
+
+    /// Create a single-producer multi-consumer channel with space for at least `capacity` values of `T`, with
+    /// the size chosen at runtime.
+    ///
+    /// See [`bounded_dyn`](crate::custom::bounded_dyn).
+    ///
+    /// # Panics
+    /// Will panic if `capacity` is larger than `2^31 - 1`.
+    #[must_use]
+    #[inline]
+    pub fn bounded_dyn<T>(capacity: usize) -> (Sender<0, T>, Receiver<0, T>) {
+        crate::custom::bounded_dyn(capacity)
+    }
+
+    /// Create a single-producer multi-consumer channel with space for `N - 1` values of `T`, filled with the
+    /// values of `iter`.
+    ///
+    /// Returns the amount of values stored together with the handles, see
+    /// [`bounded_prefilled`](crate::custom::bounded_prefilled) for what happens with the values
+    /// that don't fit.
+    #[must_use]
+    #[inline]
+    pub fn bounded_prefilled<const N: usize, T, I>(iter: I) -> (Sender<N, T>, Receiver<N, T>, usize)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        crate::custom::bounded_prefilled(iter)
+    }
 }
 
 /// A multi-producer single-consumer channel.
@@ -185,16 +756,66 @@ pub mod mpsc {
     /// The sending half of a bounded multi-producer single-consumer channel.
     pub type Sender<const N: usize, T> = crate::producer::Sender<N, T, Multi, Single>;
 
+    /// A weak handle to a [`Sender`], see [`Sender::downgrade`](crate::custom::Sender::downgrade).
+    pub type SenderWeak<const N: usize, T> = crate::producer::SenderWeak<N, T, Multi, Single>;
+
     /// An iterator over the values read by a [`Receiver`].
     pub type RecvValues<const N: usize, T> =
         crate::ring::recv_values::RecvValues<N, T, Multi, Single>;
 
-    /// Create a multi-producer single-consumer channel with space for `N` values of `T`.
+    /// An iterator over the values read by a [`Receiver`] from the last to the first, see
+    /// [`RecvValues::into_rev`](crate::custom::RecvValues::into_rev).
+    pub type RevRecvValues<const N: usize, T> =
+        crate::ring::recv_values::RevRecvValues<N, T, Multi, Single>;
+
+    /// A blocking iterator over the values of a [`Receiver`], see [`Receiver::iter`](crate::custom::Receiver::iter).
+    pub type Iter<'a, const N: usize, T> = crate::consumer::Iter<'a, N, T, Multi, Single>;
+
+    /// A view of the next value in a [`Receiver`], see [`Receiver::peek`](crate::custom::Receiver::peek).
+    pub type Peek<'a, const N: usize, T> = crate::ring::peek::Peek<'a, N, T, Multi, Single>;
+
+    /// The next value in a [`Receiver`] until it's acknowledged, see [`Receiver::recv_ack`](crate::custom::Receiver::recv_ack).
+    pub type Ack<'a, const N: usize, T> = crate::ring::peek::Ack<'a, N, T, Multi, Single>;
+
+    /// A view of the next values in a [`Receiver`], see [`Receiver::peek_bulk`](crate::custom::Receiver::peek_bulk).
+    #[cfg(not(feature = "_safe_maybeuninit"))]
+    pub type PeekGuard<'a, const N: usize, T> =
+        crate::ring::peek::PeekGuard<'a, N, T, Multi, Single>;
+
+    /// Create a multi-producer single-consumer channel with space for `N - 1` values of `T`.
     #[must_use]
     #[inline]
     pub fn bounded<const N: usize, T>() -> (Sender<N, T>, Receiver<N, T>) {
         Ring::new()
     }
+
+    /// Create a multi-producer single-consumer channel with space for at least `capacity` values of `T`, with
+    /// the size chosen at runtime.
+    ///
+    /// See [`bounded_dyn`](crate::custom::bounded_dyn).
+    ///
+    /// # Panics
+    /// Will panic if `capacity` is larger than `2^31 - 1`.
+    #[must_use]
+    #[inline]
+    pub fn bounded_dyn<T>(capacity: usize) -> (Sender<0, T>, Receiver<0, T>) {
+        crate::custom::bounded_dyn(capacity)
+    }
+
+    /// Create a multi-producer single-consumer channel with space for `N - 1` values of `T`, filled with the
+    /// values of `iter`.
+    ///
+    /// Returns the amount of values stored together with the handles, see
+    /// [`bounded_prefilled`](crate::custom::bounded_prefilled) for what happens with the values
+    /// that don't fit.
+    #[must_use]
+    #[inline]
+    pub fn bounded_prefilled<const N: usize, T, I>(iter: I) -> (Sender<N, T>, Receiver<N, T>, usize)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        crate::custom::bounded_prefilled(iter)
+    }
 }
 
 /// A multi-producer multi-consumer channel.
@@ -207,14 +828,56 @@ pub mod mpmc {
     /// The sending half of a bounded multi-producer multi-consumer channel.
     pub type Sender<const N: usize, T> = crate::producer::Sender<N, T, Multi, Multi>;
 
+    /// A weak handle to a [`Receiver`], see [`Receiver::downgrade`](crate::custom::Receiver::downgrade).
+    pub type ReceiverWeak<const N: usize, T> = crate::consumer::ReceiverWeak<N, T, Multi, Multi>;
+
+    /// A weak handle to a [`Sender`], see [`Sender::downgrade`](crate::custom::Sender::downgrade).
+    pub type SenderWeak<const N: usize, T> = crate::producer::SenderWeak<N, T, Multi, Multi>;
+
     /// An iterator over the values read by a [`Receiver`].
     pub type RecvValues<const N: usize, T> =
         crate::ring::recv_values::RecvValues<N, T, Multi, Multi>;
 
-    /// Create a multi-producer multi-consumer channel with space for `N` values of `T`.
+    /// An iterator over the values read by a [`Receiver`] from the last to the first, see
+    /// [`RecvValues::into_rev`](crate::custom::RecvValues::into_rev).
+    pub type RevRecvValues<const N: usize, T> =
+        crate::ring::recv_values::RevRecvValues<N, T, Multi, Multi>;
+
+    /// A blocking iterator over the values of a [`Receiver`], see [`Receiver::iter`](crate::custom::Receiver::iter).
+    pub type Iter<'a, const N: usize, T> = crate::consumer::Iter<'a, N, T, Multi, Multi>;
+
+    /// Create a multi-producer multi-consumer channel with space for `N - 1` values of `T`.
     #[must_use]
     #[inline]
     pub fn bounded<const N: usize, T>() -> (Sender<N, T>, Receiver<N, T>) {
         Ring::new()
     }
+
+    /// Create a multi-producer multi-consumer channel with space for at least `capacity` values of `T`, with
+    /// the size chosen at runtime.
+    ///
+    /// See [`bounded_dyn`](crate::custom::bounded_dyn).
+    ///
+    /// # Panics
+    /// Will panic if `capacity` is larger than `2^31 - 1`.
+    #[must_use]
+    #[inline]
+    pub fn bounded_dyn<T>(capacity: usize) -> (Sender<0, T>, Receiver<0, T>) {
+        crate::custom::bounded_dyn(capacity)
+    }
+
+    /// Create a multi-producer multi-consumer channel with space for `N - 1` values of `T`, filled with the
+    /// values of `iter`.
+    ///
+    /// Returns the amount of values stored together with the handles, see
+    /// [`bounded_prefilled`](crate::custom::bounded_prefilled) for what happens with the values
+    /// that don't fit.
+    #[must_use]
+    #[inline]
+    pub fn bounded_prefilled<const N: usize, T, I>(iter: I) -> (Sender<N, T>, Receiver<N, T>, usize)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        crate::custom::bounded_prefilled(iter)
+    }
 }
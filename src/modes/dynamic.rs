@@ -0,0 +1,170 @@
+//! Implementation for a consumer or producer where the mode is chosen at runtime.
+
+use crate::{
+    Error,
+    modes::{
+        Claim, Fairness, HeadTailSync, MaxHeadTailDistance, Mode, ModeInner, Multi,
+        RelaxedTailSync, SpinLimit,
+    },
+    std::sync::atomic::Ordering,
+};
+use core::num::NonZeroU32;
+
+/// A multithreaded consumer or producer where the mode is chosen at runtime.
+///
+/// This is useful when the mode comes from a configuration, as every channel has the same type.
+/// Every operation on the head and tail checks which mode is used first, so this costs a branch
+/// per operation compared to using the mode directly.
+///
+/// [`Single`](crate::modes::Single) can't be chosen, as that mode must not be shared between
+/// threads and the mode is only known at runtime. Use [`Multi`] instead.
+///
+/// ```
+/// use ringbeam::custom::{bounded_with_config, modes::{DynMode, DynModeSettings, SpinLimit}};
+/// let (sender, receiver) = bounded_with_config::<8, u8, DynMode, DynMode>(
+///     DynModeSettings::HeadTailSync(Default::default()),
+///     DynModeSettings::Multi(SpinLimit::new(0)),
+/// );
+/// assert_eq!(sender.try_send(1), Ok(None));
+/// assert_eq!(receiver.try_recv(), Ok(1));
+/// ```
+#[cfg_attr(
+    feature = "_shuttle",
+    expect(
+        clippy::large_enum_variant,
+        reason = "The variants only differ this much with the atomics of shuttle, and boxing would add an indirection to every operation"
+    )
+)]
+pub enum DynMode {
+    /// See [`Multi`].
+    Multi(Multi),
+    /// See [`HeadTailSync`].
+    HeadTailSync(HeadTailSync),
+    /// See [`RelaxedTailSync`].
+    RelaxedTailSync(RelaxedTailSync),
+}
+
+/// The mode of a [`DynMode`] together with its settings.
+///
+/// Pass this to [`bounded_with_config`](crate::custom::bounded_with_config) to configure it.
+///
+/// The default is [`Multi`] with the default [`SpinLimit`].
+#[derive(Debug, Copy, Clone)]
+pub enum DynModeSettings {
+    /// Use [`Multi`] with the given [`SpinLimit`].
+    Multi(SpinLimit),
+    /// Use [`HeadTailSync`] with the given [`Fairness`].
+    HeadTailSync(Fairness),
+    /// Use [`RelaxedTailSync`] with the given [`MaxHeadTailDistance`].
+    RelaxedTailSync(MaxHeadTailDistance),
+}
+
+impl Default for DynModeSettings {
+    #[inline]
+    fn default() -> Self {
+        Self::Multi(SpinLimit::default())
+    }
+}
+
+impl Default for DynMode {
+    #[inline]
+    fn default() -> Self {
+        Self::new_with(DynModeSettings::default())
+    }
+}
+
+impl Mode for DynMode {
+    type Settings = DynModeSettings;
+
+    #[inline]
+    fn new_with(settings: Self::Settings) -> Self {
+        match settings {
+            DynModeSettings::Multi(spin_limit) => Self::Multi(Multi::new_with(spin_limit)),
+            DynModeSettings::HeadTailSync(fairness) => {
+                Self::HeadTailSync(HeadTailSync::new_with(fairness))
+            }
+            DynModeSettings::RelaxedTailSync(htd_max) => {
+                Self::RelaxedTailSync(RelaxedTailSync::new_with(htd_max))
+            }
+        }
+    }
+}
+
+impl ModeInner for DynMode {
+    #[inline]
+    fn move_head<const IS_PROD: bool, const EXACT: bool, const CONTIGUOUS: bool, Other: Mode>(
+        &self,
+        other: &Other,
+        expected: NonZeroU32,
+        mask: u32,
+    ) -> Result<Claim, Error> {
+        match self {
+            Self::Multi(mode) => {
+                mode.move_head::<IS_PROD, EXACT, CONTIGUOUS, Other>(other, expected, mask)
+            }
+            Self::HeadTailSync(mode) => {
+                mode.move_head::<IS_PROD, EXACT, CONTIGUOUS, Other>(other, expected, mask)
+            }
+            Self::RelaxedTailSync(mode) => {
+                mode.move_head::<IS_PROD, EXACT, CONTIGUOUS, Other>(other, expected, mask)
+            }
+        }
+    }
+
+    #[inline]
+    fn update_tail(&self, claim: Claim, mask: u32) {
+        match self {
+            Self::Multi(mode) => mode.update_tail(claim, mask),
+            Self::HeadTailSync(mode) => mode.update_tail(claim, mask),
+            Self::RelaxedTailSync(mode) => mode.update_tail(claim, mask),
+        }
+    }
+
+    #[inline]
+    fn load_tail(&self, ordering: Ordering) -> u32 {
+        match self {
+            Self::Multi(mode) => mode.load_tail(ordering),
+            Self::HeadTailSync(mode) => mode.load_tail(ordering),
+            Self::RelaxedTailSync(mode) => mode.load_tail(ordering),
+        }
+    }
+
+    #[inline]
+    fn load_head(&self, ordering: Ordering) -> u32 {
+        match self {
+            Self::Multi(mode) => mode.load_head(ordering),
+            Self::HeadTailSync(mode) => mode.load_head(ordering),
+            Self::RelaxedTailSync(mode) => mode.load_head(ordering),
+        }
+    }
+
+    #[inline]
+    fn mark_finished(&self) {
+        match self {
+            Self::Multi(mode) => mode.mark_finished(),
+            Self::HeadTailSync(mode) => mode.mark_finished(),
+            Self::RelaxedTailSync(mode) => mode.mark_finished(),
+        }
+    }
+
+    #[inline]
+    unsafe fn clear_poison<const IS_PROD: bool>(&self) -> u32 {
+        // SAFETY: Guaranteed by the caller
+        unsafe {
+            match self {
+                Self::Multi(mode) => mode.clear_poison::<IS_PROD>(),
+                Self::HeadTailSync(mode) => mode.clear_poison::<IS_PROD>(),
+                Self::RelaxedTailSync(mode) => mode.clear_poison::<IS_PROD>(),
+            }
+        }
+    }
+
+    #[inline]
+    fn is_finished(&self) -> bool {
+        match self {
+            Self::Multi(mode) => mode.is_finished(),
+            Self::HeadTailSync(mode) => mode.is_finished(),
+            Self::RelaxedTailSync(mode) => mode.is_finished(),
+        }
+    }
+}
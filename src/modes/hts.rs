@@ -1,154 +1,285 @@
 //! Implementation for a multithreaded consumer or producer that only allows one access at a time.
 
 use crate::{
-    Error,
-    modes::{Claim, Mode, ModeInner, calculate_available},
+    Backoff, Error,
+    modes::{Advance, Claim, HeadGen, Mode, ModeInner, Rewind, calculate_available},
     std::{
-        hint::{cold_path, spin_loop},
+        const_fn_unless_loom,
+        hint::cold_path,
         sync::atomic::{
-            AtomicU64, Ordering,
-            Ordering::{Acquire, Relaxed, Release},
+            AtomicU32, AtomicU64, Ordering,
+            Ordering::{AcqRel, Acquire, Relaxed, Release},
         },
     },
 };
 use core::num::NonZeroU32;
 
 /// A multithreaded consumer or producer that only allows one access at a time.
-#[derive(Default)]
 pub struct HeadTailSync {
-    /// The encoded value of [`HeadTail`].
-    inner: AtomicU64,
+    /// The current head, see [`HeadGen`].
+    ///
+    /// A claim is in progress while the head differs from the tail. Only the thread with the claim
+    /// moves the head and tail, until they're the same again.
+    head: AtomicU64,
+    /// The current tail.
+    tail: AtomicU32,
+    /// Are the threads served in the order they arrived, see [`Fairness::Fifo`].
+    fifo: bool,
+    /// The next ticket to hand out to an arriving thread.
+    next_ticket: AtomicU32,
+    /// The ticket of the thread that may move the head.
+    now_serving: AtomicU32,
 }
 
-#[derive(Copy, Clone)]
-struct HeadTail {
-    /// The head position.
-    head: u32,
-    /// The tail position.
-    tail: u32,
-}
-impl From<u64> for HeadTail {
-    #[inline]
-    fn from(value: u64) -> Self {
-        let head = (value >> 32) as u32;
-        let tail = (value & 0xFFFF_FFFF) as u32;
-        Self { head, tail }
-    }
-}
-impl From<HeadTail> for u64 {
-    #[expect(
-        clippy::use_self,
-        reason = "It's clearer with the explicit integer type"
-    )]
-    #[inline]
-    fn from(value: HeadTail) -> Self {
-        ((value.head as u64) << 32) | value.tail as u64
-    }
+/// The order in which threads waiting for a [`HeadTailSync`] get their claim.
+///
+/// Pass this to [`bounded_with_config`](crate::custom::bounded_with_config) to configure it.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Fairness {
+    /// Whichever thread wins the race gets the next claim.
+    ///
+    /// This is the fastest, but under contention a thread can lose the race many times in a row.
+    #[default]
+    Unfair,
+    /// Threads get their claim in the order they arrived.
+    ///
+    /// Every thread takes a ticket, and waits until its ticket is served. This bounds how long a
+    /// thread waits, at the cost of an extra atomic operation per claim and threads not being
+    /// able to skip a thread that is descheduled.
+    Fifo,
 }
 
 impl HeadTailSync {
-    /// Load the [`HeadTail`] atomically.
-    ///
-    /// See [`AtomicU64::load`]
-    #[inline]
-    fn load(&self, order: Ordering) -> HeadTail {
-        self.inner.load(order).into()
+    const_fn_unless_loom! {
+        /// Create a new headtail that serves the threads with `fairness`.
+        ///
+        /// To configure this when creating a channel, pass the [`Fairness`] to
+        /// [`bounded_with_config`](crate::custom::bounded_with_config).
+        #[must_use]
+        #[inline]
+        pub fn new(fairness: Fairness) -> Self {
+            Self {
+                head: AtomicU64::new(0),
+                tail: AtomicU32::new(0),
+                fifo: matches!(fairness, Fairness::Fifo),
+                next_ticket: AtomicU32::new(0),
+                now_serving: AtomicU32::new(0),
+            }
+        }
     }
 
-    /// Store the [`HeadTail`] atomically.
+    /// Load the [`HeadGen`] of the head atomically.
     ///
-    /// This **overwrites** the current value.
+    /// See [`AtomicU64::load`]
     #[inline]
-    fn store(&self, val: HeadTail, order: Ordering) {
-        self.inner.store(val.into(), order);
+    fn load(&self, order: Ordering) -> HeadGen {
+        self.head.load(order).into()
     }
 
-    /// Store a [`HeadTail`] value if the current value is the same as `current`.
+    /// Take a ticket and wait until it's served.
     ///
-    /// See [`AtomicU64::compare_exchange_weak`].
+    /// # Errors
+    /// Returns [`Error::Closed`] if the tail is marked as finished while waiting. That only
+    /// happens if the ring is poisoned, so the ticket that is being served will never finish.
     #[inline]
-    #[expect(clippy::missing_errors_doc, reason = "Not really an error")]
-    fn compare_exchange_weak(
-        &self,
-        current: HeadTail,
-        new: HeadTail,
-        success: Ordering,
-        failure: Ordering,
-    ) -> Result<HeadTail, HeadTail> {
-        self.inner
-            .compare_exchange_weak(current.into(), new.into(), success, failure)
-            .map(HeadTail::from)
-            .map_err(HeadTail::from)
+    fn wait_for_turn(&self) -> Result<(), Error> {
+        let ticket = self.next_ticket.fetch_add(1, Relaxed);
+        let mut backoff = Backoff::new();
+        while self.now_serving.load(Acquire) != ticket {
+            if self.is_finished() {
+                cold_path();
+                return Err(Error::Closed);
+            }
+            backoff.snooze();
+        }
+        Ok(())
     }
-}
-
-impl Mode for HeadTailSync {
-    type Settings = ();
 
+    /// Serve the next ticket, if the threads are served in order.
     #[inline]
-    fn new_with(_settings: Self::Settings) -> Self {
-        Self::default()
+    fn next_turn(&self) {
+        if self.fifo {
+            self.now_serving.fetch_add(1, Release);
+        }
     }
-}
 
-impl ModeInner for HeadTailSync {
-    fn move_head<const N: usize, const IS_PROD: bool, const EXACT: bool, Other: Mode>(
+    /// Move the head as soon as the previous claim is returned.
+    ///
+    /// See [`ModeInner::move_head`].
+    ///
+    /// # Errors
+    /// The same as [`ModeInner::move_head`].
+    fn move_head_unordered<
+        const IS_PROD: bool,
+        const EXACT: bool,
+        const CONTIGUOUS: bool,
+        Other: Mode,
+    >(
         &self,
         other: &Other,
         expected: NonZeroU32,
+        mask: u32,
     ) -> Result<Claim, Error> {
         // Get the current head
         let mut old = self.load(Acquire);
+        let mut backoff = Backoff::new();
 
         loop {
-            while old.head != old.tail {
-                spin_loop();
+            // Sync with update_tail Release, and the previous claim of the head
+            let mut tail = self.tail.load(Acquire);
+            while old.pos != tail {
+                // The tail is only marked as finished while another thread holds a claim if the
+                // ring is poisoned, that claim will never be returned.
+                if tail & 0x8000_0000 != 0 {
+                    cold_path();
+                    return Err(Error::Closed);
+                }
+                backoff.snooze();
                 old = self.load(Acquire);
+                tail = self.tail.load(Acquire);
             }
 
-            let other_tail = other.load_tail(Relaxed);
+            // Sync with update_tail Release (github.com/DPDK/dpdk/commit/9ed8770)
+            let other_tail = other.load_tail(Acquire);
 
-            let available =
-                calculate_available::<N, IS_PROD, EXACT>(old.head, other_tail, expected)?;
+            let (available, remaining) = calculate_available::<IS_PROD, EXACT, CONTIGUOUS>(
+                old.pos, other_tail, expected, mask,
+            )?;
 
-            let new = HeadTail {
-                head: old.head.wrapping_add(available.get()) & (N as u32 - 1),
-                tail: old.tail,
-            };
+            let new_head = old.pos.wrapping_add(available.get()) & mask;
 
-            match self.compare_exchange_weak(old, new, Acquire, Acquire) {
-                Ok(_) => return Ok(Claim::many(available, old.tail)),
+            // Release the tail we saw, so a thread that sees the new head can't pair it with an
+            // older tail and think the claim is already returned.
+            match self.head.compare_exchange_weak(
+                old.into(),
+                old.next(new_head).into(),
+                AcqRel,
+                Acquire,
+            ) {
+                Ok(_) => return Ok(Claim::many(available, old.pos, remaining).tag(new_head)),
                 Err(new_old) => {
                     cold_path();
-                    old = new_old;
+                    old = HeadGen::from(new_old);
                 }
             }
         }
     }
+}
 
+impl Default for HeadTailSync {
     #[inline]
-    fn update_tail<const N: usize>(&self, claim: Claim) {
-        let new_tail = claim.new_tail::<N>();
-        let new = HeadTail {
-            head: new_tail,
-            tail: new_tail,
-        };
-        self.store(new, Release);
+    fn default() -> Self {
+        Self::new(Fairness::default())
+    }
+}
+
+impl Mode for HeadTailSync {
+    type Settings = Fairness;
+
+    #[inline]
+    fn new_with(settings: Self::Settings) -> Self {
+        Self::new(settings)
+    }
+}
+
+impl ModeInner for HeadTailSync {
+    fn move_head<const IS_PROD: bool, const EXACT: bool, const CONTIGUOUS: bool, Other: Mode>(
+        &self,
+        other: &Other,
+        expected: NonZeroU32,
+        mask: u32,
+    ) -> Result<Claim, Error> {
+        if !self.fifo {
+            return self
+                .move_head_unordered::<IS_PROD, EXACT, CONTIGUOUS, Other>(other, expected, mask);
+        }
+        self.wait_for_turn()?;
+        // It's our turn, so the previous claim is already returned
+        let result =
+            self.move_head_unordered::<IS_PROD, EXACT, CONTIGUOUS, Other>(other, expected, mask);
+        if result.is_err() {
+            cold_path();
+            self.next_turn();
+        }
+        result
+    }
+
+    #[inline]
+    fn update_tail(&self, claim: Claim, mask: u32) {
+        #[cfg(feature = "debug_claims")]
+        claim.check_in_order(self.tail.load(Relaxed));
+        // The head is already past the claim, so this lets the next thread claim
+        let new_tail = claim.new_tail(mask);
+        self.tail.store(new_tail, Release);
+        self.next_turn();
     }
 
     #[inline]
     fn load_tail(&self, ordering: Ordering) -> u32 {
-        self.load(ordering).tail
+        self.tail.load(ordering)
+    }
+
+    #[inline]
+    fn load_head(&self, ordering: Ordering) -> u32 {
+        self.load(ordering).pos
     }
 
     #[inline]
     fn mark_finished(&self) {
-        let res = self.inner.fetch_or(0x8000_0000, Relaxed);
+        let res = self.tail.fetch_or(0x8000_0000, Release);
         assert_eq!(res & 0x8000_0000, 0, "Tail was already marked as finished!");
     }
 
+    #[inline]
+    unsafe fn clear_poison<const IS_PROD: bool>(&self) -> u32 {
+        let old = self.load(Relaxed);
+        let position = if IS_PROD {
+            self.tail.load(Acquire) & 0x7FFF_FFFF
+        } else {
+            old.pos
+        };
+        self.head.store(old.next(position).into(), Relaxed);
+        self.tail.store(position, Release);
+        // Threads that gave up their ticket because of the poison never got served
+        self.now_serving
+            .store(self.next_ticket.load(Relaxed), Release);
+        position
+    }
+
     #[inline]
     fn is_finished(&self) -> bool {
-        self.inner.load(Relaxed) & 0x8000_0000 != 0
+        self.tail.load(Acquire) & 0x8000_0000 != 0
+    }
+}
+
+impl Rewind for HeadTailSync {
+    #[inline]
+    fn rewind(&self, claim: Claim, used: u32, mask: u32) {
+        // As long as we hold the claim, no other thread can move the head.
+        #[cfg(feature = "debug_claims")]
+        claim.check_in_order(self.tail.load(Relaxed));
+        let entries = claim.entries();
+        let new = claim.rewind(used, mask);
+        // Move the tail first, so the head and tail only match once both are moved
+        self.tail.store(new, Release);
+        // Otherwise the head is already at the end of the claim
+        if used != entries {
+            let old = self.load(Relaxed);
+            self.head.store(old.next(new).into(), Release);
+        }
+        self.next_turn();
+    }
+}
+
+impl Advance for HeadTailSync {
+    #[inline]
+    fn advance(&self, claim: &mut Claim, used: u32, mask: u32) {
+        // As long as we hold the claim, no other thread can move the head.
+        #[cfg(feature = "debug_claims")]
+        claim.check_in_order(self.tail.load(Relaxed));
+        // The rest of the claim is still before the head, so it's not the turn of the next
+        // thread yet
+        let new_tail = claim.split_off_front(used, mask);
+        self.tail.store(new_tail, Release);
     }
 }
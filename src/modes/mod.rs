@@ -10,14 +10,16 @@ use core::{
     num::NonZeroU32,
 };
 
+mod dynamic;
 mod hts;
 mod multi;
 mod rts;
 mod single;
 
-pub use hts::HeadTailSync;
-pub use multi::Multi;
-pub use rts::RelaxedTailSync;
+pub use dynamic::{DynMode, DynModeSettings};
+pub use hts::{Fairness, HeadTailSync};
+pub use multi::{Multi, SpinLimit};
+pub use rts::{MaxHeadTailDistance, RelaxedTailSync};
 pub use single::Single;
 
 /// The synchronisation mode of a [`Sender`](crate::custom::Sender) or [`Receiver`](crate::custom::Receiver).
@@ -36,7 +38,8 @@ pub use single::Single;
 /// The different modes allows the user to choose the synchronisation method that is best for their
 /// specific situation.
 ///
-/// There are currently four modes:
+/// There are currently four modes, and [`DynMode`] to choose between the multithreaded ones at
+/// runtime:
 /// - [`Single`]: Only allows singlethreaded access to a 'headtail'.
 /// - [`Multi`]: Allows multithreaded access to a 'headtail'. Every thread spins on the head to acquire
 ///   slots. After they're done they spin on the tail to update past their slots.
@@ -46,53 +49,113 @@ pub use single::Single;
 pub trait Mode: ModeInner {
     /// The settings for this mode.
     ///
-    /// Currently only relevant for [`RelaxedTailSync`].
+    /// [`RelaxedTailSync`] uses [`MaxHeadTailDistance`], [`HeadTailSync`] uses [`Fairness`],
+    /// [`Multi`] uses [`SpinLimit`], and [`DynMode`] uses [`DynModeSettings`]. For [`Single`] the
+    /// settings are `()`.
     type Settings: Default;
 
     /// Create the mode with custom settings.
     fn new_with(settings: Self::Settings) -> Self;
 }
 
+/// A [`Mode`] where a claim can be given back to the ring.
+///
+/// This is only possible when no other thread can move the head while the claim is held,
+/// otherwise the returned entries would be stranded beyond the head of the other thread.
+/// It's implemented for [`Single`], which only has one owner, and [`HeadTailSync`], which
+/// only allows one claim at a time.
+pub trait Rewind: Mode {
+    /// Return the claim, moving the tail past the first `used` entries and the head back to the
+    /// tail.
+    ///
+    /// The remaining entries of the claim will be part of the next claim again. `mask` is the
+    /// size of the ring minus one.
+    fn rewind(&self, claim: Claim, used: u32, mask: u32);
+}
+
+/// A [`Mode`] where the tail can be moved past part of a claim, while the rest stays claimed.
+///
+/// This is possible when the tail only moves past the claims in order. It's implemented for
+/// [`Single`], [`Multi`], and [`HeadTailSync`]. [`RelaxedTailSync`] counts the returned claims
+/// instead, so it can't return part of one.
+pub trait Advance: Mode {
+    /// Move the tail past the first `used` entries of the claim, which keeps the other entries.
+    /// `mask` is the size of the ring minus one.
+    ///
+    /// For [`Multi`] this waits until the claims before it are returned.
+    ///
+    /// # Panics
+    /// Panics if `used` is not less than the entries of the claim.
+    fn advance(&self, claim: &mut Claim, used: u32, mask: u32);
+}
+
 /// Represents the head and tail.
 ///
 /// Can be implemented in various ways, see [`Mode`].
 pub trait ModeInner: Default {
     /// Move the head.
     ///
+    /// `mask` is the size of the ring minus one, the positions in the ring are masked with it.
+    ///
     /// # Generics
-    /// - `N`: The ring size.
     /// - `IS_PROD`: Is the headtail a producer.
     /// - `EXACT`: Does the caller want exactly `expected` items, or is fewer also fine.
+    /// - `CONTIGUOUS`: Stop the claim at the end of the ring, so it doesn't wrap around.
     /// - `Other`: The mode of the other headtail on the ring.
     ///
     /// # Errors
     /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
     /// one of those states. The last one indicates that retrying can be successful. If `EXACT` it
     /// can also return [`Error::NotEnoughSpace`]/[`Error::NotEnoughItems`],
-    /// which can also be successful on a retry. If `IS_PROD` it can also return [`Error::NotEnoughItemsAndClosed`]
-    /// which can be successful on a retry with `EXACT: false`.
-    fn move_head<const N: usize, const IS_PROD: bool, const EXACT: bool, Other: Mode>(
+    /// which can also be successful on a retry. If not `IS_PROD` it can also return
+    /// [`Error::NotEnoughItemsAndClosed`] which can be successful on a retry with `EXACT: false`.
+    fn move_head<const IS_PROD: bool, const EXACT: bool, const CONTIGUOUS: bool, Other: Mode>(
         &self,
         other: &Other,
         expected: NonZeroU32,
+        mask: u32,
     ) -> Result<Claim, Error>;
 
-    /// Return the claim and move the tail forward.
-    fn update_tail<const N: usize>(&self, claim: Claim);
+    /// Return the claim and move the tail forward, `mask` is the size of the ring minus one.
+    fn update_tail(&self, claim: Claim, mask: u32);
 
     /// Load the tail value with the specified ordering.
     #[must_use]
     fn load_tail(&self, ordering: Ordering) -> u32;
 
+    /// Load the head position with the specified ordering.
+    ///
+    /// Unlike the tail, the head is never marked as finished so this is always a position in the
+    /// ring. It's only a snapshot, another thread can move the head right after it's loaded.
+    #[must_use]
+    fn load_head(&self, ordering: Ordering) -> u32;
+
     /// Mark this head as finished.
     ///
     /// This should only be called by the last owner as indicated by [`Last::InCategory`](crate::ring::active::Last),
-    /// or when the ring is poisoned.
+    /// or when the ring is poisoned. The last owner calls this after its final update of the tail,
+    /// so every thread that loads the marked tail also sees all the entries before it. Once the
+    /// other side has taken those entries, [`calculate_available`] returns [`Error::Closed`]
+    /// instead of [`Error::Empty`]/[`Error::Full`].
     fn mark_finished(&self);
 
+    /// Clear the finished mark and give up the claims that were never returned.
+    ///
+    /// This is used to recover a poisoned ring. If `IS_PROD` the claimed entries were never
+    /// published, so the head moves back to the tail. Otherwise the claimed entries could already
+    /// be taken, so the tail moves forward to the head.
+    ///
+    /// Returns the new position of the head and tail.
+    ///
+    /// # Safety
+    /// No other thread may access the headtail, and nobody may hold a claim of it.
+    unsafe fn clear_poison<const IS_PROD: bool>(&self) -> u32;
+
     /// Have all owners of the head finished.
     ///
-    /// If this is `true` then the head won't move anymore.
+    /// If this is `true` then the head won't move anymore. The load synchronizes with
+    /// [`mark_finished`](Self::mark_finished), so the ring can be deallocated after seeing both
+    /// heads finished.
     #[must_use]
     fn is_finished(&self) -> bool;
 }
@@ -110,22 +173,91 @@ pub struct Claim {
     entries: NonZeroU32,
     /// The place in the ring where the claim starts.
     start: u32,
+    /// The amount of entries that were available but not claimed.
+    remaining: u32,
+    /// The head after it was moved past the claim, see [`Claim::tag`].
+    #[cfg(feature = "debug_claims")]
+    end: u32,
 }
 
 impl Debug for Claim {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         f.write_fmt(format_args!(
-            "Claim {{ entries: {}, start: {}}}",
-            self.entries, self.start
+            "Claim {{ entries: {}, start: {}, remaining: {}}}",
+            self.entries, self.start, self.remaining
         ))
     }
 }
 
 impl Claim {
-    /// A claim for `n` entries at `start`
+    /// A claim for `n` entries at `start`, with `remaining` entries left available.
     #[inline]
-    pub const fn many(entries: NonZeroU32, start: u32) -> Self {
-        Self { entries, start }
+    pub const fn many(entries: NonZeroU32, start: u32, remaining: u32) -> Self {
+        Self {
+            entries,
+            start,
+            remaining,
+            #[cfg(feature = "debug_claims")]
+            end: start,
+        }
+    }
+
+    /// Tag the claim with the position `end` the head was moved to.
+    ///
+    /// Returning the claim asserts that it still ends there. This catches differences between the
+    /// wrap arithmetic of moving the head and updating the tail.
+    #[cfg(feature = "debug_claims")]
+    #[inline]
+    pub const fn tag(mut self, end: u32) -> Self {
+        self.end = end;
+        self
+    }
+
+    /// Tag the claim with the position `end` the head was moved to.
+    ///
+    /// This does nothing without the `debug_claims` feature.
+    #[cfg(not(feature = "debug_claims"))]
+    #[inline]
+    pub const fn tag(self, _end: u32) -> Self {
+        self
+    }
+
+    /// Assert that the claim is returned in order, so it starts at the current `tail`.
+    ///
+    /// This is for modes where the claims are returned in the order they were handed out. The
+    /// finished mark of `tail` is ignored.
+    ///
+    /// # Panics
+    /// Panics if the claim doesn't start at `tail`.
+    #[cfg(feature = "debug_claims")]
+    #[track_caller]
+    #[inline]
+    pub fn check_in_order(&self, tail: u32) {
+        assert_eq!(
+            self.start,
+            tail & 0x7FFF_FFFF,
+            "{self:?} is returned out of order"
+        );
+    }
+
+    /// Assert that the claim lies between `tail` and `head`.
+    ///
+    /// This is for modes where the claims can be returned in any order. The finished mark of
+    /// `tail` is ignored.
+    ///
+    /// # Panics
+    /// Panics if part of the claim is outside of `tail..head`.
+    #[cfg(feature = "debug_claims")]
+    #[track_caller]
+    #[inline]
+    pub fn check_claimed(&self, tail: u32, head: u32, mask: u32) {
+        let tail = tail & 0x7FFF_FFFF;
+        let offset = self.start.wrapping_sub(tail) & mask;
+        let claimed = head.wrapping_sub(tail) & mask;
+        assert!(
+            offset + self.entries.get() <= claimed,
+            "{self:?} is outside of the claimed entries between {tail} and {head}"
+        );
     }
 
     /// The amount of entries in the claim.
@@ -144,11 +276,71 @@ impl Claim {
         self.start
     }
 
-    /// Calculate the new location of the tail.
+    /// The amount of entries that were available when claiming, but not claimed.
+    ///
+    /// For a producer this is the room left in the ring, for a consumer the values left. It's
+    /// based on the tail of the other side when claiming, so it can already be outdated.
+    #[must_use]
+    #[inline]
+    pub const fn remaining(&self) -> u32 {
+        self.remaining
+    }
+
+    /// Calculate the new location of the tail, `mask` is the size of the ring minus one.
+    ///
+    /// # Panics
+    /// With the `debug_claims` feature, panics if the claim doesn't end at its [`tag`](Self::tag).
+    #[must_use]
+    #[inline]
+    pub const fn new_tail(self, mask: u32) -> u32 {
+        let new = self.start.wrapping_add(self.entries.get()) & mask;
+        #[cfg(feature = "debug_claims")]
+        assert!(
+            new == self.end,
+            "Claim doesn't end where the head was moved to"
+        );
+        let _dont_drop_self = ManuallyDrop::new(self);
+        new
+    }
+
+    /// Split off the first `used` entries, the claim keeps the entries after them.
+    ///
+    /// Returns the location of the tail after the split off entries.
+    ///
+    /// # Panics
+    /// Panics if `used` is not less than the entries of the claim.
+    #[must_use]
+    #[inline]
+    pub fn split_off_front(&mut self, used: u32, mask: u32) -> u32 {
+        self.entries = self
+            .entries
+            .get()
+            .checked_sub(used)
+            .and_then(NonZeroU32::new)
+            .expect("Can't split off every entry of a claim");
+        self.start = self.start.wrapping_add(used) & mask;
+        self.start
+    }
+
+    /// Calculate the location of the head and tail when giving back the claim after using the
+    /// first `used` entries.
+    ///
+    /// # Panics
+    /// With the `debug_claims` feature, panics if the claim doesn't end at its [`tag`](Self::tag).
     #[must_use]
     #[inline]
-    pub const fn new_tail<const N: usize>(self) -> u32 {
-        let new = self.start.wrapping_add(self.entries.get()) & (N as u32 - 1);
+    pub fn rewind(self, used: u32, mask: u32) -> u32 {
+        debug_assert!(
+            used <= self.entries.get(),
+            "Used more entries than were claimed"
+        );
+        #[cfg(feature = "debug_claims")]
+        assert_eq!(
+            self.start.wrapping_add(self.entries.get()) & mask,
+            self.end,
+            "{self:?} doesn't end where the head was moved to"
+        );
+        let new = self.start.wrapping_add(used) & mask;
         let _dont_drop_self = ManuallyDrop::new(self);
         new
     }
@@ -160,61 +352,127 @@ impl Drop for Claim {
         // The Claim should always be consumed by `new_tail` if it has at least one entry.
         // `new_tail` doesn't cause a drop because it uses ManuallyDrop.
         assert!(
-            std::thread::panicking(),
+            crate::std::thread::panicking(),
             "Claim was dropped before being returned"
         );
     }
 }
 
+/// The head position together with a generation, packed in a [`Single`], [`Multi`], or
+/// [`HeadTailSync`].
+///
+/// The generation is incremented every time the head moves. Without it, a thread that's
+/// descheduled between loading the head and the compare-exchange could succeed after the other
+/// threads moved the head a multiple of the ring size forward, as the position would be the same.
+/// It would then claim entries based on a tail that is no longer current (ABA).
+#[derive(Copy, Clone)]
+struct HeadGen {
+    /// The head position.
+    pos: u32,
+    /// How often the head was moved, wrapping around.
+    generation: u32,
+}
+impl From<u64> for HeadGen {
+    #[inline]
+    fn from(value: u64) -> Self {
+        let pos = (value >> 32) as u32;
+        let generation = (value & 0xFFFF_FFFF) as u32;
+        Self { pos, generation }
+    }
+}
+impl From<HeadGen> for u64 {
+    #[expect(
+        clippy::use_self,
+        reason = "It's clearer with the explicit integer type"
+    )]
+    #[inline]
+    fn from(value: HeadGen) -> Self {
+        ((value.pos as u64) << 32) | value.generation as u64
+    }
+}
+
+impl HeadGen {
+    /// The head moved to `pos`, in the next generation.
+    #[inline]
+    const fn next(self, pos: u32) -> Self {
+        Self {
+            pos,
+            generation: self.generation.wrapping_add(1),
+        }
+    }
+}
+
 /// Calculate the available entries (either occupied or empty).
 ///
+/// Returns the amount of entries to claim, and the amount of available entries that are left.
+///
+/// `mask` is the size of the ring minus one.
+///
 /// # Generics
-/// - `N`: The ring size.
-/// - `M`: The ring size when calculating for a producer, 0 when calculating for a consumer.
+/// - `IS_PROD`: Is the head a producer head.
+/// - `EXACT`: Does the caller want exactly `expected` items, or is fewer also fine.
+/// - `CONTIGUOUS`: Claim at most the entries up to the end of the ring.
 ///
 /// # Errors
 /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
 /// one of those states. The last one indicates that retrying can be successful. If `EXACT` it can
 /// also return [`Error::NotEnoughSpace`]/[`Error::NotEnoughItems`], which can also be successful on
-/// a retry. If `IS_PROD` it can also return [`Error::NotEnoughItemsAndClosed`] which can be successful
-/// on a retry with `EXACT: false`.
-fn calculate_available<const N: usize, const IS_PROD: bool, const EXACT: bool>(
+/// a retry. If not `IS_PROD` it can also return [`Error::NotEnoughItemsAndClosed`] which can be
+/// successful on a retry with `EXACT: false`.
+fn calculate_available<const IS_PROD: bool, const EXACT: bool, const CONTIGUOUS: bool>(
     head: u32,
     tail: u32,
     expected: NonZeroU32,
-) -> Result<NonZeroU32, Error> {
-    let start = if IS_PROD { N as u32 - 1 } else { 0 };
-    // When this is a producer head, check that there still are consumers
-    if IS_PROD && tail & 0x8000_0000 != 0 {
-        return Err(Error::Closed);
-    }
-    if head & 0x8000_0000 != 0 {
-        return Err(Error::Poisoned);
-    }
+    mask: u32,
+) -> Result<(NonZeroU32, u32), Error> {
+    let start = if IS_PROD { mask } else { 0 };
     // Clear the MSB in case the tail is already dropped
-    let available = start.wrapping_add(tail & 0x7FFF_FFFF).wrapping_sub(head) & (N as u32 - 1);
-    if available == 0 {
+    let available = start.wrapping_add(tail & 0x7FFF_FFFF).wrapping_sub(head) & mask;
+    // A producer head also fails when there are no consumers left, even if there is space
+    if (IS_PROD && tail & 0x8000_0000 != 0)
+        || head & 0x8000_0000 != 0
+        || available == 0
+        || (EXACT && expected.get() > available)
+    {
         cold_path();
-        // Check if the MSB is set, as that indicates the channel is closed on the other side
-        if tail & 0x8000_0000 != 0 {
-            cold_path();
-            Err(Error::Closed)
-        } else if IS_PROD {
-            Err(Error::Full)
-        } else {
-            Err(Error::Empty)
+        Err(unavailable::<IS_PROD>(head, tail, available))
+    } else {
+        let mut claimed =
+            expected.min(NonZeroU32::new(available).unwrap_or_else(|| unreachable!()));
+        if CONTIGUOUS {
+            // The head is a position in the ring, so at least one entry is left before the end
+            let until_end = mask - (head & mask) + 1;
+            claimed = claimed.min(NonZeroU32::new(until_end).unwrap_or_else(|| unreachable!()));
         }
-    } else if EXACT && expected.get() > available {
-        cold_path();
-        if IS_PROD {
-            Err(Error::NotEnoughSpace)
-        } else if tail & 0x8000_0000 != 0 {
-            cold_path();
-            Err(Error::NotEnoughItemsAndClosed)
+        Ok((claimed, available - claimed.get()))
+    }
+}
+
+/// The error for a head that can't claim the entries, see [`calculate_available`].
+///
+/// This is kept out of line so the claim in [`calculate_available`] stays small.
+#[cold]
+#[inline(never)]
+const fn unavailable<const IS_PROD: bool>(head: u32, tail: u32, available: u32) -> Error {
+    // Check if the MSB is set, as that indicates the channel is closed on the other side
+    let closed = tail & 0x8000_0000 != 0;
+    if IS_PROD && closed {
+        Error::Closed
+    } else if head & 0x8000_0000 != 0 {
+        Error::Poisoned
+    } else if available == 0 {
+        if closed {
+            Error::Closed
+        } else if IS_PROD {
+            Error::Full
         } else {
-            Err(Error::NotEnoughItems)
+            Error::Empty
         }
+    } else if IS_PROD {
+        Error::NotEnoughSpace
+    } else if closed {
+        Error::NotEnoughItemsAndClosed
     } else {
-        Ok(expected.min(NonZeroU32::new(available).unwrap_or_else(|| unreachable!())))
+        Error::NotEnoughItems
     }
 }
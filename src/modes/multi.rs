@@ -1,12 +1,18 @@
 //! Implementation for a multithreaded consumer or producer.
 
+#[cfg(all(
+    target_arch = "aarch64",
+    not(any(feature = "_loom", feature = "_shuttle"))
+))]
+use crate::std::thread::yield_now;
 use crate::{
-    Error,
-    modes::{Claim, Mode, ModeInner, calculate_available},
+    Backoff, Error,
+    modes::{Advance, Claim, HeadGen, Mode, ModeInner, calculate_available},
     std::{
+        const_fn_unless_loom,
         hint::{cold_path, spin_loop},
         sync::atomic::{
-            AtomicU32, Ordering,
+            AtomicU32, AtomicU64, Ordering,
             Ordering::{Acquire, Relaxed, Release},
             fence,
         },
@@ -14,32 +20,111 @@ use crate::{
 };
 use core::num::NonZeroU32;
 
+/// How often a consumer rereads the producer tail before returning [`Error::Empty`].
+///
+/// A consumer only retries while the producer head is ahead of the producer tail, which means a
+/// producer has claimed entries but not updated its tail yet. An idle ring returns right away.
+/// The retries are bounded, so a producer that takes longer, for example because it's preempted,
+/// still makes the consumer return [`Error::Empty`] although values are on their way, and so does
+/// a producer that hasn't moved its head yet. [`Error::Empty`] only means that no values were
+/// visible when the consumer looked.
+const EMPTY_RETRIES: u32 = 4;
+
 /// A multithreaded consumer or producer.
-#[derive(Default)]
+///
+/// The head and tail must stay laid out the same as in [`Single`](crate::modes::Single).
+#[repr(C)]
 pub struct Multi {
-    /// The current head.
-    head: AtomicU32,
+    /// The current head, see [`HeadGen`].
+    head: AtomicU64,
     /// The current tail.
     tail: AtomicU32,
+    /// How long to spin while waiting for earlier claims, see [`SpinLimit`].
+    ///
+    /// This is after the head and tail, so it's not part of the layout shared with
+    /// [`Single`](crate::modes::Single). Only the threads of this side read it.
+    spin_limit: u32,
+}
+
+/// How long a [`Multi`] spins while waiting for earlier claims before it yields.
+///
+/// A claim can only be returned after the claims before it, so a thread waits for the threads
+/// that claimed earlier. If such a thread is preempted, spinning just burns the rest of the time
+/// slice. After the spin limit the waiting thread yields to the scheduler instead, which lets the
+/// preempted thread run again when there are more threads than cores.
+///
+/// The limit is in the steps of a [`Backoff`], see [`Backoff::with_spin_limit`]. A lower limit
+/// yields sooner, which helps when the threads outnumber the cores but costs latency when they
+/// don't. This defaults to the limit of [`Backoff::new`].
+///
+/// ```
+/// use ringbeam::custom::{bounded_with_config, modes::{Multi, SpinLimit}};
+/// let (sender, receiver) =
+///     bounded_with_config::<8, u8, Multi, Multi>(SpinLimit::new(0), SpinLimit::default());
+/// assert_eq!(sender.try_send(1), Ok(None));
+/// assert_eq!(receiver.try_recv(), Ok(1));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SpinLimit(u32);
+impl SpinLimit {
+    /// Create the settings with a spin limit of `spin_limit` steps.
+    #[must_use]
+    #[inline]
+    pub const fn new(spin_limit: u32) -> Self {
+        Self(spin_limit)
+    }
+}
+impl Default for SpinLimit {
+    #[inline]
+    fn default() -> Self {
+        Self(Backoff::SPIN_LIMIT)
+    }
+}
+
+impl Multi {
+    const_fn_unless_loom! {
+        /// Create a new headtail that spins `spin_limit` steps before it yields.
+        ///
+        /// To configure this when creating a channel, pass a [`SpinLimit`] to
+        /// [`bounded_with_config`](crate::custom::bounded_with_config).
+        #[must_use]
+        #[inline]
+        pub fn new(spin_limit: u32) -> Self {
+            Self {
+                head: AtomicU64::new(0),
+                tail: AtomicU32::new(0),
+                spin_limit,
+            }
+        }
+    }
+}
+
+impl Default for Multi {
+    #[inline]
+    fn default() -> Self {
+        Self::new(Backoff::SPIN_LIMIT)
+    }
 }
 
 impl Mode for Multi {
-    type Settings = ();
+    type Settings = SpinLimit;
 
     #[inline]
-    fn new_with(_settings: Self::Settings) -> Self {
-        Self::default()
+    fn new_with(settings: Self::Settings) -> Self {
+        Self::new(settings.0)
     }
 }
 
 impl ModeInner for Multi {
-    fn move_head<const N: usize, const IS_PROD: bool, const EXACT: bool, Other: Mode>(
+    fn move_head<const IS_PROD: bool, const EXACT: bool, const CONTIGUOUS: bool, Other: Mode>(
         &self,
         other: &Other,
         expected: NonZeroU32,
+        mask: u32,
     ) -> Result<Claim, Error> {
         // Get the current head
-        let mut old_head = self.head.load(Relaxed);
+        let mut old = HeadGen::from(self.head.load(Relaxed));
+        let mut retries = if IS_PROD { 0 } else { EMPTY_RETRIES };
 
         loop {
             // Ensure head is read before tail (github.com/DPDK/dpdk/commit/86757c2)
@@ -50,31 +135,48 @@ impl ModeInner for Multi {
             // Sync with update_tail Release (github.com/DPDK/dpdk/commit/9ed8770)
             let other_tail = other.load_tail(Acquire);
 
-            let available =
-                calculate_available::<N, IS_PROD, EXACT>(old_head, other_tail, expected)?;
+            let (available, remaining) = match calculate_available::<IS_PROD, EXACT, CONTIGUOUS>(
+                old.pos, other_tail, expected, mask,
+            ) {
+                Ok(available) => available,
+                Err(Error::Empty)
+                    if retries > 0 && other.load_head(Relaxed) != other_tail & 0x7FFF_FFFF =>
+                {
+                    cold_path();
+                    retries -= 1;
+                    spin_loop();
+                    old = HeadGen::from(self.head.load(Relaxed));
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
 
-            let new_head = old_head.wrapping_add(available.get()) & (N as u32 - 1);
+            let new_head = old.pos.wrapping_add(available.get()) & mask;
 
-            match self
-                .head
-                .compare_exchange_weak(old_head, new_head, Relaxed, Relaxed)
-            {
-                Ok(_) => return Ok(Claim::many(available, old_head)),
-                Err(new_old_head) => {
+            // Release the tail we loaded, so a thread that sees the new head also sees a tail that
+            // is at least as new. Otherwise it could pair the new head with an old tail and claim
+            // entries that aren't there.
+            match self.head.compare_exchange_weak(
+                old.into(),
+                old.next(new_head).into(),
+                Release,
+                Relaxed,
+            ) {
+                Ok(_) => return Ok(Claim::many(available, old.pos, remaining).tag(new_head)),
+                Err(new_old) => {
                     cold_path();
-                    old_head = new_old_head;
+                    old = HeadGen::from(new_old);
                 }
             }
         }
     }
 
     #[inline]
-    fn update_tail<const N: usize>(&self, claim: Claim) {
-        while self.tail.load(Relaxed) != claim.start {
-            // TODO: WFE/SEV optimisation
-            spin_loop();
-        }
-        let new_tail = claim.new_tail::<N>();
+    fn update_tail(&self, claim: Claim, mask: u32) {
+        wait_until_equal(&self.tail, claim.start, self.spin_limit);
+        #[cfg(feature = "debug_claims")]
+        claim.check_claimed(claim.start, self.load_head(Relaxed), mask);
+        let new_tail = claim.new_tail(mask);
         self.tail.store(new_tail, Release);
     }
 
@@ -83,14 +185,122 @@ impl ModeInner for Multi {
         self.tail.load(ordering)
     }
 
+    #[inline]
+    fn load_head(&self, ordering: Ordering) -> u32 {
+        HeadGen::from(self.head.load(ordering)).pos
+    }
+
     #[inline]
     fn mark_finished(&self) {
-        let res = self.tail.fetch_or(0x8000_0000, Relaxed);
+        let res = self.tail.fetch_or(0x8000_0000, Release);
         assert_eq!(res & 0x8000_0000, 0, "Tail was already marked as finished!");
     }
 
+    #[inline]
+    unsafe fn clear_poison<const IS_PROD: bool>(&self) -> u32 {
+        let old = HeadGen::from(self.head.load(Relaxed));
+        let position = if IS_PROD {
+            self.tail.load(Acquire) & 0x7FFF_FFFF
+        } else {
+            old.pos
+        };
+        self.head.store(old.next(position).into(), Relaxed);
+        self.tail.store(position, Release);
+        position
+    }
+
     #[inline]
     fn is_finished(&self) -> bool {
-        self.tail.load(Relaxed) & 0x8000_0000 != 0
+        self.tail.load(Acquire) & 0x8000_0000 != 0
+    }
+}
+
+impl Advance for Multi {
+    #[inline]
+    fn advance(&self, claim: &mut Claim, used: u32, mask: u32) {
+        // The tail moves past the claims in order, and stays at the rest of this claim
+        wait_until_equal(&self.tail, claim.start, self.spin_limit);
+        #[cfg(feature = "debug_claims")]
+        claim.check_claimed(claim.start, self.load_head(Relaxed), mask);
+        let new_tail = claim.split_off_front(used, mask);
+        self.tail.store(new_tail, Release);
+    }
+}
+
+/// Wait until `atomic` has the value `expected`, loading it with [`Acquire`].
+///
+/// The tail is stored by the previous claim, whose entries must be visible before this claim
+/// publishes its own tail. A plain store doesn't continue the release sequence of the previous one,
+/// so the load has to acquire it.
+///
+/// It yields once the backoff reaches `spin_limit`, see [`SpinLimit`].
+#[cfg(not(all(
+    target_arch = "aarch64",
+    not(any(feature = "_loom", feature = "_shuttle"))
+)))]
+#[inline]
+fn wait_until_equal(atomic: &AtomicU32, expected: u32, spin_limit: u32) {
+    let mut backoff = Backoff::with_spin_limit(spin_limit);
+    while atomic.load(Acquire) != expected {
+        backoff.snooze();
+    }
+}
+
+/// Wait until `atomic` has the value `expected`, loading it with [`Acquire`].
+///
+/// Instead of busy spinning, this puts the core in a low-power state with `WFE` until another
+/// core writes to `atomic`. The exclusive load arms the exclusive monitor for the address,
+/// and any store to it clears the monitor which generates the wake-up event. This means the
+/// storing side doesn't need to execute `SEV`, the same approach as `rte_wait_until_equal_32`.
+///
+/// Every `WFE` counts as a spin of a [`Backoff`] with `spin_limit`. After that many it yields
+/// instead, as the store could be from a thread that isn't running.
+#[cfg(all(
+    target_arch = "aarch64",
+    not(any(feature = "_loom", feature = "_shuttle"))
+))]
+#[inline]
+fn wait_until_equal(atomic: &AtomicU32, expected: u32, spin_limit: u32) {
+    /// Load the value with an exclusive load-acquire, arming the exclusive monitor.
+    #[inline]
+    fn load_exclusive(atomic: &AtomicU32) -> u32 {
+        let value: u32;
+        // SAFETY: The pointer is valid and aligned for the lifetime of `atomic`. An exclusive
+        //         load-acquire is a normal acquire load that also arms the exclusive monitor.
+        unsafe {
+            core::arch::asm!(
+                "ldaxr {value:w}, [{addr}]",
+                value = out(reg) value,
+                addr = in(reg) atomic.as_ptr(),
+                // Not `readonly`, as the compiler may not move memory accesses before the acquire
+                options(nostack, preserves_flags)
+            );
+        }
+        value
+    }
+
+    if load_exclusive(atomic) != expected {
+        // Make sure the first `WFE` doesn't block, as the store could have been done already.
+        // SAFETY: `SEVL` only sets the local event register.
+        unsafe {
+            core::arch::asm!("sevl", options(nomem, nostack, preserves_flags));
+        }
+        // The amount of spins of a `Backoff` before it yields, `2^0 + ... + 2^spin_limit`
+        let max_waits = (2_u32 << spin_limit.min(16)) - 1;
+        let mut waits = 0;
+        loop {
+            if waits < max_waits {
+                waits += 1;
+                // SAFETY: `WFE` only waits for an event, which the exclusive monitor will generate.
+                unsafe {
+                    core::arch::asm!("wfe", options(nomem, nostack, preserves_flags));
+                }
+            } else {
+                yield_now();
+            }
+            if load_exclusive(atomic) == expected {
+                break;
+            }
+        }
     }
 }
@@ -1,13 +1,14 @@
 //! Implementation for a multithreaded consumer or producer where the tail is updated by the last thread.
 
 use crate::{
-    Error,
+    Backoff, Error,
     modes::{Claim, Mode, ModeInner, calculate_available},
     std::{
-        hint::{cold_path, spin_loop},
+        const_fn_unless_loom,
+        hint::cold_path,
         sync::atomic::{
             AtomicU64, Ordering,
-            Ordering::{Acquire, Relaxed, Release},
+            Ordering::{AcqRel, Acquire, Relaxed, Release},
         },
     },
 };
@@ -32,25 +33,34 @@ impl Default for RelaxedTailSync {
 }
 
 impl RelaxedTailSync {
-    /// Create a new headtail with a maximum distance between the head and tail of `htd_max`.
-    // TODO: Actually be able to configure this when creating the ring
-    #[must_use]
-    #[inline]
-    pub fn new(htd_max: NonZeroU32) -> Self {
-        Self {
-            head: AtomicPosCnt::default(),
-            htd_max,
-            tail: AtomicPosCnt::default(),
+    const_fn_unless_loom! {
+        /// Create a new headtail with a maximum distance between the head and tail of `htd_max`.
+        ///
+        /// To configure this when creating a channel, pass a [`MaxHeadTailDistance`] to
+        /// [`bounded_with_config`](crate::custom::bounded_with_config).
+        #[must_use]
+        #[inline]
+        pub fn new(htd_max: NonZeroU32) -> Self {
+            Self {
+                head: AtomicPosCnt::new(),
+                htd_max,
+                tail: AtomicPosCnt::new(),
+            }
         }
     }
 }
 
-/// The head and tail of the head/tail.
+/// A position together with the amount of claims, packed in the head or tail of a [`RelaxedTailSync`].
+///
+/// The count of the head is the amount of claims made, and of the tail the amount of claims
+/// returned. The tail only moves to the head once they match. The count wraps around at
+/// `u32::MAX`, like the generation of [`HeadGen`](super::HeadGen) it stops a descheduled thread
+/// from moving the head after the other threads moved it a multiple of the ring size (ABA).
 #[derive(Copy, Clone, Debug)]
 struct PosCnt {
-    /// The head of the head/tail
+    /// The position in the ring.
     pos: u32,
-    /// The tail of the head/tail
+    /// The amount of claims, wrapping around.
     cnt: u32,
 }
 impl From<u64> for PosCnt {
@@ -73,12 +83,21 @@ impl From<PosCnt> for u64 {
 }
 
 /// An atomic version of [`PosCnt`] that can be safely shared between threads.
-#[derive(Default)]
 struct AtomicPosCnt {
     /// The encoded value of [`PosCnt`].
     inner: AtomicU64,
 }
 impl AtomicPosCnt {
+    const_fn_unless_loom! {
+        /// Create a new [`PosCnt`] at position 0 without claims.
+        #[inline]
+        fn new() -> Self {
+            Self {
+                inner: AtomicU64::new(0),
+            }
+        }
+    }
+
     /// Loads the [`PosCnt`] value atomically.
     ///
     /// See [`AtomicU64::load`].
@@ -108,9 +127,20 @@ impl AtomicPosCnt {
 
 /// The maximum distance between the head and tail of a 'headtail'.
 ///
-/// This defaults to `u32::MAX`.
+/// A smaller distance limits how far threads can get ahead of a stalled thread, at the cost of
+/// more spinning when many threads are active. This defaults to `u32::MAX`.
+#[derive(Debug, Copy, Clone)]
 pub struct MaxHeadTailDistance(NonZeroU32);
+impl MaxHeadTailDistance {
+    /// Create the settings with a maximum distance between the head and tail of `htd_max`.
+    #[must_use]
+    #[inline]
+    pub const fn new(htd_max: NonZeroU32) -> Self {
+        Self(htd_max)
+    }
+}
 impl Default for MaxHeadTailDistance {
+    #[inline]
     fn default() -> Self {
         Self(NonZeroU32::MAX)
     }
@@ -121,46 +151,50 @@ impl Mode for RelaxedTailSync {
 
     #[inline]
     fn new_with(settings: Self::Settings) -> Self {
-        Self {
-            head: AtomicPosCnt::default(),
-            htd_max: settings.0,
-            tail: AtomicPosCnt::default(),
-        }
+        Self::new(settings.0)
     }
 }
 
 impl ModeInner for RelaxedTailSync {
-    fn move_head<const N: usize, const IS_PROD: bool, const EXACT: bool, Other: Mode>(
+    fn move_head<const IS_PROD: bool, const EXACT: bool, const CONTIGUOUS: bool, Other: Mode>(
         &self,
         other: &Other,
         expected: NonZeroU32,
+        mask: u32,
     ) -> Result<Claim, Error> {
         // Get the current head
         let mut old_head = self.head.load(Acquire);
+        let mut backoff = Backoff::new();
 
         loop {
-            while old_head.pos.wrapping_sub(self.tail.load(Acquire).pos) & (N as u32 - 1)
-                > self.htd_max.get()
+            while old_head.pos.wrapping_sub(self.tail.load(Acquire).pos) & mask > self.htd_max.get()
             {
-                spin_loop();
+                backoff.snooze();
                 old_head = self.head.load(Acquire);
             }
             // Sync with update_tail Release (github.com/DPDK/dpdk/commit/9ed8770)
             let other_tail = other.load_tail(Acquire);
 
-            let available =
-                calculate_available::<N, IS_PROD, EXACT>(old_head.pos, other_tail, expected)?;
+            let (available, remaining) = calculate_available::<IS_PROD, EXACT, CONTIGUOUS>(
+                old_head.pos,
+                other_tail,
+                expected,
+                mask,
+            )?;
 
             let new_head = PosCnt {
-                pos: old_head.pos.wrapping_add(available.get()) & (N as u32 - 1),
-                cnt: old_head.cnt.wrapping_add(1) & (N as u32 - 1),
+                pos: old_head.pos.wrapping_add(available.get()) & mask,
+                cnt: old_head.cnt.wrapping_add(1),
             };
 
+            // Release the tail we loaded, see `Multi::move_head`
             match self
                 .head
-                .compare_exchange_weak(old_head, new_head, Acquire, Acquire)
+                .compare_exchange_weak(old_head, new_head, AcqRel, Acquire)
             {
-                Ok(_) => return Ok(Claim::many(available, old_head.pos)),
+                Ok(_) => {
+                    return Ok(Claim::many(available, old_head.pos, remaining).tag(new_head.pos));
+                }
                 Err(new_old_head) => {
                     cold_path();
                     old_head = new_old_head;
@@ -169,13 +203,15 @@ impl ModeInner for RelaxedTailSync {
         }
     }
 
-    fn update_tail<const N: usize>(&self, claim: Claim) {
+    fn update_tail(&self, claim: Claim, mask: u32) {
         let mut old_tail = self.tail.load(Acquire);
-        let _ = claim.new_tail::<N>();
+        #[cfg(feature = "debug_claims")]
+        claim.check_claimed(old_tail.pos, self.head.load(Relaxed).pos, mask);
+        let _ = claim.new_tail(mask);
         loop {
             let head = self.head.load(Relaxed);
             let mut new_tail = PosCnt {
-                cnt: old_tail.cnt.wrapping_add(1) & (N as u32 - 1),
+                cnt: old_tail.cnt.wrapping_add(1),
                 pos: old_tail.pos,
             };
             // If we've caught up to the rest, update the tail
@@ -200,9 +236,14 @@ impl ModeInner for RelaxedTailSync {
         self.tail.load(ordering).pos
     }
 
+    #[inline]
+    fn load_head(&self, ordering: Ordering) -> u32 {
+        self.head.load(ordering).pos
+    }
+
     #[inline]
     fn mark_finished(&self) {
-        let res = self.tail.inner.fetch_or(0x8000_0000_0000_0000, Relaxed);
+        let res = self.tail.inner.fetch_or(0x8000_0000_0000_0000, Release);
         assert_eq!(
             res & 0x8000_0000_0000_0000,
             0,
@@ -210,8 +251,25 @@ impl ModeInner for RelaxedTailSync {
         );
     }
 
+    #[inline]
+    unsafe fn clear_poison<const IS_PROD: bool>(&self) -> u32 {
+        let position = if IS_PROD {
+            self.tail.load(Acquire).pos & 0x7FFF_FFFF
+        } else {
+            self.head.load(Relaxed).pos
+        };
+        // Without claims the count can start over, it only needs to be the same for both.
+        let new = u64::from(PosCnt {
+            pos: position,
+            cnt: 0,
+        });
+        self.head.inner.store(new, Relaxed);
+        self.tail.inner.store(new, Release);
+        position
+    }
+
     #[inline]
     fn is_finished(&self) -> bool {
-        self.tail.inner.load(Relaxed) & 0x8000_0000_0000_0000 != 0
+        self.tail.inner.load(Acquire) & 0x8000_0000_0000_0000 != 0
     }
 }
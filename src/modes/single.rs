@@ -2,23 +2,31 @@
 
 use crate::{
     Error,
-    modes::{Claim, Mode, ModeInner, calculate_available},
-    std::sync::atomic::{
-        AtomicU32, Ordering,
-        Ordering::{Acquire, Relaxed, Release},
-        fence,
+    modes::{Advance, Claim, HeadGen, Mode, ModeInner, Rewind, calculate_available},
+    std::{
+        const_fn_unless_loom,
+        sync::atomic::{
+            AtomicU32, AtomicU64, Ordering,
+            Ordering::{Acquire, Relaxed, Release},
+            fence,
+        },
     },
 };
 use core::{marker::PhantomData, num::NonZeroU32};
 
 /// A single threaded consumer or producer.
-#[derive(Default)]
+///
+/// The head and tail are laid out like in [`Multi`](crate::modes::Multi), so a ring can switch from
+/// [`Multi`](crate::modes::Multi) to [`Single`] once only one handle is left. See
+/// [`Sender::try_downgrade_to_single`](crate::custom::Sender::try_downgrade_to_single).
+#[repr(C)]
 pub struct Single {
-    /// The current head.
+    /// The current head, see [`HeadGen`].
     ///
     /// This is an atomic because all the operations in `Mode` take an immutable reference,
-    /// so we need the interior mutability of the atomic type.
-    head: AtomicU32,
+    /// so we need the interior mutability of the atomic type. The generation isn't needed for a
+    /// single thread, but it's kept up to date for the layout of [`Multi`](crate::modes::Multi).
+    head: AtomicU64,
     /// The current tail.
     ///
     /// This is an atomic because it's used by the other headtail for synchronisation.
@@ -27,23 +35,47 @@ pub struct Single {
     _not_sync: PhantomData<*mut ()>,
 }
 
+impl Single {
+    const_fn_unless_loom! {
+        /// Create a new headtail.
+        #[must_use]
+        #[inline]
+        pub fn new() -> Self {
+            Self {
+                head: AtomicU64::new(0),
+                tail: AtomicU32::new(0),
+                _not_sync: PhantomData,
+            }
+        }
+    }
+}
+
+impl Default for Single {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Mode for Single {
     type Settings = ();
 
     #[inline]
     fn new_with(_settings: Self::Settings) -> Self {
-        Self::default()
+        Self::new()
     }
 }
 
 impl ModeInner for Single {
-    fn move_head<const N: usize, const IS_PROD: bool, const EXACT: bool, Other: Mode>(
+    fn move_head<const IS_PROD: bool, const EXACT: bool, const CONTIGUOUS: bool, Other: Mode>(
         &self,
         other: &Other,
         expected: NonZeroU32,
+        mask: u32,
     ) -> Result<Claim, Error> {
         // Get the current head
-        let old_head = self.head.load(Relaxed);
+        let old = HeadGen::from(self.head.load(Relaxed));
+        let old_head = old.pos;
 
         // Ensure head is read before tail (github.com/DPDK/dpdk/commit/86757c2)
         // This works because the compiler/processor is not allowed to reorder operations
@@ -53,17 +85,21 @@ impl ModeInner for Single {
         // Sync with update_tail Release (github.com/DPDK/dpdk/commit/9ed8770)
         let other_tail = other.load_tail(Acquire);
 
-        let available = calculate_available::<N, IS_PROD, EXACT>(old_head, other_tail, expected)?;
+        let (available, remaining) = calculate_available::<IS_PROD, EXACT, CONTIGUOUS>(
+            old_head, other_tail, expected, mask,
+        )?;
 
-        let new_head = old_head.wrapping_add(available.get()) & (N as u32 - 1);
+        let new_head = old_head.wrapping_add(available.get()) & mask;
 
-        self.head.store(new_head, Relaxed);
-        Ok(Claim::many(available, old_head))
+        self.head.store(old.next(new_head).into(), Relaxed);
+        Ok(Claim::many(available, old_head, remaining).tag(new_head))
     }
 
     #[inline]
-    fn update_tail<const N: usize>(&self, claim: Claim) {
-        let new_tail = claim.new_tail::<N>();
+    fn update_tail(&self, claim: Claim, mask: u32) {
+        #[cfg(feature = "debug_claims")]
+        claim.check_in_order(self.tail.load(Relaxed));
+        let new_tail = claim.new_tail(mask);
         self.tail.store(new_tail, Release);
     }
 
@@ -73,14 +109,56 @@ impl ModeInner for Single {
         self.tail.load(ordering)
     }
 
+    #[inline]
+    fn load_head(&self, ordering: Ordering) -> u32 {
+        HeadGen::from(self.head.load(ordering)).pos
+    }
+
     #[inline]
     fn mark_finished(&self) {
-        let res = self.tail.fetch_or(0x8000_0000, Relaxed);
+        let res = self.tail.fetch_or(0x8000_0000, Release);
         assert_eq!(res & 0x8000_0000, 0, "Tail was already marked as finished!");
     }
 
+    #[inline]
+    unsafe fn clear_poison<const IS_PROD: bool>(&self) -> u32 {
+        let old = HeadGen::from(self.head.load(Relaxed));
+        let position = if IS_PROD {
+            self.tail.load(Acquire) & 0x7FFF_FFFF
+        } else {
+            old.pos
+        };
+        self.head.store(old.next(position).into(), Relaxed);
+        self.tail.store(position, Release);
+        position
+    }
+
     #[inline]
     fn is_finished(&self) -> bool {
-        self.tail.load(Relaxed) & 0x8000_0000 != 0
+        self.tail.load(Acquire) & 0x8000_0000 != 0
+    }
+}
+
+impl Rewind for Single {
+    #[inline]
+    fn rewind(&self, claim: Claim, used: u32, mask: u32) {
+        #[cfg(feature = "debug_claims")]
+        claim.check_in_order(self.tail.load(Relaxed));
+        let new = claim.rewind(used, mask);
+        if used != 0 {
+            self.tail.store(new, Release);
+        }
+        let old = HeadGen::from(self.head.load(Relaxed));
+        self.head.store(old.next(new).into(), Relaxed);
+    }
+}
+
+impl Advance for Single {
+    #[inline]
+    fn advance(&self, claim: &mut Claim, used: u32, mask: u32) {
+        #[cfg(feature = "debug_claims")]
+        claim.check_in_order(self.tail.load(Relaxed));
+        let new_tail = claim.split_off_front(used, mask);
+        self.tail.store(new_tail, Release);
     }
 }
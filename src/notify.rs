@@ -0,0 +1,25 @@
+//! Optional notification of the consumers when values are sent.
+//!
+//! The receivers of a channel poll the ring, there is no blocking mechanism built in. A ring
+//! with a [`Notify`] hook calls it every time values become visible to the consumers, so a
+//! blocking consumer can be woken with whatever mechanism fits, like a condvar or an eventfd.
+
+/// Get notified when values are sent to a channel.
+///
+/// See [`bounded_with_notify`](crate::custom::bounded_with_notify).
+pub trait Notify: Send + Sync {
+    /// Called after `n` values were sent and are visible to the consumers.
+    ///
+    /// This is called by the sender, so it should be quick and not wait on the consumers.
+    fn notify(&self, n: usize);
+}
+
+impl<F> Notify for F
+where
+    F: Fn(usize) + Send + Sync,
+{
+    #[inline]
+    fn notify(&self, n: usize) {
+        self(n);
+    }
+}
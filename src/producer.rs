@@ -1,12 +1,15 @@
 //! The user facing producer implementation.
 
+#[cfg(not(feature = "_safe_maybeuninit"))]
+use crate::ring::write_guard::WriteGuard;
 use crate::{
-    Error,
-    modes::Mode,
-    ring::{Ring, active::Last},
-    std::hint::cold_path,
+    Backoff, Error,
+    modes::{Mode, Multi, Rewind, Single},
+    ring::{Ring, active::Last, permit::Permit},
+    std::{hint::cold_path, thread::panicking},
 };
-use std::thread::panicking;
+use core::{iter::Chain, mem::ManuallyDrop};
+use std::collections::{VecDeque, vec_deque};
 
 /// The sending-half of the channel.
 ///
@@ -15,6 +18,28 @@ use std::thread::panicking;
 /// - `T`: the type being sent over the channel.
 /// - `P`: the synchronisation mode of the sender, see [`Mode`].
 /// - `C`: the synchronisation mode of the receiver, see [`Mode`].
+///
+/// # Cloning
+/// A sender can be cloned if the producer mode `P` supports multiple threads, independent of
+/// the consumer mode `C`:
+/// ```
+/// let (sender, _receiver) = ringbeam::mpsc::bounded::<4, u8>();
+/// let _sender2 = sender.clone();
+/// ```
+///
+/// A sender of a single producer channel can't be cloned:
+/// ```compile_fail,E0599
+/// let (sender, _receiver) = ringbeam::spmc::bounded::<4, u8>();
+/// let _sender2 = sender.clone();
+/// ```
+///
+/// # Threads
+/// A sender can only be sent to another thread if `T` is [`Send`], as the values are moved between
+/// threads through the channel. A channel of values that aren't [`Send`] stays on one thread:
+/// ```compile_fail,E0277
+/// let (sender, _receiver) = ringbeam::spsc::bounded::<4, std::rc::Rc<u8>>();
+/// std::thread::spawn(move || drop(sender));
+/// ```
 pub struct Sender<const N: usize, T, P, C>
 where
     P: Mode,
@@ -69,16 +94,97 @@ where
     /// Try to put the value in the channel.
     ///
     /// # Errors
-    /// Returns [`Ok(Some(T))`] when full, [`Error::Closed`] when closed, and [`Error::Poisoned`]
-    /// when the ring is poisoned.
+    /// Returns [`Ok(Some(T))`] when full, or when the channel has a byte budget and the value
+    /// would exceed it. Returns [`Error::Closed`] when closed, and [`Error::Poisoned`] when the
+    /// ring is poisoned.
     #[inline]
     pub fn try_send(&self, value: T) -> Result<Option<T>, Error> {
-        let mut once = core::iter::once(value);
-        match self.try_send_bulk(&mut once) {
-            Ok(1) => Ok(None),
+        match self.try_send_or_return(value) {
+            Ok(()) => Ok(None),
+            Err((Error::Full | Error::OverBudget, value)) => {
+                cold_path();
+                Ok(Some(value))
+            }
+            Err((error, _)) => {
+                cold_path();
+                Err(error)
+            }
+        }
+    }
+
+    /// Try to put the value in the channel, returning the amount of free slots when it's full.
+    ///
+    /// The free slots are counted after the send failed. This is usually 0, but a receiver can
+    /// take values in between. A producer can use this to decide whether to retry right away or
+    /// to back off.
+    ///
+    /// ```
+    /// let (sender, receiver) = ringbeam::spsc::bounded::<2, u8>();
+    /// assert_eq!(sender.try_send_or_len(1), Ok(Ok(())));
+    /// assert_eq!(sender.try_send_or_len(2), Ok(Err((2, 0))));
+    /// assert_eq!(receiver.try_recv(), Ok(1));
+    /// ```
+    ///
+    /// # Returns
+    /// `Ok(())` if the value was sent, `Err((value, free))` if the channel is full or if the
+    /// channel has a byte budget and the value would exceed it.
+    ///
+    /// # Errors
+    /// Returns [`Error::Closed`] when closed, and [`Error::Poisoned`] when the ring is poisoned.
+    #[inline]
+    pub fn try_send_or_len(&self, value: T) -> Result<Result<(), (T, usize)>, Error> {
+        match self.try_send_or_return(value) {
+            Ok(()) => Ok(Ok(())),
+            Err((Error::Full | Error::OverBudget, value)) => {
+                cold_path();
+                Ok(Err((value, self.capacity().saturating_sub(self.len()))))
+            }
+            Err((error, _)) => {
+                cold_path();
+                Err(error)
+            }
+        }
+    }
+
+    /// Try to put a clone of the value in the channel.
+    ///
+    /// The value is only cloned after a slot in the channel is claimed, so nothing is cloned when
+    /// the channel is full. This avoids the wasted clone of `try_send(value.clone())`.
+    ///
+    /// ```
+    /// let (sender, receiver) = ringbeam::spsc::bounded::<2, String>();
+    /// let value = String::from("hello");
+    /// assert_eq!(sender.try_send_ref(&value), Ok(true));
+    /// assert_eq!(sender.try_send_ref(&value), Ok(false));
+    /// assert_eq!(receiver.try_recv().unwrap(), value);
+    /// ```
+    ///
+    /// # Returns
+    /// `true` if a clone was sent, `false` if the channel is full and nothing was cloned.
+    ///
+    /// # Errors
+    /// Returns [`Error::Closed`] when closed, and [`Error::Poisoned`] when the ring is poisoned.
+    /// If the channel has a byte budget, it returns [`Error::OverBudget`] when the value would
+    /// exceed it. The value isn't cloned in any of those cases.
+    #[inline]
+    pub fn try_send_ref(&self, value: &T) -> Result<bool, Error>
+    where
+        T: Clone,
+    {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        let bytes = ring.budget().measure(value);
+        ring.budget().reserve(bytes)?;
+
+        // The iterator is only advanced after the slot is claimed
+        let mut once = core::iter::once_with(|| value.clone());
+        match ring.try_enqueue::<true, _>(&mut once, Some(bytes)) {
+            Ok((1, _)) => Ok(true),
             Err(Error::Full) => {
                 cold_path();
-                Ok(once.next())
+                Ok(false)
             }
             Err(error) => {
                 cold_path();
@@ -88,9 +194,327 @@ where
         }
     }
 
+    /// Try to put the value in the channel, returning it with the error if that failed.
+    ///
+    /// # Errors
+    /// See [`try_send`](Self::try_send), but full is reported as [`Error::Full`] and a value
+    /// that doesn't fit in the byte budget as [`Error::OverBudget`].
+    #[inline]
+    pub(crate) fn try_send_or_return(&self, value: T) -> Result<(), (Error, T)> {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        let bytes = ring.budget().measure(&value);
+        if let Err(error) = ring.budget().reserve(bytes) {
+            cold_path();
+            return Err((error, value));
+        }
+
+        let mut once = core::iter::once(value);
+        match ring.try_enqueue::<true, _>(&mut once, Some(bytes)) {
+            Ok((1, _)) => Ok(()),
+            Err(error) => {
+                cold_path();
+                Err((error, once.next().unwrap_or_else(|| unreachable!())))
+            }
+            Ok(_) => unreachable!(),
+        }
+    }
+
+    /// Try to put as many values from the front of `buffer` as possible into the channel.
+    ///
+    /// Unlike [`try_send_burst`](Self::try_send_burst) this works with a byte budget, as the values
+    /// can be measured before they're taken from `buffer`. Only the values that fit are taken.
+    ///
+    /// # Errors
+    /// See [`try_send_burst`](Self::try_send_burst), if the first value doesn't fit in the byte
+    /// budget it returns [`Error::OverBudget`].
+    fn try_send_front(&self, buffer: &mut VecDeque<T>) -> Result<usize, Error> {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        let budget = ring.budget();
+        if !budget.is_limited() {
+            return ring
+                .try_enqueue::<false, _>(&mut Front(buffer), None)
+                .map(|(written, _)| written);
+        }
+        let (count, bytes) = budget.reserve_prefix(|| buffer.iter().map(|v| budget.measure(v)));
+        if count == 0 && !buffer.is_empty() {
+            cold_path();
+            return Err(Error::OverBudget);
+        }
+        let (written, _) =
+            ring.try_enqueue::<false, _>(&mut Front(buffer).take(count), Some(bytes))?;
+        if written < count {
+            cold_path();
+            // Release the bytes of the values that didn't fit in the ring
+            budget.release(
+                buffer
+                    .iter()
+                    .take(count - written)
+                    .map(|v| budget.measure(v))
+                    .sum(),
+            );
+        }
+        Ok(written)
+    }
+
+    /// Is `value` larger than the whole byte budget, so waiting for room can't help.
+    #[inline]
+    pub(crate) fn exceeds_budget(&self, value: &T) -> bool {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        let budget = ring.budget();
+        budget.exceeds_limit(budget.measure(value))
+    }
+
+    /// Put the value in the channel, evicting the oldest value if the channel is full.
+    ///
+    /// This is meant for channels where only the latest values matter, like telemetry. The
+    /// producer evicts the oldest value by moving the consumer head like a consumer would,
+    /// so it's only available if the consumer mode supports multiple threads doing that at the
+    /// same time. It's not available for [`Single`](crate::custom::modes::Single) consumers, as
+    /// their head can only be moved by the consumer itself. A consumer that's reading at the
+    /// same time either gets the oldest value or the value after it, but never the same value
+    /// as the producer.
+    ///
+    /// # Returns
+    /// The evicted value, if a value had to be evicted. If other producers fill the freed slot
+    /// first, more than one value can be evicted. Only the last one is returned, the others are
+    /// dropped.
+    ///
+    /// # Errors
+    /// Returns [`Error::Closed`] when closed and [`Error::Poisoned`] when the ring is poisoned,
+    /// the value is dropped in that case. If the channel has a byte budget, it returns
+    /// [`Error::OverBudget`] when the value would exceed it.
+    #[inline]
+    pub fn try_send_overwrite(&self, mut value: T) -> Result<Option<T>, Error>
+    where
+        C: Sync,
+    {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        let mut evicted = None;
+        loop {
+            match self.try_send_or_return(value) {
+                Ok(()) => return Ok(evicted),
+                Err((Error::Full, returned)) => value = returned,
+                Err((error, _)) => {
+                    cold_path();
+                    return Err(error);
+                }
+            }
+            cold_path();
+            match ring.try_dequeue_one() {
+                Ok(oldest) => evicted = Some(oldest),
+                // A consumer emptied the channel in the meantime
+                Err(Error::Empty) => {}
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    /// Put the value in the channel, waiting while the channel is full.
+    ///
+    /// This waits using the default [`Backoff`], see [`send_with_backoff`](Self::send_with_backoff)
+    /// to configure it.
+    ///
+    /// # Errors
+    /// Returns [`Error::Closed`] when closed and [`Error::Poisoned`] when the ring is poisoned,
+    /// the value is dropped in that case. It also waits while the value doesn't fit in the byte
+    /// budget, but returns [`Error::OverBudget`] if it's larger than the whole budget.
+    #[inline]
+    pub fn send(&self, value: T) -> Result<(), Error> {
+        self.send_with_backoff(value, &mut Backoff::new())
+    }
+
+    /// Put the value in the channel, waiting with `backoff` while the channel is full.
+    ///
+    /// # Errors
+    /// Returns [`Error::Closed`] when closed and [`Error::Poisoned`] when the ring is poisoned,
+    /// the value is dropped in that case. It also waits while the value doesn't fit in the byte
+    /// budget, but returns [`Error::OverBudget`] if it's larger than the whole budget.
+    #[inline]
+    pub fn send_with_backoff(&self, mut value: T, backoff: &mut Backoff) -> Result<(), Error> {
+        loop {
+            match self.try_send_or_return(value) {
+                Ok(()) => return Ok(()),
+                Err((Error::Full, returned)) => value = returned,
+                Err((Error::OverBudget, returned)) if !self.exceeds_budget(&returned) => {
+                    value = returned;
+                }
+                Err((error, _)) => {
+                    cold_path();
+                    return Err(error);
+                }
+            }
+            backoff.snooze();
+        }
+    }
+
+    /// Put the value in the channel, waiting while the channel is full as long as the consumers
+    /// are making progress.
+    ///
+    /// This is a watchdog for pipelines that can hang: it gives up when no values are received
+    /// for `idle` while the channel is full, instead of waiting forever. The consumers can also be
+    /// slow because they're not scheduled, so it can report a stall that would have resolved
+    /// itself. `idle` should be a lot longer than a consumer usually needs for a value.
+    ///
+    /// # Errors
+    /// Returns [`Error::Stalled`] together with the value if the channel stays full and the
+    /// consumers don't take a value for `idle`. Returns [`Error::Closed`] when closed and
+    /// [`Error::Poisoned`] when the ring is poisoned, also together with the value. It also waits
+    /// while the value doesn't fit in the byte budget, but returns [`Error::OverBudget`] together
+    /// with the value if it's larger than the whole budget.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn send_with_progress_timeout(
+        &self,
+        mut value: T,
+        idle: core::time::Duration,
+    ) -> Result<(), (Error, T)> {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        let mut backoff = Backoff::new();
+        // The consumer tail and when it was first seen, only loaded once the channel is full
+        let mut progress = None;
+        loop {
+            match self.try_send_or_return(value) {
+                Ok(()) => return Ok(()),
+                Err((Error::Full, returned)) => value = returned,
+                Err((Error::OverBudget, returned)) if !self.exceeds_budget(&returned) => {
+                    value = returned;
+                }
+                Err(error) => {
+                    cold_path();
+                    return Err(error);
+                }
+            }
+            let tail = ring.cons_tail();
+            match progress {
+                Some((last, _)) if last != tail => {
+                    progress = Some((tail, std::time::Instant::now()));
+                    backoff.reset();
+                }
+                Some((_, since)) if since.elapsed() >= idle => {
+                    cold_path();
+                    return Err((Error::Stalled, value));
+                }
+                Some(_) => {}
+                None => progress = Some((tail, std::time::Instant::now())),
+            }
+            backoff.snooze();
+        }
+    }
+
+    /// Put all values of `iter` in the channel, waiting while the channel is full.
+    ///
+    /// The values are taken from `iter` in batches and sent with [`try_send_burst`](Self::try_send_burst),
+    /// so `iter` doesn't need to be an [`ExactSizeIterator`]. This waits using the default
+    /// [`Backoff`].
+    ///
+    /// # Returns
+    /// The amount of values sent, which is all values of `iter`.
+    ///
+    /// # Errors
+    /// Returns the amount of values sent together with [`Error::Closed`] when closed, or
+    /// [`Error::Poisoned`] when the ring is poisoned. The values that weren't sent are dropped.
+    /// It also waits while the next value doesn't fit in the byte budget, but returns
+    /// [`Error::OverBudget`] if it's larger than the whole budget.
+    #[inline]
+    pub fn send_iter_all<I>(&self, iter: I) -> Result<usize, (usize, Error)>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut iter = iter.into_iter();
+        let batch = ITER_BATCH.min(self.capacity());
+        let mut buffer = VecDeque::with_capacity(batch);
+        let mut backoff = Backoff::new();
+        let mut sent = 0;
+        loop {
+            buffer.extend(iter.by_ref().take(batch - buffer.len()));
+            if buffer.is_empty() {
+                return Ok(sent);
+            }
+            match self.try_send_front(&mut buffer) {
+                Ok(n) => {
+                    sent += n;
+                    backoff.reset();
+                }
+                Err(Error::Full) => backoff.snooze(),
+                Err(Error::OverBudget)
+                    if !buffer.front().is_some_and(|v| self.exceeds_budget(v)) =>
+                {
+                    backoff.snooze();
+                }
+                Err(error) => {
+                    cold_path();
+                    return Err((sent, error));
+                }
+            }
+        }
+    }
+
+    /// Put as many values of `iter` in the channel as fit, returning the values that weren't sent.
+    ///
+    /// The values are taken from `iter` in batches and sent with [`try_send_burst`](Self::try_send_burst),
+    /// so `iter` doesn't need to be an [`ExactSizeIterator`]. Sending stops at the first batch
+    /// that doesn't fit completely. The values of that batch that weren't sent come first in the
+    /// returned iterator, followed by the rest of `iter`, so no value is lost and the order is kept.
+    /// The caller can then decide to retry later or to put the values somewhere else.
+    ///
+    /// ```
+    /// let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+    /// let mut rest = sender.push_iter((1..=5).filter(|v| v % 2 == 1));
+    /// assert_eq!(rest.next(), None);
+    /// assert_eq!(receiver.try_recv(), Ok(1));
+    /// let rest = sender.push_iter(7..10);
+    /// assert_eq!(rest.collect::<Vec<_>>(), [8, 9]);
+    /// assert_eq!(receiver.try_recv_burst(4).unwrap().collect::<Vec<_>>(), [3, 5, 7]);
+    /// ```
+    ///
+    /// # Returns
+    /// The values that weren't sent, which is empty if everything was sent. Sending also stops
+    /// when the channel is closed, poisoned, or over its byte budget, see
+    /// [`try_send_burst`](Self::try_send_burst). Use [`is_closed`](Self::is_closed) to tell
+    /// whether a retry can succeed.
+    #[must_use = "The values that weren't sent are dropped"]
+    #[inline]
+    pub fn push_iter<I>(&self, iter: I) -> Chain<vec_deque::IntoIter<T>, I::IntoIter>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut iter = iter.into_iter();
+        let batch = ITER_BATCH.min(self.capacity());
+        let mut buffer = VecDeque::with_capacity(batch);
+        loop {
+            buffer.extend(iter.by_ref().take(batch));
+            if buffer.is_empty() {
+                break;
+            }
+            // Only continue with the next batch if this one was sent completely
+            let sent = self.try_send_front(&mut buffer);
+            if sent.is_err() || !buffer.is_empty() {
+                cold_path();
+                break;
+            }
+        }
+        buffer.into_iter().chain(iter)
+    }
+
     /// Try to put all values into the channel or none at all.
     ///
     /// To put as many values in the channel as possible, see [`try_send_burst`](Self::try_send_burst).
+    /// A single send is limited to `u32::MAX` values, more never fit in the channel.
     ///
     /// # Returns
     /// The amount of values written.
@@ -98,7 +522,9 @@ where
     /// # Errors
     /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
     /// one of those states. The last one indicates that retrying can be successful. It can also
-    /// return [`Error::NotEnoughSpace`], which can also be successful on a retry.
+    /// return [`Error::NotEnoughSpace`], which can also be successful on a retry. If the channel
+    /// has a byte budget, it returns [`Error::OverBudget`] without taking a value from `values`,
+    /// see [`bounded_with_budget`](crate::custom::bounded_with_budget).
     ///
     /// # Panics
     /// Can panic if the [`ExactSizeIterator`] implementation of `I` is wrong.
@@ -111,7 +537,8 @@ where
         //         No mutable aliasing in the ring except for inside the UnsafeCell.
         let ring = unsafe { &*self.ring };
 
-        ring.try_enqueue::<true, I>(values)
+        ring.try_enqueue::<true, I>(values, None)
+            .map(|(written, _)| written)
     }
 
     /// Try to put as many values as possible into the channel.
@@ -125,7 +552,9 @@ where
     ///
     /// # Errors
     /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
-    /// one of those states. The last one indicates that retrying can be successful.
+    /// one of those states. The last one indicates that retrying can be successful. If the channel
+    /// has a byte budget, it returns [`Error::OverBudget`] without taking a value from `values`,
+    /// see [`bounded_with_budget`](crate::custom::bounded_with_budget).
     ///
     /// # Panics
     /// Can panic if the [`ExactSizeIterator`] implementation of `I` is wrong.
@@ -138,75 +567,852 @@ where
         //         No mutable aliasing in the ring except for inside the UnsafeCell.
         let ring = unsafe { &*self.ring };
 
-        ring.try_enqueue::<false, I>(values)
+        ring.try_enqueue::<false, I>(values, None)
+            .map(|(written, _)| written)
     }
-}
 
-impl<const N: usize, T, P, C> Clone for Sender<N, T, P, C>
-where
-    P: Mode + Sync,
-    C: Mode,
-{
+    /// Try to put at most `max` values of `values` into the channel, for iterators that don't know
+    /// their length.
+    ///
+    /// Room for `max` values is claimed up front, and the room that is left when `values` ends is
+    /// given back. Giving back room is only possible for producer modes that implement [`Rewind`],
+    /// those are [`Single`](crate::custom::modes::Single) and [`HeadTailSync`](crate::custom::modes::HeadTailSync).
+    /// For `HeadTailSync` the other senders wait while the values are pulled from `values`, so a
+    /// `max` much larger than the amount of values costs throughput under contention. When the
+    /// length is known, [`try_send_burst`](Self::try_send_burst) doesn't claim more than it sends.
+    ///
+    /// ```
+    /// let (sender, receiver) = ringbeam::spsc::bounded::<8, u8>();
+    /// let mut values = (0..10).filter(|v| v % 3 == 0);
+    /// assert_eq!(sender.try_send_from(&mut values, 8), Ok(4));
+    /// assert_eq!(receiver.try_recv_burst(8).unwrap().collect::<Vec<_>>(), [0, 3, 6, 9]);
+    /// ```
+    ///
+    /// # Returns
+    /// The amount of values written, which is less than `max` if `values` ended or the channel
+    /// didn't have room for `max` values.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Full`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful. If the channel
+    /// has a byte budget, it returns [`Error::OverBudget`] without taking a value from `values`,
+    /// see [`bounded_with_budget`](crate::custom::bounded_with_budget).
     #[inline]
-    fn clone(&self) -> Self {
-        // SAFETY: because `self` is valid, `ring` is initialized and aligned.
-        unsafe { Self::new(self.ring).expect("Failed to clone producer!") }
+    pub fn try_send_from<I>(&self, values: &mut I, max: usize) -> Result<usize, Error>
+    where
+        P: Rewind,
+        I: Iterator<Item = T>,
+    {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.try_enqueue_from(values, max)
     }
-}
 
-impl<const N: usize, T, P, C> Drop for Sender<N, T, P, C>
-where
-    P: Mode,
-    C: Mode,
-{
-    #[expect(
-        clippy::missing_inline_in_public_items,
-        reason = "This function is too large too inline"
-    )]
-    fn drop(&mut self) {
-        if panicking() {
-            cold_path();
-            // SAFETY: Ring is valid before we call unregister_producer
-            unsafe {
-                (*self.ring).poison();
+    /// Try to put as many values of `values` as possible into the channel, giving back the rest.
+    ///
+    /// This is [`try_send_burst`](Self::try_send_burst), but the values that don't fit are
+    /// returned in their original order. They can be sent again later, without keeping an
+    /// iterator around.
+    ///
+    /// ```
+    /// let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+    /// let (sent, rest, error) = sender.try_send_prefix(vec![1, 2, 3, 4, 5]).unwrap_err();
+    /// assert_eq!((sent, rest, error), (3, vec![4, 5], ringbeam::Error::Full));
+    /// assert_eq!(receiver.try_recv(), Ok(1));
+    /// ```
+    ///
+    /// # Errors
+    /// If not all values are sent, returns the amount of values that were sent, the values that
+    /// weren't, and why. That's [`Error::Full`] if the channel ran out of room or bytes in its
+    /// budget, or one of the errors of [`try_send_burst`](Self::try_send_burst) if no value was
+    /// sent. With a byte budget, only the values that fit in it are sent.
+    #[inline]
+    pub fn try_send_prefix(&self, values: Vec<T>) -> Result<(), (usize, Vec<T>, Error)> {
+        if values.is_empty() {
+            return Ok(());
+        }
+        let mut buffer = VecDeque::from(values);
+        match self.try_send_front(&mut buffer) {
+            Ok(_) if buffer.is_empty() => Ok(()),
+            Ok(sent) => {
+                cold_path();
+                Err((sent, Vec::from(buffer), Error::Full))
             }
-        } else {
-            // SAFETY: Ring is valid before we call unregister_producer
-            match unsafe {
-                (*self.ring)
-                    .active()
-                    .unregister_producer()
-                    .expect("Ring is poisoned!")
-            } {
-                Last::InCategory => {
-                    // SAFETY: Even if another thread starts the ring cleanup, the cleanup will
-                    // wait for the tail being marked.
-                    unsafe {
-                        (*self.ring).mark_prod_finished();
-                    }
-                }
-                Last::InRing => {
-                    // SAFETY: `Last::InRing` guarantees that we're the last
-                    unsafe { Ring::cleanup(self.ring) }
-                }
-                Last::NotLast => {}
+            Err(error) => {
+                cold_path();
+                Err((0, Vec::from(buffer), error))
             }
         }
     }
-}
 
-// SAFETY: The ring is designed to be accessed from different threads.
-unsafe impl<const N: usize, T, P, C> Send for Sender<N, T, P, C>
-where
-    P: Mode,
-    C: Mode,
-{
-}
+    /// Try to put as many values as possible into the channel, also returning the room left.
+    ///
+    /// This is [`try_send_burst`](Self::try_send_burst), but it also returns how many values still
+    /// fit in the channel after the burst. This can be used to decide whether to yield, without
+    /// an extra call to [`len`](Self::len).
+    ///
+    /// The room left is a snapshot of when the values were claimed. It can already be outdated
+    /// when it's returned, as consumers can take values and other producers can add them.
+    ///
+    /// # Returns
+    /// The amount of values written and the room left in the channel.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Full`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful. If the channel
+    /// has a byte budget, it returns [`Error::OverBudget`] without taking a value from `values`,
+    /// see [`bounded_with_budget`](crate::custom::bounded_with_budget).
+    ///
+    /// # Panics
+    /// Can panic if the [`ExactSizeIterator`] implementation of `I` is wrong.
+    #[inline]
+    pub fn try_send_burst_report<I>(&self, values: &mut I) -> Result<(usize, usize), Error>
+    where
+        I: Iterator<Item = T> + ExactSizeIterator,
+    {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
 
-// SAFETY: Mutable access to the producer head is guarded by atomics, but only for `Multi`.
-unsafe impl<const N: usize, T, P, C> Sync for Sender<N, T, P, C>
-where
-    P: Mode + Sync,
-    C: Mode,
-{
-}
+        ring.try_enqueue::<false, I>(values, None)
+    }
+
+    /// Try to put all values of the slice into the channel or none at all.
+    ///
+    /// This copies the values directly into the channel, which is a lot faster than
+    /// [`try_send_bulk`](Self::try_send_bulk) for small types. To put as many values in the
+    /// channel as possible, see [`try_send_slice_burst`](Self::try_send_slice_burst).
+    ///
+    /// # Returns
+    /// The amount of values written.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful. It can also
+    /// return [`Error::NotEnoughSpace`], which can also be successful on a retry. If the channel
+    /// has a byte budget and the values don't fit in it, it returns [`Error::OverBudget`].
+    #[inline]
+    pub fn try_send_slice(&self, values: &[T]) -> Result<usize, Error>
+    where
+        T: Copy,
+    {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.try_enqueue_slices::<true>(&[values])
+    }
+
+    /// Try to put as many values of the slice as possible into the channel.
+    ///
+    /// The values are copied from the start of the slice, so the returned amount is the length
+    /// of the prefix that was written. To return an error when there is not enough space for all
+    /// the values, see [`try_send_slice`](Self::try_send_slice).
+    ///
+    /// # Returns
+    /// The amount of values written.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful. If the channel
+    /// has a byte budget, only the values that fit in it are written. If the first value doesn't
+    /// fit, it returns [`Error::OverBudget`].
+    #[inline]
+    pub fn try_send_slice_burst(&self, values: &[T]) -> Result<usize, Error>
+    where
+        T: Copy,
+    {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.try_enqueue_slices::<false>(&[values])
+    }
+
+    /// Try to put the values of all `bufs` into the channel as one contiguous run, or none at all.
+    ///
+    /// Consumers see the values in the order of `bufs`, without values from other producers in
+    /// between. This avoids concatenating a message that is split over multiple buffers before
+    /// sending it.
+    ///
+    /// # Returns
+    /// The amount of values written.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful. It can also
+    /// return [`Error::NotEnoughSpace`], which can also be successful on a retry. If the channel
+    /// has a byte budget and the values don't fit in it, it returns [`Error::OverBudget`].
+    #[inline]
+    pub fn try_send_vectored(&self, bufs: &[&[T]]) -> Result<usize, Error>
+    where
+        T: Copy,
+    {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.try_enqueue_slices::<true>(bufs)
+    }
+
+    /// Reserve a slot in the channel, to send a value once it's ready.
+    ///
+    /// Sending the value with [`Permit::send`] only fails if it doesn't fit in the byte budget of
+    /// the channel, dropping the permit sends nothing.
+    /// This is only available for producer modes that implement [`Rewind`], those are
+    /// [`Single`](crate::custom::modes::Single) and [`HeadTailSync`](crate::custom::modes::HeadTailSync).
+    /// For `HeadTailSync` other senders can't send anything while the [`Permit`] exists.
+    ///
+    /// ```
+    /// let (mut sender, receiver) = ringbeam::spsc::bounded::<4, String>();
+    /// let permit = sender.reserve().unwrap();
+    /// permit.send(String::from("ready")).unwrap();
+    /// assert_eq!(receiver.try_recv().unwrap(), "ready");
+    /// ```
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Full`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful. If the channel
+    /// has a byte budget which is exhausted, it returns [`Error::OverBudget`].
+    #[inline]
+    pub fn reserve(&mut self) -> Result<Permit<'_, N, T, P, C>, Error>
+    where
+        P: Rewind,
+    {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.try_reserve()
+    }
+
+    /// Claim `n` slots in the channel to write values in place.
+    ///
+    /// The values are written through [`WriteGuard::as_mut_slices`] and sent with
+    /// [`WriteGuard::commit`], dropping the guard sends nothing. This is only available for
+    /// producer modes that implement [`Rewind`], those are [`Single`](crate::custom::modes::Single)
+    /// and [`HeadTailSync`](crate::custom::modes::HeadTailSync). For `HeadTailSync` other
+    /// senders can't send anything while the [`WriteGuard`] exists.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Full`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful. It can also
+    /// return [`Error::NotEnoughSpace`], which can also be successful on a retry. If the channel
+    /// has a byte budget which is exhausted, it returns [`Error::OverBudget`].
+    #[cfg(not(feature = "_safe_maybeuninit"))]
+    #[inline]
+    pub fn claim_write(&mut self, n: usize) -> Result<WriteGuard<'_, N, T, P, C>, Error>
+    where
+        P: Rewind,
+    {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.try_claim_write(n)
+    }
+
+    /// Create a weak handle to the channel, which doesn't keep it open.
+    ///
+    /// The channel is closed once every [`Sender`] is dropped, even if there are still weak
+    /// handles. A weak handle can be upgraded to a sender again as long as the channel is open,
+    /// see [`SenderWeak::upgrade`]. This is only available for producer modes that can be cloned.
+    ///
+    /// # Panics
+    /// Will panic if there are already `u16::MAX` weak handles.
+    #[must_use]
+    #[inline]
+    pub fn downgrade(&self) -> SenderWeak<N, T, P, C>
+    where
+        P: Sync,
+    {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.active().register_weak();
+        SenderWeak { ring: self.ring }
+    }
+
+    /// Do `self` and `other` send to the same channel.
+    ///
+    /// This compares the channels, not the values in them. Senders are the same channel if one
+    /// is a clone of the other, or if they're created from the same pointer with
+    /// [`from_raw`](Self::from_raw).
+    ///
+    /// ```
+    /// let (sender, _receiver) = ringbeam::mpmc::bounded::<4, u8>();
+    /// let (other, _other_receiver) = ringbeam::mpmc::bounded::<4, u8>();
+    /// assert!(sender.same_channel(&sender.clone()));
+    /// assert!(!sender.same_channel(&other));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn same_channel(&self, other: &Self) -> bool {
+        core::ptr::eq(self.ring, other.ring)
+    }
+
+    /// Get a pointer to the channel, to pass it through FFI.
+    ///
+    /// The pointer can be turned into a new sender with [`from_raw`](Self::from_raw), or into a
+    /// new receiver with [`Receiver::from_raw`](crate::custom::Receiver::from_raw). It doesn't keep
+    /// the channel alive, so it's only valid for as long as a handle of the channel exists.
+    #[must_use]
+    #[inline]
+    pub const fn as_raw(&self) -> *const () {
+        self.ring.cast()
+    }
+
+    /// Create a new sender from a pointer returned by [`as_raw`](Self::as_raw).
+    ///
+    /// The new sender is registered like a clone, and must be dropped like any other sender. This
+    /// is only available for producer modes that can be cloned. A C interop layer can pass the
+    /// pointer around as an opaque handle:
+    /// ```
+    /// use ringbeam::mpsc::Sender;
+    ///
+    /// extern "C" fn produce(channel: *const ()) -> bool {
+    ///     // SAFETY: `channel` is from a `Sender<4, u8>` that outlives this call.
+    ///     let Ok(sender) = (unsafe { Sender::<4, u8>::from_raw(channel) }) else {
+    ///         return false;
+    ///     };
+    ///     sender.try_send(1).is_ok()
+    /// }
+    ///
+    /// let (sender, receiver) = ringbeam::mpsc::bounded::<4, u8>();
+    /// assert!(produce(sender.as_raw()));
+    /// assert_eq!(receiver.try_recv(), Ok(1));
+    /// ```
+    ///
+    /// # Safety
+    /// - `ptr` must be returned by `as_raw` of a [`Sender`] or [`Receiver`](crate::custom::Receiver)
+    ///   with exactly the same `N`, `T`, `P`, and `C`. Any other generics are undefined behaviour,
+    ///   even if the types have the same size.
+    /// - A handle of the channel must exist for the duration of this call, so the channel isn't
+    ///   deallocated. This includes weak handles.
+    /// - `ptr` must be used in the same process it was created in.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`] or [`Error::Poisoned`] when the ring is in that state, which
+    /// includes every sender being dropped already. It can return [`Error::TooManyProducers`] if
+    /// there are already `u16::MAX - 1` producers.
+    #[inline]
+    pub unsafe fn from_raw(ptr: *const ()) -> Result<Self, Error>
+    where
+        P: Sync,
+    {
+        // SAFETY: The caller guarantees `ptr` points to a live ring with the same generics.
+        unsafe { Self::new(ptr.cast()) }
+    }
+
+    /// Close the channel for every sender, without dropping them.
+    ///
+    /// Afterwards sending returns [`Error::Closed`] for this and every other sender. The receivers
+    /// can still receive the values that are in the channel, after which they get
+    /// [`Error::Closed`] too. A value that is being sent by another sender at the same time can
+    /// still arrive after that. Closing an already closed channel does nothing.
+    ///
+    /// ```
+    /// let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+    /// sender.try_send(1).unwrap();
+    /// sender.close();
+    /// assert!(sender.is_closed());
+    /// assert_eq!(sender.try_send(2), Err(ringbeam::Error::Closed));
+    /// assert_eq!(receiver.try_recv(), Ok(1));
+    /// assert_eq!(receiver.try_recv(), Err(ringbeam::Error::Closed));
+    /// ```
+    #[inline]
+    pub fn close(&self) {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.close_prod();
+    }
+
+    /// Is the channel closed.
+    ///
+    /// This is `true` once every [`Receiver`](crate::custom::Receiver) is dropped, when either
+    /// side closed the channel with [`close`](Self::close) or [`Receiver::close`](crate::custom::Receiver::close),
+    /// or when the ring is poisoned, and stays `true` from then on (unless the poison is cleared with
+    /// [`Receiver::try_clear_poison_with`](crate::custom::Receiver::try_clear_poison_with)).
+    /// A `false` is only a snapshot, the last
+    /// receiver can be dropped right after, so a following send can still fail with [`Error::Closed`].
+    #[must_use]
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        // The tail is marked after the last receiver unregistered, so counting the receivers could
+        // report a closed channel that still accepts a few values.
+        ring.active().check_producer().is_err() || ring.is_cons_finished()
+    }
+
+    /// Is the ring poisoned.
+    ///
+    /// The ring is poisoned when a handle panics while it has access to the channel, after which
+    /// every operation returns [`Error::Poisoned`]. This checks for that without trying an
+    /// operation, and is the same for every handle of the channel, like [`Receiver::is_poisoned`](crate::custom::Receiver::is_poisoned).
+    /// It stays `true` until the poison is cleared with [`Receiver::try_clear_poison_with`](crate::custom::Receiver::try_clear_poison_with).
+    #[must_use]
+    #[inline]
+    pub fn is_poisoned(&self) -> bool {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.active().is_poisoned()
+    }
+
+    /// Check that the channel can still take values.
+    ///
+    /// Values are visible to the receivers as soon as a send returns, so there is nothing to
+    /// flush. This is for code that expects a `flush` like [`std::io::Write::flush`], and only
+    /// reports if the channel is closed.
+    ///
+    /// # Errors
+    /// Returns [`Error::Closed`] if the channel is closed or every receiver is dropped, or
+    /// [`Error::Poisoned`] if the ring is poisoned.
+    #[inline]
+    pub fn flush(&self) -> Result<(), Error> {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.active().check_producer()?;
+        if ring.is_cons_finished() {
+            cold_path();
+            return Err(Error::Closed);
+        }
+        Ok(())
+    }
+
+    /// Wait until there are at least `n` receivers for the channel.
+    ///
+    /// This is useful in fan-out setups, where sending before every receiver is created would
+    /// give the first values to only a few receivers. This waits using the default [`Backoff`].
+    ///
+    /// ```
+    /// let (sender, receiver) = ringbeam::mpmc::bounded::<4, u8>();
+    /// let receivers = [receiver.clone(), receiver];
+    /// sender.wait_for_consumers(2).unwrap();
+    /// # drop(receivers);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`Error::Closed`] if the channel is closed before there are `n` receivers, this
+    /// includes every receiver being dropped. Returns [`Error::Poisoned`] if the ring is poisoned.
+    #[inline]
+    pub fn wait_for_consumers(&self, n: u16) -> Result<(), Error> {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        let mut backoff = Backoff::new();
+        loop {
+            ring.active().check_producer()?;
+            let consumers = ring.active().consumers()?;
+            if consumers >= n {
+                return Ok(());
+            }
+            if consumers == 0 {
+                // A receiver can't be created once the last one is dropped
+                cold_path();
+                return Err(Error::Closed);
+            }
+            backoff.snooze();
+        }
+    }
+
+    /// Get access to the ring.
+    #[inline]
+    pub(crate) const fn ring(&self) -> &Ring<N, T, P, C> {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        unsafe { &*self.ring }
+    }
+
+    /// The amount of values that fit in the channel, `N - 1`.
+    ///
+    /// The same as [`capacity`](Self::capacity), for when there is no handle, see
+    /// [Capacity](crate#capacity). Fails to compile for a channel with a size chosen at runtime.
+    pub const CAPACITY: usize = {
+        assert!(N != 0, "The capacity of the channel is chosen at runtime");
+        N - 1
+    };
+
+    /// The amount of slots in the ring, `N`.
+    ///
+    /// This is one more than [`CAPACITY`](Self::CAPACITY), as one slot is always kept free.
+    /// Fails to compile for a channel with a size chosen at runtime.
+    pub const RAW_CAPACITY: usize = {
+        assert!(N != 0, "The capacity of the channel is chosen at runtime");
+        N
+    };
+
+    /// The amount of values that fit in the channel.
+    ///
+    /// One slot of the ring is used to tell a full ring apart from an empty one, so this is `N - 1`
+    /// and not `N`, see [Capacity](crate#capacity).
+    #[must_use]
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+        ring.capacity()
+    }
+
+    /// The amount of values currently in the channel.
+    ///
+    /// Values that are still being written by a [`Sender`](crate::custom::Sender) are not
+    /// included, values that are still being read by a [`RecvValues`](crate::custom::RecvValues)
+    /// are. Under concurrent access this is only an approximation, as the channel can change
+    /// before the value is returned.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.len()
+    }
+
+    /// Is the channel empty.
+    ///
+    /// Under concurrent access this is only an approximation, see [`len`](Self::len).
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Is the channel full.
+    ///
+    /// Under concurrent access this is only an approximation, see [`len`](Self::len).
+    #[must_use]
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+
+    /// The total amount of values sent to the channel, by all senders.
+    ///
+    /// The counter is updated with a relaxed atomic add once per operation, not once per value,
+    /// on its own cache line. It's meant for statistics, under concurrent access it can lag
+    /// behind the values that are actually sent.
+    ///
+    /// The atomic add has a cost: sending and receiving single values in a loop on one thread
+    /// took about 40% longer with the `metrics` feature on `x86_64`. The cost is shared by all
+    /// values of a bulk or burst operation, and the default build doesn't have the counters.
+    #[cfg(feature = "metrics")]
+    #[must_use]
+    #[inline]
+    pub fn sent_total(&self) -> u64 {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.sent_total()
+    }
+
+    /// The amount of bytes currently buffered in the channel.
+    ///
+    /// This is always 0 if the channel doesn't have a byte budget, see
+    /// [`bounded_with_budget`](crate::custom::bounded_with_budget).
+    #[must_use]
+    #[inline]
+    pub fn len_bytes(&self) -> usize {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.budget().bytes()
+    }
+}
+
+impl<const N: usize, T, C> Sender<N, T, Multi, C>
+where
+    C: Mode,
+{
+    /// Turn the last sender of a multi producer channel into a single producer sender.
+    ///
+    /// A [`Single`] sender doesn't need a compare-exchange to claim entries, so this is useful
+    /// when the other senders were only needed during setup. The receivers keep working as before.
+    ///
+    /// This only succeeds if this is the only sender, and there are no weak handles that could
+    /// create a new one. Otherwise, or if the channel is poisoned, the sender is returned unchanged.
+    /// Once downgraded, no other sender can be created for the channel. A [`Sender::from_raw`] with a
+    /// pointer from before the downgrade returns [`Error::TooManyProducers`].
+    ///
+    /// ```
+    /// let (sender, _receiver) = ringbeam::mpmc::bounded::<4, u8>();
+    /// let sender2 = sender.clone();
+    /// let sender = sender.try_downgrade_to_single().unwrap_err();
+    /// drop(sender2);
+    /// let sender = sender.try_downgrade_to_single().unwrap();
+    /// sender.try_send(1).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `self` if there are other senders or weak handles, or the channel is poisoned.
+    #[inline]
+    pub fn try_downgrade_to_single(self) -> Result<Sender<N, T, Single, C>, Self> {
+        const {
+            assert!(
+                Ring::<N, T, Multi, C>::layout().size() == Ring::<N, T, Single, C>::layout().size()
+                    && Ring::<N, T, Multi, C>::layout().align()
+                        == Ring::<N, T, Single, C>::layout().align(),
+                "Multi and Single must have the same layout"
+            );
+        };
+
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        // Other senders can only be created from this sender, from a weak handle, or from a raw
+        // pointer. Once the producers are downgraded none of them can register anymore. The last
+        // sender that was dropped released its claims, which this acquires.
+        if ring.active().downgrade_producers().is_err() {
+            return Err(self);
+        }
+
+        let this = ManuallyDrop::new(self);
+        // The registration of this sender is moved to the new sender.
+        // SAFETY: `Multi` and `Single` lay out and store the head and tail in the same way, and the
+        //         rest of `Multi` is only read by its own handles. So the ring is valid for either
+        //         mode. Every claim of the other senders is
+        //         returned, and no thread can access the producer head anymore except through
+        //         the new sender.
+        Ok(Sender {
+            ring: this.ring.cast::<Ring<N, T, Single, C>>(),
+        })
+    }
+}
+
+impl<const N: usize, T, P, C> Clone for Sender<N, T, P, C>
+where
+    P: Mode + Sync,
+    C: Mode,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        // SAFETY: because `self` is valid, `ring` is initialized and aligned.
+        unsafe { Self::new(self.ring).expect("Failed to clone producer!") }
+    }
+}
+
+impl<const N: usize, T, P, C> core::fmt::Debug for Sender<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.fmt_handle("Sender", f)
+    }
+}
+
+impl<const N: usize, T, P, C> Drop for Sender<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    #[expect(
+        clippy::missing_inline_in_public_items,
+        reason = "This function is too large too inline"
+    )]
+    fn drop(&mut self) {
+        if panicking() {
+            cold_path();
+            // SAFETY: Ring is valid before we call unregister_producer
+            unsafe {
+                (*self.ring).poison();
+            }
+        }
+        // SAFETY: Ring is valid before we call unregister_producer
+        match unsafe { (*self.ring).active().unregister_producer() } {
+            // The ring is leaked, unless the poison is cleared by the last handle.
+            Err(_) => cold_path(),
+            Ok(Last::InCategory) => {
+                // SAFETY: Even if another thread starts the ring cleanup, the cleanup will
+                // wait for the tail being marked.
+                unsafe {
+                    (*self.ring).mark_prod_finished();
+                }
+            }
+            Ok(Last::InRing) => {
+                // SAFETY: `Last::InRing` guarantees that we're the last
+                unsafe { Ring::cleanup(self.ring) }
+            }
+            Ok(Last::NotLast) => {}
+        }
+    }
+}
+
+// SAFETY: The ring is designed to be accessed from different threads. The values are moved to
+//         the thread of the receiver, so they must be `Send`.
+unsafe impl<const N: usize, T, P, C> Send for Sender<N, T, P, C>
+where
+    T: Send,
+    P: Mode,
+    C: Mode,
+{
+}
+
+// SAFETY: Mutable access to the producer head is guarded by atomics, but only for `Multi`.
+unsafe impl<const N: usize, T, P, C> Sync for Sender<N, T, P, C>
+where
+    T: Send,
+    P: Mode + Sync,
+    C: Mode,
+{
+}
+
+/// A weak handle to the sending-half of the channel.
+///
+/// Unlike a [`Sender`], this doesn't keep the channel open. It only keeps the channel allocated,
+/// so it can be upgraded to a [`Sender`] while there are still other senders. Created with
+/// [`Sender::downgrade`].
+///
+/// ```
+/// let (sender, receiver) = ringbeam::mpsc::bounded::<4, u8>();
+/// let weak = sender.downgrade();
+/// assert!(weak.upgrade().is_some());
+/// drop(sender);
+/// assert!(weak.upgrade().is_none());
+/// assert!(receiver.is_closed());
+/// ```
+pub struct SenderWeak<const N: usize, T, P, C>
+where
+    P: Mode + Sync,
+    C: Mode,
+{
+    /// The actual ring.
+    ///
+    /// This pointer is valid and aligned for the entire lifetime of [`SenderWeak`].
+    ring: *const Ring<N, T, P, C>,
+}
+
+impl<const N: usize, T, P, C> SenderWeak<N, T, P, C>
+where
+    P: Mode + Sync,
+    C: Mode,
+{
+    /// Try to get a [`Sender`] for the channel.
+    ///
+    /// Returns `None` if every [`Sender`] is already dropped, or if the ring is poisoned.
+    #[must_use]
+    #[inline]
+    pub fn upgrade(&self) -> Option<Sender<N, T, P, C>> {
+        // SAFETY: because `self` is valid, `ring` is initialized and aligned.
+        unsafe { Sender::new(self.ring).ok() }
+    }
+}
+
+impl<const N: usize, T, P, C> Clone for SenderWeak<N, T, P, C>
+where
+    P: Mode + Sync,
+    C: Mode,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        unsafe {
+            (*self.ring).active().register_weak();
+        }
+        Self { ring: self.ring }
+    }
+}
+
+impl<const N: usize, T, P, C> core::fmt::Debug for SenderWeak<N, T, P, C>
+where
+    P: Mode + Sync,
+    C: Mode,
+{
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // SAFETY: `self` is valid therefore `ring` is initialized and aligned.
+        //         No mutable aliasing in the ring except for inside the UnsafeCell.
+        let ring = unsafe { &*self.ring };
+
+        ring.fmt_handle("SenderWeak", f)
+    }
+}
+
+impl<const N: usize, T, P, C> Drop for SenderWeak<N, T, P, C>
+where
+    P: Mode + Sync,
+    C: Mode,
+{
+    #[inline]
+    fn drop(&mut self) {
+        // SAFETY: Ring is valid before we call unregister_weak
+        match unsafe { (*self.ring).active().unregister_weak() } {
+            // The ring is leaked, unless the poison is cleared by the last handle.
+            Err(_) => cold_path(),
+            Ok(Last::InRing) => {
+                // SAFETY: `Last::InRing` guarantees that we're the last, and the tails are marked
+                //         before the cleanup.
+                unsafe {
+                    (*self.ring).wait_until_finished();
+                    Ring::cleanup(self.ring);
+                }
+            }
+            Ok(Last::InCategory | Last::NotLast) => {}
+        }
+    }
+}
+
+// SAFETY: A weak handle only gives access to the ring by upgrading to a `Sender`, which is `Send`
+//         and `Sync` under the same bounds.
+unsafe impl<const N: usize, T, P, C> Send for SenderWeak<N, T, P, C>
+where
+    T: Send,
+    P: Mode + Sync,
+    C: Mode,
+{
+}
+
+// SAFETY: See `Send`.
+unsafe impl<const N: usize, T, P, C> Sync for SenderWeak<N, T, P, C>
+where
+    T: Send,
+    P: Mode + Sync,
+    C: Mode,
+{
+}
+
+/// The maximum amount of values taken from an iterator before sending them.
+const ITER_BATCH: usize = 64;
+
+/// An [`ExactSizeIterator`] that takes values from the front of a [`VecDeque`].
+///
+/// Values that aren't taken stay in the [`VecDeque`].
+struct Front<'a, T>(&'a mut VecDeque<T>);
+
+impl<T> Iterator for Front<'_, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_front()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl<T> ExactSizeIterator for Front<'_, T> {}
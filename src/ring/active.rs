@@ -1,23 +1,67 @@
-//! Logic for tracking the amount of consumers and producers.
+//! Logic for tracking the amount of consumers, producers, and weak handles.
 use crate::{
     Error,
     std::{
+        const_fn_unless_loom,
         hint::cold_path,
         sync::atomic::{
-            AtomicU32, Ordering,
+            AtomicU64, Ordering,
             Ordering::{Relaxed, SeqCst},
         },
     },
 };
 
-/// A counter of active consumers and producers that can be shared between threads.
+/// A counter of active consumers, producers, and weak handles that can be shared between threads.
 ///
-/// This is a wrapper around [`AtomicU32`], converting to and from [`Active`] on load and store.
+/// This is a wrapper around [`AtomicU64`], converting to and from [`Active`] on load and store.
 pub struct AtomicActive {
     /// The encoded form of [`Active`].
-    inner: AtomicU32,
+    inner: AtomicU64,
 }
 
+/// The bit of the encoded [`Active`] that is set when the ring is poisoned.
+const POISONED: u64 = 1 << 32;
+/// The bit of the encoded [`Active`] that is set when the producers are closed.
+const PRODUCERS_CLOSED: u64 = 1 << 33;
+/// The bit of the encoded [`Active`] that is set when the consumers are closed.
+const CONSUMERS_CLOSED: u64 = 1 << 34;
+/// The bit of the encoded [`Active`] that is set when the producers are downgraded to [`Single`].
+///
+/// [`Single`]: crate::modes::Single
+const PRODUCERS_DOWNGRADED: u64 = 1 << 35;
+/// The bit of the encoded [`Active`] that is set when the consumers are downgraded to [`Single`].
+///
+/// [`Single`]: crate::modes::Single
+const CONSUMERS_DOWNGRADED: u64 = 1 << 36;
+
+/// The ordering of a successful register.
+///
+/// A handle is only registered by cloning or upgrading a handle that keeps the ring allocated, so
+/// the counter doesn't have to publish anything. The acquire pairs with the release of
+/// [`UNREGISTER`], like `Arc::upgrade`.
+#[cfg(feature = "relaxed_orderings")]
+const REGISTER: Ordering = Ordering::Acquire;
+/// The ordering of a successful register.
+#[cfg(not(feature = "relaxed_orderings"))]
+const REGISTER: Ordering = SeqCst;
+
+/// The ordering of a successful unregister.
+///
+/// Every handle releases its accesses to the ring, and the last one acquires them before it cleans
+/// up the ring. The values in the ring are published through the tails, not through the counter.
+#[cfg(feature = "relaxed_orderings")]
+const UNREGISTER: Ordering = Ordering::AcqRel;
+/// The ordering of a successful unregister.
+#[cfg(not(feature = "relaxed_orderings"))]
+const UNREGISTER: Ordering = SeqCst;
+
+/// The ordering of the loads of a (un)register, the value is only used to compute the update.
+#[cfg(feature = "relaxed_orderings")]
+const FETCH: Ordering = Relaxed;
+/// The ordering of the loads of a (un)register.
+#[cfg(not(feature = "relaxed_orderings"))]
+const FETCH: Ordering = SeqCst;
+
 /// Before unregistering was the entity the last in its category or the entire ring.
 #[expect(clippy::enum_variant_names, reason = "Clearer this way")]
 pub enum Last {
@@ -34,17 +78,19 @@ pub enum Last {
 }
 
 impl AtomicActive {
-    /// Create a new counter with the given initial values.
-    #[inline]
-    pub fn new(consumers: u16, producers: u16) -> Self {
-        Self {
-            inner: AtomicU32::new(Active::new(consumers, producers).into()),
+    const_fn_unless_loom! {
+        /// Create a new counter with the given initial values.
+        #[inline]
+        pub fn new(consumers: u16, producers: u16) -> Self {
+            Self {
+                inner: AtomicU64::new(Active::new(consumers, producers).encode()),
+            }
         }
     }
 
     /// Loads the [`Active`] value atomically.
     ///
-    /// See [`AtomicU32::load`].
+    /// See [`AtomicU64::load`].
     #[inline]
     pub fn load(&self, ordering: Ordering) -> Active {
         self.inner.load(ordering).into()
@@ -54,7 +100,7 @@ impl AtomicActive {
     ///
     /// `f` can run multiple times but it is guaranteed that the result is only stored once.
     ///
-    /// See [`AtomicU32::fetch_update`].
+    /// See [`AtomicU64::fetch_update`].
     #[inline]
     #[expect(clippy::missing_errors_doc, reason = "Not really an error")]
     pub fn fetch_update<F>(
@@ -67,7 +113,7 @@ impl AtomicActive {
         F: FnMut(Active) -> Option<Active>,
     {
         self.inner
-            .fetch_update(set_order, fetch_order, |v| f(v.into()).map(u32::from))
+            .fetch_update(set_order, fetch_order, |v| f(v.into()).map(u64::from))
             .map(Active::from)
             .map_err(Active::from)
     }
@@ -76,11 +122,15 @@ impl AtomicActive {
     ///
     /// # Errors
     /// Returns [`Error::Closed`] if the ring is closed, [`Error::Poisoned`] if the ring is in
-    /// a poisoned state, [`Error::TooManyProducers`] if the maximum amount of producers is reached.
+    /// a poisoned state, [`Error::TooManyProducers`] if the maximum amount of producers is reached
+    /// or the producers are downgraded to a single producer.
     pub fn register_producer(&self) -> Result<(), Error> {
-        // TODO: This ordering is most likely too strict
-        self.fetch_update(SeqCst, SeqCst, |mut a| {
-            if a.producers > 0 && a.producers < u16::MAX {
+        self.fetch_update(REGISTER, FETCH, |mut a| {
+            if !a.poisoned
+                && !a.producers_downgraded
+                && a.producers > 0
+                && a.producers < u16::MAX - 1
+            {
                 a.producers += 1;
                 Some(a)
             } else {
@@ -91,10 +141,12 @@ impl AtomicActive {
         .map(|_old| ())
         .map_err(|old| {
             cold_path();
-            match old.producers {
-                0 => Error::Closed,
-                u16::MAX => Error::Poisoned,
-                _ => Error::TooManyProducers,
+            if old.poisoned {
+                Error::Poisoned
+            } else if old.producers == 0 {
+                Error::Closed
+            } else {
+                Error::TooManyProducers
             }
         })
     }
@@ -103,11 +155,37 @@ impl AtomicActive {
     ///
     /// # Errors
     /// Returns [`Error::Closed`] if the ring is closed, [`Error::Poisoned`] if the ring is in
-    /// a poisoned state, [`Error::TooManyConsumers`] if the maximum amount of consumers is reached.
+    /// a poisoned state, [`Error::TooManyConsumers`] if the maximum amount of consumers is reached
+    /// or the consumers are downgraded to a single consumer.
     pub fn register_consumer(&self) -> Result<(), Error> {
-        // TODO: This ordering is most likely too strict
-        self.fetch_update(SeqCst, SeqCst, |mut a| {
-            if a.consumers > 0 && a.consumers < u16::MAX {
+        self.register_consumer_inner(false)
+    }
+
+    /// Register the values of a claim as a consumer, see
+    /// [`RecvValues`](crate::ring::recv_values::RecvValues).
+    ///
+    /// The values only keep the ring alive and don't move the head, so unlike
+    /// [`register_consumer`](Self::register_consumer) this is allowed when the consumers are
+    /// downgraded to a single consumer.
+    ///
+    /// # Errors
+    /// Returns [`Error::Closed`] if the ring is closed, [`Error::Poisoned`] if the ring is in
+    /// a poisoned state, [`Error::TooManyConsumers`] if the maximum amount of consumers is reached.
+    pub fn register_values(&self) -> Result<(), Error> {
+        self.register_consumer_inner(true)
+    }
+
+    /// Register a consumer, even if the consumers are downgraded when `downgraded` is `true`.
+    ///
+    /// # Errors
+    /// See [`register_consumer`](Self::register_consumer).
+    fn register_consumer_inner(&self, downgraded: bool) -> Result<(), Error> {
+        self.fetch_update(REGISTER, FETCH, |mut a| {
+            if !a.poisoned
+                && (downgraded || !a.consumers_downgraded)
+                && a.consumers > 0
+                && a.consumers < u16::MAX - 1
+            {
                 a.consumers += 1;
                 Some(a)
             } else {
@@ -118,10 +196,12 @@ impl AtomicActive {
         .map(|_old| ())
         .map_err(|old| {
             cold_path();
-            match old.consumers {
-                0 => Error::Closed,
-                u16::MAX => Error::Poisoned,
-                _ => Error::TooManyConsumers,
+            if old.poisoned {
+                Error::Poisoned
+            } else if old.consumers == 0 {
+                Error::Closed
+            } else {
+                Error::TooManyConsumers
             }
         })
     }
@@ -134,38 +214,35 @@ impl AtomicActive {
     /// # Panics
     /// Can panic if producers is already 0.
     pub fn unregister_producer(&self) -> Result<Last, Error> {
-        // TODO: This ordering is most likely too strict
-        self.fetch_update(SeqCst, SeqCst, |mut a| {
-            if a.producers > 0 && a.producers < u16::MAX {
-                a.producers -= 1;
-                Some(a)
-            } else {
-                cold_path();
-                None
-            }
-        })
-        .map(|a| {
-            // If the previous value had one producer remaining, that is now zero.
-            match (a.producers, a.consumers) {
-                (1, 0) => {
-                    cold_path();
-                    Last::InRing
-                }
-                (1, _) => {
+        let old = self
+            .fetch_update(UNREGISTER, FETCH, |mut a| {
+                if a.producers > 0 {
+                    a.producers -= 1;
+                    Some(a)
+                } else {
                     cold_path();
-                    Last::InCategory
+                    None
                 }
-                (_, _) => Last::NotLast,
-            }
-        })
-        .map_err(|a| {
-            cold_path();
-            if a.producers == 0 {
+            })
+            .unwrap_or_else(|_| {
                 cold_path();
                 panic!("Producers was already 0 when trying to unregister a producer");
-            } else {
-                Error::Poisoned
+            });
+        if old.poisoned {
+            cold_path();
+            return Err(Error::Poisoned);
+        }
+        // If the previous value had one producer remaining, that is now zero.
+        Ok(match (old.producers, old.consumers, old.weak) {
+            (1, 0, 0) => {
+                cold_path();
+                Last::InRing
+            }
+            (1, _, _) => {
+                cold_path();
+                Last::InCategory
             }
+            (_, _, _) => Last::NotLast,
         })
     }
 
@@ -177,41 +254,204 @@ impl AtomicActive {
     /// # Panics
     /// Can panic if consumers is already 0.
     pub fn unregister_consumer(&self) -> Result<Last, Error> {
-        // TODO: This ordering is most likely too strict
+        let old = self
+            .fetch_update(UNREGISTER, FETCH, |mut a| {
+                if a.consumers > 0 {
+                    a.consumers -= 1;
+                    Some(a)
+                } else {
+                    cold_path();
+                    None
+                }
+            })
+            .unwrap_or_else(|_| {
+                cold_path();
+                panic!("Consumers was already 0 when trying to unregister a consumer");
+            });
+        if old.poisoned {
+            cold_path();
+            return Err(Error::Poisoned);
+        }
+        // If the previous value had one consumer remaining, that is now zero.
+        Ok(match (old.consumers, old.producers, old.weak) {
+            (1, 0, 0) => {
+                cold_path();
+                Last::InRing
+            }
+            (1, _, _) => {
+                cold_path();
+                Last::InCategory
+            }
+            (_, _, _) => Last::NotLast,
+        })
+    }
+
+    /// Downgrade the producers to a single producer, if the caller is the only producer.
+    ///
+    /// Afterwards [`register_producer`](Self::register_producer) fails, so no other producer can be
+    /// created for the ring, not even from a raw pointer. This can't be undone. The acquire pairs
+    /// with the release of the other producers when they unregistered, so their claims are
+    /// returned.
+    ///
+    /// # Errors
+    /// Returns [`Error::Poisoned`] if the ring is poisoned, and [`Error::TooManyProducers`] if
+    /// there are other producers or weak handles.
+    pub fn downgrade_producers(&self) -> Result<(), Error> {
         self.fetch_update(SeqCst, SeqCst, |mut a| {
-            if a.consumers > 0 && a.consumers < u16::MAX {
-                a.consumers -= 1;
+            if !a.poisoned && a.producers == 1 && a.weak == 0 {
+                a.producers_downgraded = true;
                 Some(a)
             } else {
-                cold_path();
                 None
             }
         })
-        .map(|a| {
-            // If the previous value had one consumer remaining, that is now zero.
-            match (a.consumers, a.producers) {
-                (1, 0) => {
-                    cold_path();
-                    Last::InRing
-                }
-                (1, _) => {
-                    cold_path();
-                    Last::InCategory
-                }
-                (_, _) => Last::NotLast,
+        .map(|_old| ())
+        .map_err(|old| {
+            if old.poisoned {
+                Error::Poisoned
+            } else {
+                Error::TooManyProducers
             }
         })
-        .map_err(|a| {
-            cold_path();
-            if a.consumers == 0 {
-                cold_path();
-                panic!("Consumers was already 0 when trying to unregister a consumer");
+    }
+
+    /// Downgrade the consumers to a single consumer, if the caller is the only consumer.
+    ///
+    /// See [`downgrade_producers`](Self::downgrade_producers).
+    ///
+    /// # Errors
+    /// Returns [`Error::Poisoned`] if the ring is poisoned, and [`Error::TooManyConsumers`] if
+    /// there are other consumers or weak handles.
+    pub fn downgrade_consumers(&self) -> Result<(), Error> {
+        self.fetch_update(SeqCst, SeqCst, |mut a| {
+            if !a.poisoned && a.consumers == 1 && a.weak == 0 {
+                a.consumers_downgraded = true;
+                Some(a)
             } else {
+                None
+            }
+        })
+        .map(|_old| ())
+        .map_err(|old| {
+            if old.poisoned {
                 Error::Poisoned
+            } else {
+                Error::TooManyConsumers
             }
         })
     }
 
+    /// Register a weak handle.
+    ///
+    /// Weak handles keep the ring allocated, but don't keep it open.
+    ///
+    /// # Panics
+    /// Will panic if there are already `u16::MAX` weak handles.
+    pub fn register_weak(&self) {
+        self.fetch_update(REGISTER, FETCH, |mut a| {
+            a.weak = a.weak.checked_add(1)?;
+            Some(a)
+        })
+        .unwrap_or_else(|_| {
+            cold_path();
+            panic!("Too many weak handles");
+        });
+    }
+
+    /// Unregister a weak handle, returns [`Last::InRing`] if it was the last entity.
+    ///
+    /// As weak handles don't have a category, it never returns [`Last::InCategory`].
+    ///
+    /// # Errors
+    /// Returns [`Error::Poisoned`] if the ring is poisoned.
+    ///
+    /// # Panics
+    /// Can panic if weak is already 0.
+    pub fn unregister_weak(&self) -> Result<Last, Error> {
+        let old = self
+            .fetch_update(UNREGISTER, FETCH, |mut a| {
+                a.weak = a.weak.checked_sub(1)?;
+                Some(a)
+            })
+            .unwrap_or_else(|_| {
+                cold_path();
+                panic!("Weak was already 0 when trying to unregister a weak handle");
+            });
+        if old.poisoned {
+            cold_path();
+            return Err(Error::Poisoned);
+        }
+        Ok(match (old.weak, old.consumers, old.producers) {
+            (1, 0, 0) => {
+                cold_path();
+                Last::InRing
+            }
+            (_, _, _) => Last::NotLast,
+        })
+    }
+
+    /// Close the producers, without unregistering them.
+    ///
+    /// Returns `true` if the producers were already closed.
+    #[inline]
+    pub fn close_producers(&self) -> bool {
+        self.inner.fetch_or(PRODUCERS_CLOSED, SeqCst) & PRODUCERS_CLOSED != 0
+    }
+
+    /// Close the consumers, without unregistering them.
+    ///
+    /// Returns `true` if the consumers were already closed.
+    #[inline]
+    pub fn close_consumers(&self) -> bool {
+        self.inner.fetch_or(CONSUMERS_CLOSED, SeqCst) & CONSUMERS_CLOSED != 0
+    }
+
+    /// Check that a producer can still send.
+    ///
+    /// # Errors
+    /// Returns [`Error::Poisoned`] if the ring is poisoned, and [`Error::Closed`] if either side
+    /// is closed.
+    #[inline]
+    pub fn check_producer(&self) -> Result<(), Error> {
+        let active = self.inner.load(Relaxed);
+        if active & (POISONED | PRODUCERS_CLOSED | CONSUMERS_CLOSED) == 0 {
+            Ok(())
+        } else if active & POISONED != 0 {
+            cold_path();
+            Err(Error::Poisoned)
+        } else {
+            cold_path();
+            Err(Error::Closed)
+        }
+    }
+
+    /// Check that a consumer can still receive.
+    ///
+    /// A consumer can keep receiving when the producers are closed, until the ring is empty.
+    ///
+    /// # Errors
+    /// Returns [`Error::Poisoned`] if the ring is poisoned, and [`Error::Closed`] if the consumers
+    /// are closed.
+    #[inline]
+    pub fn check_consumer(&self) -> Result<(), Error> {
+        let active = self.inner.load(Relaxed);
+        if active & (POISONED | CONSUMERS_CLOSED) == 0 {
+            Ok(())
+        } else if active & POISONED != 0 {
+            cold_path();
+            Err(Error::Poisoned)
+        } else {
+            cold_path();
+            Err(Error::Closed)
+        }
+    }
+
+    /// Are the producers closed.
+    #[inline]
+    pub fn is_producers_closed(&self) -> bool {
+        self.inner.load(SeqCst) & PRODUCERS_CLOSED != 0
+    }
+
     /// The amount of active producers.
     ///
     /// # Errors
@@ -219,11 +459,11 @@ impl AtomicActive {
     #[inline]
     pub fn producers(&self) -> Result<u16, Error> {
         // TODO: This ordering is most likely too strict
-        let producers = self.load(SeqCst).producers;
-        if producers == u16::MAX {
+        let active = self.load(SeqCst);
+        if active.poisoned {
             Err(Error::Poisoned)
         } else {
-            Ok(producers)
+            Ok(active.producers)
         }
     }
 
@@ -234,20 +474,31 @@ impl AtomicActive {
     #[inline]
     pub fn consumers(&self) -> Result<u16, Error> {
         // TODO: This ordering is most likely too strict
-        let consumers = self.load(SeqCst).consumers;
-        if consumers == u16::MAX {
+        let active = self.load(SeqCst);
+        if active.poisoned {
             Err(Error::Poisoned)
         } else {
-            Ok(consumers)
+            Ok(active.consumers)
         }
     }
 
     /// Poison the counter.
     ///
+    /// The amount of producers and consumers is still tracked, so it can be checked before
+    /// clearing the poison again.
+    ///
+    /// Returns `true` if the counter was already poisoned.
+    ///
     /// This is a safe function as it will only result in a memory leak, which is safe.
     #[inline]
-    pub fn poison(&self) {
-        self.inner.store(u32::MAX, Relaxed);
+    pub fn poison(&self) -> bool {
+        self.inner.fetch_or(POISONED, SeqCst) & POISONED != 0
+    }
+
+    /// Clear the poison of the counter.
+    #[inline]
+    pub fn clear_poison(&self) {
+        self.inner.fetch_and(!POISONED, SeqCst);
     }
 
     /// Is the counter poisoned.
@@ -255,20 +506,34 @@ impl AtomicActive {
     /// This is a safe function as it will only result in a memory leak, which is safe.
     #[inline]
     pub fn is_poisoned(&self) -> bool {
-        self.inner.load(Relaxed) == u32::MAX
+        self.inner.load(Relaxed) & POISONED != 0
     }
 }
 
-/// A counter of active consumers and producers.
+/// A counter of active consumers, producers, and weak handles.
+#[expect(
+    clippy::struct_excessive_bools,
+    reason = "Every bool is a flag of the encoded counter"
+)]
 pub struct Active {
     /// Amount of active consumers.
-    ///
-    /// Is `0xFFFF` if the ring is poisoned.
     pub consumers: u16,
     /// Amount of active producers.
-    ///
-    /// Is `0xFFFF` if the ring is poisoned.
     pub producers: u16,
+    /// Amount of weak handles, which don't count as active.
+    pub weak: u16,
+    /// Is the ring poisoned.
+    pub poisoned: bool,
+    /// Are the producers closed with [`Sender::close`](crate::custom::Sender::close).
+    pub producers_closed: bool,
+    /// Are the consumers closed with [`Receiver::close`](crate::custom::Receiver::close).
+    pub consumers_closed: bool,
+    /// Are the producers downgraded with
+    /// [`Sender::try_downgrade_to_single`](crate::custom::Sender::try_downgrade_to_single).
+    pub producers_downgraded: bool,
+    /// Are the consumers downgraded with
+    /// [`Receiver::try_downgrade_to_single`](crate::custom::Receiver::try_downgrade_to_single).
+    pub consumers_downgraded: bool,
 }
 
 impl Active {
@@ -278,39 +543,87 @@ impl Active {
         Self {
             consumers,
             producers,
+            weak: 0,
+            poisoned: false,
+            producers_closed: false,
+            consumers_closed: false,
+            producers_downgraded: false,
+            consumers_downgraded: false,
         }
     }
 
-    /// Have all producers and consumers shutdown.
+    /// Encode the counter in the form stored by [`AtomicActive`].
+    ///
+    /// The same as converting it into a `u64`, but usable in a `const` context.
+    #[inline]
+    const fn encode(self) -> u64 {
+        let poisoned = if self.poisoned { POISONED } else { 0 };
+        let producers_closed = if self.producers_closed {
+            PRODUCERS_CLOSED
+        } else {
+            0
+        };
+        let consumers_closed = if self.consumers_closed {
+            CONSUMERS_CLOSED
+        } else {
+            0
+        };
+        let producers_downgraded = if self.producers_downgraded {
+            PRODUCERS_DOWNGRADED
+        } else {
+            0
+        };
+        let consumers_downgraded = if self.consumers_downgraded {
+            CONSUMERS_DOWNGRADED
+        } else {
+            0
+        };
+        ((self.weak as u64) << 48)
+            | poisoned
+            | producers_closed
+            | consumers_closed
+            | producers_downgraded
+            | consumers_downgraded
+            | ((self.consumers as u64) << 16)
+            | (self.producers as u64)
+    }
+
+    /// Have all producers, consumers, and weak handles shutdown.
     ///
     /// # Errors
     /// Can return [`Error::Poisoned`] if the ring is poisoned.
     #[inline]
     pub const fn is_empty(&self) -> Result<bool, Error> {
-        if self.consumers == 0xFFFF && self.producers == 0xFFFF {
+        if self.poisoned {
             Err(Error::Poisoned)
         } else {
-            Ok(self.consumers == 0 && self.producers == 0)
+            Ok(self.consumers == 0 && self.producers == 0 && self.weak == 0)
         }
     }
 }
 
-impl From<u32> for Active {
+impl From<u64> for Active {
     #[inline]
-    fn from(value: u32) -> Self {
+    fn from(value: u64) -> Self {
         let consumers = (value >> 16) as u16;
         let producers = (value & 0xFFFF) as u16;
+        let weak = (value >> 48) as u16;
         Self {
             consumers,
             producers,
+            weak,
+            poisoned: value & POISONED != 0,
+            producers_closed: value & PRODUCERS_CLOSED != 0,
+            consumers_closed: value & CONSUMERS_CLOSED != 0,
+            producers_downgraded: value & PRODUCERS_DOWNGRADED != 0,
+            consumers_downgraded: value & CONSUMERS_DOWNGRADED != 0,
         }
     }
 }
 
-impl From<Active> for u32 {
-    #[expect(clippy::use_self, reason = "Clearer this way")]
+impl From<Active> for u64 {
     #[inline]
     fn from(active: Active) -> Self {
-        ((active.consumers as u32) << 16) | (active.producers as u32)
+        active.encode()
     }
 }
@@ -1,31 +1,64 @@
 //! The core logic of the ring.
 pub mod active;
+pub mod peek;
+pub mod permit;
 pub mod recv_values;
+#[cfg(feature = "debug")]
+pub mod stats;
+pub mod storage;
+#[cfg(feature = "async")]
+pub mod wakers;
+#[cfg(not(feature = "_safe_maybeuninit"))]
+pub mod write_guard;
 
+#[cfg(not(feature = "_safe_maybeuninit"))]
+use crate::ring::peek::PeekGuard;
+#[cfg(feature = "metrics")]
+use crate::std::sync::atomic::{AtomicU64, Ordering::Relaxed};
 use crate::{
     Error,
+    budget::Budget,
     cache_padded::CachePadded,
     consumer::Receiver,
-    modes::{Claim, Mode},
+    modes::{Advance, Claim, Mode, Rewind},
+    notify::Notify,
     producer::Sender,
-    ring::{active::AtomicActive, recv_values::RecvValues},
+    ring::{
+        active::AtomicActive, peek::Peek, permit::Permit, recv_values::RecvValues,
+        storage::RingStorage,
+    },
     std::{
         alloc::{Layout, alloc, dealloc, handle_alloc_error},
         cell::UnsafeCell,
         hint::{cold_path, spin_loop},
         mem::MaybeUninit,
-        sync::atomic::Ordering::SeqCst,
+        sync::atomic::Ordering::{Acquire, SeqCst},
     },
 };
-use core::{mem::offset_of, num::NonZeroU32, ops::Deref as _};
+use core::{fmt, mem::offset_of, num::NonZeroU32, ops::Deref as _};
+#[cfg(feature = "async")]
+use wakers::WakerList;
+#[cfg(not(feature = "_safe_maybeuninit"))]
+use write_guard::WriteGuard;
 
 /// A ring buffer.
 ///
 /// # Generics
-/// - `N`, the capacity of the channel. Must be equal to `2.pow(m)-1` where `m >= 1 && m <= 31`.
-/// - `T`, the type of messages that will be sent. `size_of::<T>()` must be a multiple of 4.
+/// - `N`, the size of the ring. Must be a power of two with `N >= 2 && N <= 2.pow(31)`, or 0 if the
+///   size is chosen at runtime with [`new_dyn`](Self::new_dyn).
+/// - `T`, the type of messages that will be sent.
 /// - `P`, the mode of head-tail synchronisation of producers, see [`Mode`].
 /// - `C`, the mode of head-tail synchronisation of consumers, see [`Mode`].
+///
+/// # Layout
+/// The fields are laid out in the order they are declared, starting with `active`,
+/// `prod_headtail`, and `cons_headtail` and ending with `data`. Every field except `allocated`,
+/// `notify`, `on_close`, `mask`, and `dyn_data` starts on its own cache line. If `T` is aligned to
+/// more than a cache line, `data` and the ring itself are aligned like `T`, which [`Ring::layout`]
+/// includes. The fields in between depend on the enabled features, so two programs only agree on
+/// the layout if they're built with the same features and the same `N`, `T`, `P`, and `C`. The
+/// order is checked at compile time when the ring is created.
+#[repr(C)]
 pub struct Ring<const N: usize, T, P, C>
 where
     P: Mode,
@@ -39,6 +72,40 @@ where
     prod_headtail: CachePadded<P>,
     /// The head and tail of the consumers.
     cons_headtail: CachePadded<C>,
+    /// The bytes currently buffered, if the ring has a byte budget.
+    budget: CachePadded<Budget<T>>,
+    /// Is the ring allocated by the global allocator, instead of placed in a [`RingStorage`].
+    allocated: bool,
+    /// Called after values are sent, if the ring has a hook.
+    notify: Option<Box<dyn Notify>>,
+    /// Called once when the last handle is dropped, if the ring has a hook.
+    on_close: Option<fn()>,
+    /// The total amount of values that were sent.
+    #[cfg(feature = "metrics")]
+    sent_total: CachePadded<AtomicU64>,
+    /// The total amount of values that were received.
+    #[cfg(feature = "metrics")]
+    recv_total: CachePadded<AtomicU64>,
+    /// The tasks waiting for values to receive.
+    #[cfg(feature = "async")]
+    recv_wakers: CachePadded<WakerList>,
+    /// The tasks waiting for room to send values.
+    #[cfg(feature = "async")]
+    send_wakers: CachePadded<WakerList>,
+    /// The size of the ring minus one if `N` is 0, see [`mask`](Self::mask).
+    mask: u32,
+    /// The data of the ring if `N` is 0, it's allocated right after the ring.
+    ///
+    /// This is a pointer into the allocation, as a reference to the ring doesn't cover the data
+    /// after it. It's null if `N` isn't 0.
+    ///
+    /// A ring with a size chosen at compile time carries these two unused fields, but they're
+    /// never loaded: `N` is a constant, so every `N == 0` check is resolved when the ring is
+    /// monomorphized.
+    ///
+    /// # Safety
+    /// The same as for `data`.
+    dyn_data: *mut UnsafeCell<MaybeUninit<T>>,
     /// The actual data of the ring.
     ///
     /// # Safety
@@ -52,6 +119,14 @@ where
     P: Mode,
     C: Mode,
 {
+    /// The layout of the allocation of the ring.
+    ///
+    /// This doesn't include the data of a ring with a size chosen at runtime.
+    #[inline]
+    pub const fn layout() -> Layout {
+        Layout::new::<Self>()
+    }
+
     /// Create the ring returning a sender and receiver.
     #[expect(
         clippy::new_ret_no_self,
@@ -66,26 +141,145 @@ where
         producer_settings: P::Settings,
         consumer_settings: C::Settings,
     ) -> (Sender<N, T, P, C>, Receiver<N, T, P, C>) {
-        // Check input
+        Self::new_with_budget(producer_settings, consumer_settings, Budget::unlimited())
+    }
+
+    /// Create the ring with a byte budget returning a sender and receiver.
+    pub fn new_with_budget(
+        producer_settings: P::Settings,
+        consumer_settings: C::Settings,
+        budget: Budget<T>,
+    ) -> (Sender<N, T, P, C>, Receiver<N, T, P, C>) {
+        Self::new_with_notify(producer_settings, consumer_settings, budget, None)
+    }
+
+    /// Create the ring with a byte budget and a [`Notify`] hook returning a sender and receiver.
+    pub fn new_with_notify(
+        producer_settings: P::Settings,
+        consumer_settings: C::Settings,
+        budget: Budget<T>,
+        notify: Option<Box<dyn Notify>>,
+    ) -> (Sender<N, T, P, C>, Receiver<N, T, P, C>) {
+        Self::new_with_hooks(producer_settings, consumer_settings, budget, notify, None)
+    }
+
+    /// Create the ring with a byte budget, a [`Notify`] hook, and a hook for when the last handle
+    /// is dropped, returning a sender and receiver.
+    pub fn new_with_hooks(
+        producer_settings: P::Settings,
+        consumer_settings: C::Settings,
+        budget: Budget<T>,
+        notify: Option<Box<dyn Notify>>,
+        on_close: Option<fn()>,
+    ) -> (Sender<N, T, P, C>, Receiver<N, T, P, C>) {
         const {
             assert!(
-                N >= 2 && N.is_power_of_two() && N <= u32::MAX as usize,
-                "Requested capacity was not a power of two"
+                N != 0,
+                "The size of the ring `N` must be a power of two from 2 up to and including 2^31"
             );
-            // Loom's UnsafeCell type is larger, because it tracks (mutable) references.
-            #[cfg(not(any(
-                feature = "_loom",
-                feature = "_shuttle",
-                feature = "_safe_maybeuninit"
-            )))]
+        }
+        Self::alloc(
+            N,
+            producer_settings,
+            consumer_settings,
+            budget,
+            notify,
+            on_close,
+        )
+    }
+
+    /// Create a ring with `size` slots and a byte budget returning a sender and receiver.
+    ///
+    /// The size is chosen at runtime, so `N` must be 0.
+    ///
+    /// # Panics
+    /// Will panic if `size` is not a power of two with `size >= 2 && size <= 2.pow(31)`.
+    pub fn new_dyn(
+        size: usize,
+        producer_settings: P::Settings,
+        consumer_settings: C::Settings,
+        budget: Budget<T>,
+    ) -> (Sender<N, T, P, C>, Receiver<N, T, P, C>) {
+        const {
+            assert!(N == 0, "The size of the ring is chosen at compile time");
+        }
+        assert!(
+            size >= 2 && size.is_power_of_two() && u32::try_from(size).is_ok(),
+            "The size of the ring must be a power of two from 2 up to and including 2^31"
+        );
+        Self::alloc(
+            size,
+            producer_settings,
+            consumer_settings,
+            budget,
+            None,
+            None,
+        )
+    }
+
+    /// Create the ring in `storage` returning a sender and receiver.
+    ///
+    /// The ring is not allocated, and the memory is not deallocated when the last handle is
+    /// dropped.
+    pub fn new_in(
+        storage: &'static mut RingStorage<N, T, P, C>,
+        producer_settings: P::Settings,
+        consumer_settings: C::Settings,
+        budget: Budget<T>,
+    ) -> (Sender<N, T, P, C>, Receiver<N, T, P, C>) {
+        const {
             assert!(
-                size_of::<T>() == size_of::<UnsafeCell<MaybeUninit<T>>>(),
-                "Missed optimisation"
+                N != 0,
+                "The size of the ring `N` must be a power of two from 2 up to and including 2^31"
             );
         }
+        let ptr = storage.as_mut_ptr().cast::<u8>();
+        // SAFETY: The storage is valid and aligned for `Self`, and as it's borrowed mutably forever
+        //         nobody else can access it.
+        unsafe {
+            Self::init(
+                ptr,
+                N,
+                producer_settings,
+                consumer_settings,
+                budget,
+                None,
+                None,
+                false,
+            )
+        }
+    }
 
+    /// The layout of the allocation of a ring with `size` slots and the offset of the data if `N`
+    /// is 0.
+    ///
+    /// A ring with a size chosen at runtime stores its data right after the ring, in the same
+    /// allocation.
+    ///
+    /// # Panics
+    /// Will panic if the ring doesn't fit in the address space.
+    fn alloc_layout(size: usize) -> (Layout, usize) {
+        if N == 0 {
+            let (layout, offset) = Layout::array::<UnsafeCell<MaybeUninit<T>>>(size)
+                .and_then(|data| Layout::new::<Self>().extend(data))
+                .expect("Requested capacity is too large");
+            (layout.pad_to_align(), offset)
+        } else {
+            (Self::layout(), 0)
+        }
+    }
+
+    /// Allocate a ring with `size` slots returning a sender and receiver.
+    fn alloc(
+        size: usize,
+        producer_settings: P::Settings,
+        consumer_settings: C::Settings,
+        budget: Budget<T>,
+        notify: Option<Box<dyn Notify>>,
+        on_close: Option<fn()>,
+    ) -> (Sender<N, T, P, C>, Receiver<N, T, P, C>) {
         // Allocate the ring
-        let layout = Layout::new::<Self>();
+        let (layout, _) = Self::alloc_layout(size);
         // SAFETY: Layout is valid
         let ptr = unsafe { alloc(layout) };
         if ptr.is_null() {
@@ -93,12 +287,79 @@ where
             handle_alloc_error(layout);
         }
 
+        // SAFETY: The allocation is valid and aligned for `Self` and its data.
+        unsafe {
+            Self::init(
+                ptr,
+                size,
+                producer_settings,
+                consumer_settings,
+                budget,
+                notify,
+                on_close,
+                true,
+            )
+        }
+    }
+
+    /// Initialize the ring with `size` slots at `ptr` returning a sender and receiver.
+    ///
+    /// # Safety
+    /// `ptr` must be valid for writes of [`Ring::alloc_layout`], aligned, and not be accessed by
+    /// anything else for as long as the ring is alive. If `allocated` is `true`, it must be
+    /// allocated with the global allocator and that layout. `size` must be `N` if `N` isn't 0.
+    #[expect(
+        clippy::too_many_arguments,
+        clippy::too_many_lines,
+        reason = "All the parts of the ring are initialized here"
+    )]
+    unsafe fn init(
+        ptr: *mut u8,
+        size: usize,
+        producer_settings: P::Settings,
+        consumer_settings: C::Settings,
+        budget: Budget<T>,
+        notify: Option<Box<dyn Notify>>,
+        on_close: Option<fn()>,
+        allocated: bool,
+    ) -> (Sender<N, T, P, C>, Receiver<N, T, P, C>) {
+        // Check input
+        const {
+            assert!(
+                N == 0 || (N >= 2 && N.is_power_of_two() && N <= 1 << 31),
+                "The size of the ring `N` must be a power of two from 2 up to and including 2^31"
+            );
+            // The slice functions treat the data as a `[T]`. This holds for any `T`, as
+            // `UnsafeCell` and `MaybeUninit` are `repr(transparent)`.
+            // Loom's UnsafeCell type is larger, because it tracks (mutable) references.
+            #[cfg(not(any(
+                feature = "_loom",
+                feature = "_shuttle",
+                feature = "_safe_maybeuninit"
+            )))]
+            assert!(
+                size_of::<T>() == size_of::<UnsafeCell<MaybeUninit<T>>>(),
+                "The entries of the ring are not laid out as `[T]`"
+            );
+            // See the layout of `Ring`
+            assert!(
+                offset_of!(Self, active) == 0
+                    && offset_of!(Self, prod_headtail) == size_of::<CachePadded<AtomicActive>>()
+                    && offset_of!(Self, cons_headtail)
+                        == offset_of!(Self, prod_headtail) + size_of::<CachePadded<P>>()
+                    && offset_of!(Self, data)
+                        + size_of::<CachePadded<[UnsafeCell<MaybeUninit<T>>; N]>>()
+                        == size_of::<Self>(),
+                "The fields of the ring are not laid out in declaration order"
+            );
+        }
+
         // Initialize the ring
-        // SAFETY: Pointer is not null. The allocation is valid and aligned.
         #[expect(
             clippy::cast_ptr_alignment,
             reason = "The pointers are guaranteed aligned by Layout"
         )]
+        // SAFETY: Caller guarantees the pointer is valid and aligned.
         unsafe {
             ptr.add(offset_of!(Self, active))
                 .cast::<CachePadded<AtomicActive>>()
@@ -109,11 +370,61 @@ where
             ptr.add(offset_of!(Self, cons_headtail))
                 .cast::<CachePadded<C>>()
                 .write(CachePadded::new(C::new_with(consumer_settings)));
+            ptr.add(offset_of!(Self, budget))
+                .cast::<CachePadded<Budget<T>>>()
+                .write(CachePadded::new(budget));
+            ptr.add(offset_of!(Self, allocated))
+                .cast::<bool>()
+                .write(allocated);
+            ptr.add(offset_of!(Self, notify))
+                .cast::<Option<Box<dyn Notify>>>()
+                .write(notify);
+            ptr.add(offset_of!(Self, on_close))
+                .cast::<Option<fn()>>()
+                .write(on_close);
+            #[cfg(feature = "metrics")]
+            ptr.add(offset_of!(Self, sent_total))
+                .cast::<CachePadded<AtomicU64>>()
+                .write(CachePadded::new(AtomicU64::new(0)));
+            #[cfg(feature = "metrics")]
+            ptr.add(offset_of!(Self, recv_total))
+                .cast::<CachePadded<AtomicU64>>()
+                .write(CachePadded::new(AtomicU64::new(0)));
+            #[cfg(feature = "async")]
+            ptr.add(offset_of!(Self, recv_wakers))
+                .cast::<CachePadded<WakerList>>()
+                .write(CachePadded::new(WakerList::new()));
+            #[cfg(feature = "async")]
+            ptr.add(offset_of!(Self, send_wakers))
+                .cast::<CachePadded<WakerList>>()
+                .write(CachePadded::new(WakerList::new()));
             ptr.add(offset_of!(Self, data))
                 .cast::<CachePadded<[UnsafeCell<MaybeUninit<T>>; N]>>()
                 .write(CachePadded::new(core::array::from_fn(|_| {
                     UnsafeCell::new(MaybeUninit::uninit())
                 })));
+            #[expect(
+                clippy::cast_possible_truncation,
+                reason = "The size is at most 2.pow(31)"
+            )]
+            ptr.add(offset_of!(Self, mask))
+                .cast::<u32>()
+                .write((size - 1) as u32);
+            let dyn_data = if N == 0 {
+                let (_, data_offset) = Self::alloc_layout(size);
+                let dyn_data = ptr.add(data_offset).cast::<UnsafeCell<MaybeUninit<T>>>();
+                for i in 0..size {
+                    dyn_data
+                        .add(i)
+                        .write(UnsafeCell::new(MaybeUninit::uninit()));
+                }
+                dyn_data
+            } else {
+                core::ptr::null_mut()
+            };
+            ptr.add(offset_of!(Self, dyn_data))
+                .cast::<*mut UnsafeCell<MaybeUninit<T>>>()
+                .write(dyn_data);
         }
 
         // The ring is now initialized and valid
@@ -132,7 +443,9 @@ where
 
     /// Deallocate the ring buffer.
     ///
-    /// It will wait for both `cons_headtail` and `prod_headtail` to be marked as finished.
+    /// The values that are still in the ring are dropped. If the ring is placed in a
+    /// [`RingStorage`], only the fields are dropped and the memory is left alone. It will wait for both `cons_headtail` and `prod_headtail` to be marked as finished,
+    /// and then call the `on_close` hook if there is one.
     ///
     /// # Safety
     /// The caller *must* be the last with access to the ring and already unregistered (i.e. `self.active == 0`).
@@ -159,7 +472,45 @@ where
             }
         }
 
-        let layout = Layout::new::<Self>();
+        // Drop the values that were sent but never received
+        if core::mem::needs_drop::<T>() {
+            // SAFETY: Ring is still valid before we call dealloc
+            let ring = unsafe { &*ring };
+            let cons_tail = ring.cons_headtail.load_tail(Acquire) & 0x7FFF_FFFF;
+            let data = ring.data();
+            for i in 0..ring.len() as u32 {
+                let index = (cons_tail.wrapping_add(i) & ring.mask()) as usize;
+                // SAFETY: Every claim is returned, so the entries from the consumer tail up to the
+                //         producer tail are initialized and nobody else has access to them.
+                drop(unsafe { data[index].with_mut(|p| (*p).assume_init_take()) });
+            }
+        }
+
+        // Only the last handle calls this function, after both sides are done with the ring
+        // SAFETY: Ring is still valid before we call dealloc
+        if let Some(on_close) = unsafe { (*ring).on_close } {
+            on_close();
+        }
+
+        // SAFETY: The hook is initialized and nobody else has access to the ring anymore.
+        unsafe {
+            core::ptr::drop_in_place((&raw const (*ring).notify).cast_mut());
+        }
+
+        // SAFETY: The wakers are initialized and nobody else has access to the ring anymore.
+        #[cfg(feature = "async")]
+        unsafe {
+            core::ptr::drop_in_place((&raw const (*ring).recv_wakers).cast_mut());
+            core::ptr::drop_in_place((&raw const (*ring).send_wakers).cast_mut());
+        }
+
+        // SAFETY: Ring is still valid before we call dealloc
+        if !unsafe { (*ring).allocated } {
+            return;
+        }
+
+        // SAFETY: Ring is still valid before we call dealloc
+        let (layout, _) = Self::alloc_layout(unsafe { (*ring).size() });
         // SAFETY: `ring` is allocated as this function must only be called once, and the layout
         //         is the same.
         unsafe {
@@ -167,12 +518,44 @@ where
         }
     }
 
+    /// Wait until both the prod and the cons tail are marked as finished.
+    ///
+    /// The last weak handle must call this before the cleanup, as the last producer and consumer
+    /// can still be marking their tails.
+    #[inline]
+    pub fn wait_until_finished(&self) {
+        while !(self.prod_headtail.is_finished() && self.cons_headtail.is_finished()) {
+            spin_loop();
+        }
+    }
+
+    /// Is the prod tail marked as finished.
+    ///
+    /// The last producer marks the tail after unregistering, so this can be `false` while there
+    /// are no producers left. Once it's `true`, no value can be sent anymore.
+    #[inline]
+    pub fn is_prod_finished(&self) -> bool {
+        self.prod_headtail.is_finished()
+    }
+
+    /// Is the cons tail marked as finished.
+    ///
+    /// See [`is_prod_finished`](Self::is_prod_finished).
+    #[inline]
+    pub fn is_cons_finished(&self) -> bool {
+        self.cons_headtail.is_finished()
+    }
+
     /// Mark the prod tail as finished.
     ///
     /// # Safety
     /// This *must* only be called by the last producer.
     #[inline]
     pub unsafe fn mark_prod_finished(&self) {
+        // The ring can be deallocated as soon as the tail is marked, the waiting tasks see that
+        // there are no producers left instead.
+        #[cfg(feature = "async")]
+        self.recv_wakers.wake_all();
         self.prod_headtail.mark_finished();
     }
 
@@ -182,18 +565,367 @@ where
     /// This *must* only be called by the last consumer.
     #[inline]
     pub unsafe fn mark_cons_finished(&self) {
+        // The ring can be deallocated as soon as the tail is marked, the waiting tasks see that
+        // there are no consumers left instead.
+        #[cfg(feature = "async")]
+        self.send_wakers.wake_all();
         self.cons_headtail.mark_finished();
     }
 
+    /// Close the producers, so consumers get [`Error::Closed`] once the ring is empty.
+    ///
+    /// The producers stay registered, so the ring is only cleaned up once they're dropped.
+    #[inline]
+    pub fn close_prod(&self) {
+        // Wake the tasks after closing, so they see that it's closed
+        #[cfg(feature = "async")]
+        if !self.active.close_producers() {
+            self.recv_wakers.wake_all();
+            self.send_wakers.wake_all();
+        }
+        #[cfg(not(feature = "async"))]
+        self.active.close_producers();
+    }
+
+    /// Close the consumers, so producers get [`Error::Closed`] right away.
+    ///
+    /// The consumers stay registered, so the ring is only cleaned up once they're dropped.
+    #[inline]
+    pub fn close_cons(&self) {
+        // Wake the tasks after closing, so they see that it's closed
+        #[cfg(feature = "async")]
+        if !self.active.close_consumers() {
+            self.recv_wakers.wake_all();
+            self.send_wakers.wake_all();
+        }
+        #[cfg(not(feature = "async"))]
+        self.active.close_consumers();
+    }
+
     /// Get access to the producer and consumer tracking.
     pub fn active(&self) -> &AtomicActive {
         &self.active
     }
 
+    /// The amount of values in the ring.
+    ///
+    /// This is the distance between the consumer tail and the producer tail. Values that are
+    /// currently being written are not included, values that are currently being read are.
+    /// The tails are loaded one after the other, so under concurrent access the result can
+    /// already be outdated when it's returned.
+    #[inline]
+    pub fn len(&self) -> usize {
+        let cons_tail = self.cons_headtail.load_tail(Acquire) & 0x7FFF_FFFF;
+        let prod_tail = self.prod_headtail.load_tail(Acquire) & 0x7FFF_FFFF;
+        (prod_tail.wrapping_sub(cons_tail) & self.mask()) as usize
+    }
+
+    /// The position of the consumer tail, without the finished mark.
+    ///
+    /// This moves every time values are received, so it can be used to see if the consumers are
+    /// making progress.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn cons_tail(&self) -> u32 {
+        self.cons_headtail.load_tail(Acquire) & 0x7FFF_FFFF
+    }
+
+    /// Format the state of the ring for the [`Debug`](fmt::Debug) impl of a handle.
+    ///
+    /// This only loads atomics, so it doesn't take a claim and can be called at any time. The
+    /// values in the ring are not printed.
+    ///
+    /// # Errors
+    /// Returns an error if writing to `f` fails.
+    #[inline]
+    pub fn fmt_handle(&self, name: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let active = self.active.load(SeqCst);
+        f.debug_struct(name)
+            .field("capacity", &self.capacity())
+            .field("producer_mode", &core::any::type_name::<P>())
+            .field("consumer_mode", &core::any::type_name::<C>())
+            .field("len", &self.len())
+            .field("producers", &active.producers)
+            .field("consumers", &active.consumers)
+            .field("poisoned", &active.poisoned)
+            .field("weak", &active.weak)
+            .finish_non_exhaustive()
+    }
+
+    /// Get access to the tasks waiting for values to receive.
+    #[cfg(feature = "async")]
+    #[inline]
+    pub fn recv_wakers(&self) -> &WakerList {
+        &self.recv_wakers
+    }
+
+    /// Get access to the tasks waiting for room to send values.
+    #[cfg(feature = "async")]
+    #[inline]
+    pub fn send_wakers(&self) -> &WakerList {
+        &self.send_wakers
+    }
+
+    /// The total amount of values that were sent.
+    #[cfg(feature = "metrics")]
+    #[inline]
+    pub fn sent_total(&self) -> u64 {
+        self.sent_total.load(Relaxed)
+    }
+
+    /// The total amount of values that were received.
+    #[cfg(feature = "metrics")]
+    #[inline]
+    pub fn recv_total(&self) -> u64 {
+        self.recv_total.load(Relaxed)
+    }
+
+    /// Get access to the byte budget.
+    #[inline]
+    pub fn budget(&self) -> &Budget<T> {
+        &self.budget
+    }
+
+    /// The size of the ring minus one, used to wrap an index around the ring.
+    #[inline]
+    pub const fn mask(&self) -> u32 {
+        if N == 0 { self.mask } else { N as u32 - 1 }
+    }
+
+    /// The amount of slots in the ring.
+    #[inline]
+    pub const fn size(&self) -> usize {
+        self.mask() as usize + 1
+    }
+
+    /// The amount of values that fit in the ring, one slot is always kept free.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        self.mask() as usize
+    }
+
     /// Get a reference to the data part of the ring.
     #[inline]
-    fn data(&self) -> &[UnsafeCell<MaybeUninit<T>>; N] {
-        self.data.deref()
+    fn data(&self) -> &[UnsafeCell<MaybeUninit<T>>] {
+        if N == 0 {
+            // SAFETY: `dyn_data` points to the `size` initialized slots after the ring, which
+            //         live as long as the ring.
+            unsafe { core::slice::from_raw_parts(self.dyn_data, self.size()) }
+        } else {
+            self.data.deref()
+        }
+    }
+
+    /// Call the [`Notify`] hook, if any, after `n` values were sent.
+    #[inline]
+    fn notify_sent(&self, n: usize) {
+        if let Some(notify) = &self.notify {
+            notify.notify(n);
+        }
+    }
+
+    /// Replace [`Error::Closed`] with [`Error::Poisoned`] if the ring is poisoned.
+    ///
+    /// A poisoned ring marks both tails as finished, which [`ModeInner::move_head`](crate::modes::ModeInner::move_head)
+    /// reports as closed. If the producers are closed with [`close_prod`](Self::close_prod) their
+    /// tail isn't marked, so a consumer that runs out of items is told here that the ring is closed.
+    ///
+    /// Only called when a claim failed, so it's kept out of line.
+    #[cold]
+    #[inline(never)]
+    fn closed_or_poisoned(&self, err: Error) -> Error {
+        match err {
+            Error::Closed => {
+                cold_path();
+                if self.active.is_poisoned() {
+                    Error::Poisoned
+                } else {
+                    Error::Closed
+                }
+            }
+            Error::Empty if self.active.is_producers_closed() => {
+                cold_path();
+                Error::Closed
+            }
+            Error::NotEnoughItems if self.active.is_producers_closed() => {
+                cold_path();
+                Error::NotEnoughItemsAndClosed
+            }
+            err => err,
+        }
+    }
+
+    /// Release the `reserved` bytes of an enqueue that failed with `err`, see [`try_enqueue`](Self::try_enqueue).
+    #[cold]
+    #[inline(never)]
+    fn enqueue_failed(&self, err: Error, reserved: Option<usize>) -> Error {
+        if let Some(bytes) = reserved {
+            self.budget.release(bytes);
+        }
+        self.closed_or_poisoned(err)
+    }
+
+    /// Copy `values` into the ring starting at `offset`, wrapping around at the end of the ring.
+    ///
+    /// # Safety
+    /// The caller must own a [`Claim`] for the `values.len()` entries starting at `offset`.
+    unsafe fn write_slice(&self, offset: u32, values: &[T])
+    where
+        T: Copy,
+    {
+        #[cfg(not(feature = "_safe_maybeuninit"))]
+        {
+            let offset = offset as usize;
+            let first = values.len().min(self.size() - offset);
+            // `UnsafeCell` and `MaybeUninit` are `repr(transparent)`, so the data is laid out as `[T]`.
+            let data = self.data().as_ptr().cast::<T>().cast_mut();
+            // SAFETY: The caller owns the claim, so we have exclusive access to these entries.
+            //         `first <= size - offset` and `values.len() - first <= offset` so both copies
+            //         stay inside the ring.
+            unsafe {
+                data.add(offset)
+                    .copy_from_nonoverlapping(values.as_ptr(), first);
+                data.copy_from_nonoverlapping(values.as_ptr().add(first), values.len() - first);
+            }
+        }
+        #[cfg(feature = "_safe_maybeuninit")]
+        {
+            let data = self.data();
+            for (i, value) in values.iter().enumerate() {
+                let index = (offset as usize).wrapping_add(i) & self.mask() as usize;
+                // SAFETY: The caller owns the claim, so we have exclusive access to this index.
+                unsafe {
+                    data[index].with_mut(|p| (*p).write(*value));
+                }
+            }
+        }
+    }
+
+    /// Copy `out.len()` values from the ring starting at `offset` into `out`, wrapping around at
+    /// the end of the ring.
+    ///
+    /// # Safety
+    /// The caller must own a [`Claim`] for the `out.len()` initialized entries starting at `offset`.
+    unsafe fn read_slice(&self, offset: u32, out: &mut [T])
+    where
+        T: Copy,
+    {
+        #[cfg(not(feature = "_safe_maybeuninit"))]
+        {
+            let offset = offset as usize;
+            let first = out.len().min(self.size() - offset);
+            // `UnsafeCell` and `MaybeUninit` are `repr(transparent)`, so the data is laid out as `[T]`.
+            let data = self.data().as_ptr().cast::<T>();
+            // SAFETY: The caller owns the claim, so we have exclusive access to these initialized
+            //         entries. `first <= size - offset` and `out.len() - first <= offset` so both
+            //         copies stay inside the ring.
+            unsafe {
+                out.as_mut_ptr()
+                    .copy_from_nonoverlapping(data.add(offset), first);
+                out.as_mut_ptr()
+                    .add(first)
+                    .copy_from_nonoverlapping(data, out.len() - first);
+            }
+        }
+        #[cfg(feature = "_safe_maybeuninit")]
+        {
+            let data = self.data();
+            for (i, value) in out.iter_mut().enumerate() {
+                let index = (offset as usize).wrapping_add(i) & self.mask() as usize;
+                // SAFETY: The caller owns the claim, so we have exclusive access to this
+                //         initialized index.
+                *value = unsafe { data[index].with_mut(|p| (*p).assume_init_take()) };
+            }
+        }
+    }
+
+    /// Try to enqueue the values of all `slices` to the ring as one contiguous run.
+    ///
+    /// If `EXACT` the enqueue will fail if there isn't room for all the values, otherwise it
+    /// can enqueue a prefix of the values.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful. If `EXACT` it
+    /// can also return [`Error::NotEnoughSpace`], which can also be successful on a retry. If the
+    /// first value, or if `EXACT` all values, don't fit in the byte budget it returns
+    /// [`Error::OverBudget`].
+    pub fn try_enqueue_slices<const EXACT: bool>(&self, slices: &[&[T]]) -> Result<usize, Error>
+    where
+        T: Copy,
+    {
+        let total = slices.iter().map(|slice| slice.len()).sum::<usize>();
+        let Some(len) = NonZeroU32::new(claim_len(total)) else {
+            cold_path();
+            return Ok(0);
+        };
+
+        self.active.check_producer()?;
+
+        // The values are measured before they're claimed, so only the bytes of the values that
+        // fit are reserved
+        let values = || slices.iter().flat_map(|slice| slice.iter());
+        let (len, reserved) = if self.budget.is_limited() {
+            let (count, bytes) = self.budget.reserve_prefix(|| {
+                values()
+                    .take(len.get() as usize)
+                    .map(|v| self.budget.measure(v))
+            });
+            let count = count as u32;
+            match NonZeroU32::new(count) {
+                Some(count) if !EXACT || count == len => (count, Some(bytes)),
+                _ => {
+                    cold_path();
+                    self.budget.release(bytes);
+                    return Err(Error::OverBudget);
+                }
+            }
+        } else {
+            (len, None)
+        };
+
+        let claim = self
+            .prod_headtail
+            .move_head::<true, EXACT, false, _>(self.cons_headtail.deref(), len, self.mask())
+            .map_err(|err| {
+                cold_path();
+                self.enqueue_failed(err, reserved)
+            })?;
+
+        let n = claim.entries() as usize;
+        if reserved.is_some() && n < len.get() as usize {
+            cold_path();
+            // Release the bytes of the values that didn't fit in the ring
+            let bytes = values()
+                .take(len.get() as usize)
+                .skip(n)
+                .map(|v| self.budget.measure(v))
+                .sum();
+            self.budget.release(bytes);
+        }
+        let mut offset = claim.start();
+        let mut left = n;
+        for slice in slices {
+            let slice = &slice[..slice.len().min(left)];
+            // SAFETY: The claim gives us exclusive access to the `left` entries at `offset`.
+            unsafe {
+                self.write_slice(offset, slice);
+            }
+            offset = offset.wrapping_add(slice.len() as u32) & self.mask();
+            left -= slice.len();
+            if left == 0 {
+                break;
+            }
+        }
+
+        self.prod_headtail.update_tail(claim, self.mask());
+        #[cfg(feature = "metrics")]
+        self.sent_total.fetch_add(n as u64, Relaxed);
+        #[cfg(feature = "async")]
+        self.recv_wakers.wake_all();
+        self.notify_sent(n);
+
+        Ok(n)
     }
 
     /// Try to enqueue `n` items to the ring.
@@ -201,43 +933,63 @@ where
     /// If `EXACT` the enqueue will fail if there isn't room for at least `n` entries, otherwise it
     /// can enqueue less than `n` items, leaving the remainder of the items in the iterator.
     ///
+    /// `reserved` is the amount of bytes the caller already reserved in the [`Budget`] for all
+    /// the values. It's released again if the enqueue fails, if less than all values are enqueued
+    /// the caller releases the bytes of the rest. If it's `None` and the ring has a budget, the
+    /// enqueue fails with [`Error::OverBudget`]. The values can only be measured after they're
+    /// taken from the iterator, so the values that don't fit would be lost.
+    ///
+    /// An iterator with more than `u32::MAX` items is treated as if it has `u32::MAX` items, which
+    /// is more than the ring can hold.
+    ///
+    /// Returns the amount of items enqueued and the room that was left in the ring after the enqueue.
+    ///
     /// # Errors
     /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
     /// one of those states. The last one indicates that retrying can be successful. If `EXACT` it
-    /// can also return [`Error::NotEnoughSpace`], which can also be successful on a retry.
+    /// can also return [`Error::NotEnoughSpace`], which can also be successful on a retry. If
+    /// `reserved` is `None` it can return [`Error::OverBudget`].
     ///
     /// # Panics
     /// Can panic if the [`ExactSizeIterator`] implementation of `I` is wrong.
-    pub fn try_enqueue<const EXACT: bool, I>(&self, values: &mut I) -> Result<usize, Error>
+    pub fn try_enqueue<const EXACT: bool, I>(
+        &self,
+        values: &mut I,
+        reserved: Option<usize>,
+    ) -> Result<(usize, usize), Error>
     where
         I: Iterator<Item = T> + ExactSizeIterator,
     {
-        let Some(len) = NonZeroU32::new(values.len() as u32) else {
+        let Some(len) = NonZeroU32::new(claim_len(values.len())) else {
             cold_path();
-            return Ok(0);
+            return Ok((0, self.capacity() - self.len()));
         };
 
+        if let Err(error) = self.active.check_producer() {
+            cold_path();
+            return Err(self.enqueue_failed(error, reserved));
+        }
+        if reserved.is_none() && self.budget.is_limited() {
+            cold_path();
+            return Err(Error::OverBudget);
+        }
+
         let claim = self
             .prod_headtail
-            .move_head::<N, true, EXACT, _>(self.cons_headtail.deref(), len)
+            .move_head::<true, EXACT, false, _>(self.cons_headtail.deref(), len, self.mask())
             .map_err(|err| {
                 cold_path();
-                if err == Error::Closed {
-                    cold_path();
-                    if self.active.is_poisoned() {
-                        Error::Poisoned
-                    } else {
-                        Error::Closed
-                    }
-                } else {
-                    err
-                }
+                self.enqueue_failed(err, reserved)
             })?;
+        debug_assert!(
+            claim.entries() as usize <= values.len(),
+            "Claimed more entries than there are values"
+        );
 
         let data = self.data();
         let mut total = 0;
         for (i, value) in values.take(claim.entries() as usize).enumerate() {
-            let offset = i.wrapping_add(claim.start() as usize) & (N - 1);
+            let offset = i.wrapping_add(claim.start() as usize) & self.mask() as usize;
             // SAFETY: Our Claim gives exclusive access to this index
             unsafe {
                 data[offset].with_mut(|p| (*p).write(value));
@@ -253,16 +1005,107 @@ where
         }
 
         let n = claim.entries() as usize;
+        let remaining = claim.remaining() as usize;
 
-        self.prod_headtail.update_tail::<N>(claim);
+        self.prod_headtail.update_tail(claim, self.mask());
+        #[cfg(feature = "metrics")]
+        self.sent_total.fetch_add(n as u64, Relaxed);
+        #[cfg(feature = "async")]
+        self.recv_wakers.wake_all();
+        self.notify_sent(n);
 
-        Ok(n)
+        Ok((n, remaining))
+    }
+
+    /// Try to enqueue at most `max` items from `values`, which doesn't need to know its length.
+    ///
+    /// The entries are claimed first, and the entries that are left when `values` ends are given
+    /// back. If pulling a value from `values` panics, the values before it are still sent.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Full`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful. If the ring
+    /// has a byte budget it returns [`Error::OverBudget`], see [`try_enqueue`](Self::try_enqueue).
+    pub fn try_enqueue_from<I>(&self, values: &mut I, max: usize) -> Result<usize, Error>
+    where
+        P: Rewind,
+        I: Iterator<Item = T>,
+    {
+        /// Sends the written values and gives back the other entries when dropped.
+        struct Fill<'a, const N: usize, T, P, C>
+        where
+            P: Rewind,
+            C: Mode,
+        {
+            /// The ring of the claim.
+            ring: &'a Ring<N, T, P, C>,
+            /// The claimed entries.
+            claim: Option<Claim>,
+            /// The amount of entries at the start of the claim that are written.
+            written: u32,
+        }
+
+        impl<const N: usize, T, P, C> Drop for Fill<'_, N, T, P, C>
+        where
+            P: Rewind,
+            C: Mode,
+        {
+            fn drop(&mut self) {
+                if let Some(claim) = self.claim.take() {
+                    self.ring.rewind_prod(claim, self.written);
+                }
+            }
+        }
+
+        let Some(len) = NonZeroU32::new(max.min(self.size() - 1) as u32) else {
+            cold_path();
+            return Ok(0);
+        };
+
+        self.active.check_producer()?;
+        if self.budget.is_limited() {
+            cold_path();
+            return Err(Error::OverBudget);
+        }
+
+        let claim = self
+            .prod_headtail
+            .move_head::<true, false, false, _>(self.cons_headtail.deref(), len, self.mask())
+            .map_err(|err| {
+                cold_path();
+                self.closed_or_poisoned(err)
+            })?;
+
+        let data = self.data();
+        let (start, entries) = (claim.start() as usize, claim.entries());
+        let mut fill = Fill {
+            ring: self,
+            claim: Some(claim),
+            written: 0,
+        };
+        while fill.written < entries {
+            let Some(value) = values.next() else {
+                cold_path();
+                break;
+            };
+            let offset = start.wrapping_add(fill.written as usize) & self.mask() as usize;
+            // SAFETY: Our Claim gives exclusive access to this index
+            unsafe {
+                data[offset].with_mut(|p| (*p).write(value));
+            }
+            fill.written += 1;
+        }
+
+        let written = fill.written as usize;
+        drop(fill);
+        Ok(written)
     }
 
     /// Try to dequeue `n` items from the ring.
     ///
     /// If `EXACT` the dequeue will fail if there aren't at least `n` entries, otherwise it can
-    /// return less than `n` items.
+    /// return less than `n` items. If `CONTIGUOUS` the claim stops at the end of the ring, so it
+    /// can also return less than `n` items while more are available.
     ///
     /// # Errors
     /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
@@ -271,52 +1114,498 @@ where
     /// also return [`Error::NotEnoughItemsAndClosed`] where retrying can be successful with `EXACT: false`.
     ///
     /// If there are `u16::MAX - 1` consumers it can also return [`Error::TooManyConsumers`].
-    pub fn try_dequeue<const EXACT: bool>(
+    pub fn try_dequeue<const EXACT: bool, const CONTIGUOUS: bool>(
         &self,
         n: usize,
     ) -> Result<RecvValues<N, T, P, C>, Error> {
-        let Some(len) = NonZeroU32::new(n as u32) else {
+        let Some(len) = NonZeroU32::new(claim_len(n)) else {
             cold_path();
             return Ok(RecvValues::new_empty());
         };
+        // Consumers can still claim items in a poisoned ring, but `RecvValues` can't register.
+        self.active.check_consumer()?;
         let claim = self
             .cons_headtail
-            .move_head::<N, false, EXACT, _>(self.prod_headtail.deref(), len)
+            .move_head::<false, EXACT, CONTIGUOUS, _>(self.prod_headtail.deref(), len, self.mask())
             .map_err(|err| {
                 cold_path();
-                if err == Error::Closed {
-                    cold_path();
-                    if self.active.is_poisoned() {
-                        Error::Poisoned
-                    } else {
-                        Error::Closed
-                    }
-                } else {
-                    err
-                }
+                self.closed_or_poisoned(err)
             })?;
 
         // SAFETY: The ring is valid
         unsafe { RecvValues::new(self, claim) }
     }
 
+    /// Try to dequeue `n` items from the ring, moving them to the end of `out`.
+    ///
+    /// If `EXACT` the dequeue will fail if there aren't at least `n` entries, otherwise it can
+    /// move less than `n` items.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful. If `EXACT` it
+    /// can also return [`Error::NotEnoughItems`], which can also be successful on a retry. It can
+    /// also return [`Error::NotEnoughItemsAndClosed`] where retrying can be successful with `EXACT: false`.
+    pub fn try_dequeue_into<const EXACT: bool>(
+        &self,
+        n: usize,
+        out: &mut Vec<T>,
+    ) -> Result<usize, Error> {
+        let Some(len) = NonZeroU32::new(claim_len(n)) else {
+            cold_path();
+            return Ok(0);
+        };
+        self.active.check_consumer()?;
+        // Reserve before claiming, so a failing allocation can't panic while holding the claim
+        out.reserve(n.min(self.size() - 1));
+        let claim = self
+            .cons_headtail
+            .move_head::<false, EXACT, false, _>(self.prod_headtail.deref(), len, self.mask())
+            .map_err(|err| {
+                cold_path();
+                self.closed_or_poisoned(err)
+            })?;
+
+        let n = claim.entries() as usize;
+        let data = self.data();
+        let mut bytes = 0;
+        for i in 0..n {
+            let offset = i.wrapping_add(claim.start() as usize) & self.mask() as usize;
+            // SAFETY: The claim gives us exclusive access to the `n` initialized entries at its start.
+            let value = unsafe { data[offset].with_mut(|p| (*p).assume_init_take()) };
+            if self.budget.is_limited() {
+                bytes += self.budget.measure(&value);
+            }
+            out.push(value);
+        }
+        self.budget.release(bytes);
+        self.return_claim_cons(claim);
+
+        Ok(n)
+    }
+
+    /// Try to dequeue exactly `K` items from the ring into an array.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful. It can also
+    /// return [`Error::NotEnoughItems`], which can also be successful on a retry, or
+    /// [`Error::NotEnoughItemsAndClosed`].
+    ///
+    /// # Panics
+    /// Will panic if `K` isn't smaller than the size of the ring.
+    pub fn try_dequeue_array<const K: usize>(&self) -> Result<[T; K], Error> {
+        /// The first `len` values of `array` are initialized, and dropped with the guard.
+        struct Partial<T, const K: usize> {
+            /// The values that have been moved out of the ring.
+            array: [core::mem::MaybeUninit<T>; K],
+            /// The amount of values that are initialized.
+            len: usize,
+        }
+
+        impl<T, const K: usize> Drop for Partial<T, K> {
+            fn drop(&mut self) {
+                for value in &mut self.array[..self.len] {
+                    // SAFETY: The first `len` values are initialized
+                    unsafe {
+                        value.assume_init_drop();
+                    }
+                }
+            }
+        }
+
+        const {
+            assert!(
+                N == 0 || K < N,
+                "The array is larger than the capacity of the ring"
+            );
+        }
+        assert!(
+            K < self.size(),
+            "The array is larger than the capacity of the ring"
+        );
+        let Some(len) = NonZeroU32::new(K as u32) else {
+            cold_path();
+            return Ok(core::array::from_fn(|_| unreachable!()));
+        };
+        self.active.check_consumer()?;
+        let claim = self
+            .cons_headtail
+            .move_head::<false, true, false, _>(self.prod_headtail.deref(), len, self.mask())
+            .map_err(|err| {
+                cold_path();
+                self.closed_or_poisoned(err)
+            })?;
+
+        let data = self.data();
+        let mut partial = Partial {
+            array: [const { core::mem::MaybeUninit::uninit() }; K],
+            len: 0,
+        };
+        let mut bytes = 0;
+        while partial.len < K {
+            let offset = partial.len.wrapping_add(claim.start() as usize) & self.mask() as usize;
+            // SAFETY: The claim gives us exclusive access to the `K` initialized entries at its start.
+            let value = unsafe { data[offset].with_mut(|p| (*p).assume_init_take()) };
+            // If measuring panics, the guard drops the values that were already taken
+            if self.budget.is_limited() {
+                bytes += self.budget.measure(&value);
+            }
+            partial.array[partial.len].write(value);
+            partial.len += 1;
+        }
+        self.budget.release(bytes);
+        self.return_claim_cons(claim);
+
+        let partial = core::mem::ManuallyDrop::new(partial);
+        // SAFETY: All `K` values are initialized and the guard won't drop them.
+        //         `MaybeUninit<T>` has the same layout as `T`.
+        Ok(unsafe { core::ptr::read((&raw const partial.array).cast::<[T; K]>()) })
+    }
+
+    /// Try to dequeue up to the total length of `outs` items from the ring as one contiguous run,
+    /// filling the slices in order.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful.
+    pub fn try_dequeue_slices(&self, outs: &mut [&mut [T]]) -> Result<usize, Error>
+    where
+        T: Copy,
+    {
+        let total = outs.iter().map(|out| out.len()).sum::<usize>();
+        let Some(len) = NonZeroU32::new(claim_len(total)) else {
+            cold_path();
+            return Ok(0);
+        };
+        self.active.check_consumer()?;
+        let claim = self
+            .cons_headtail
+            .move_head::<false, false, false, _>(self.prod_headtail.deref(), len, self.mask())
+            .map_err(|err| {
+                cold_path();
+                self.closed_or_poisoned(err)
+            })?;
+
+        let n = claim.entries() as usize;
+        let mut offset = claim.start();
+        let mut left = n;
+        let mut bytes = 0;
+        for out in outs {
+            let len = out.len().min(left);
+            let out = &mut out[..len];
+            // SAFETY: The claim gives us exclusive access to the `left` initialized entries at `offset`.
+            unsafe {
+                self.read_slice(offset, out);
+            }
+            if self.budget.is_limited() {
+                bytes += out.iter().map(|v| self.budget.measure(v)).sum::<usize>();
+            }
+            offset = offset.wrapping_add(len as u32) & self.mask();
+            left -= len;
+            if left == 0 {
+                break;
+            }
+        }
+        self.budget.release(bytes);
+        self.return_claim_cons(claim);
+
+        Ok(n)
+    }
+
+    /// Try to dequeue one item from the ring without registering a consumer.
+    ///
+    /// This is used by producers to evict the oldest item, so `C` must allow multiple threads
+    /// to move the consumer head at the same time.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful.
+    pub fn try_dequeue_one(&self) -> Result<T, Error>
+    where
+        C: Sync,
+    {
+        let claim = self
+            .cons_headtail
+            .move_head::<false, true, false, _>(
+                self.prod_headtail.deref(),
+                NonZeroU32::MIN,
+                self.mask(),
+            )
+            .map_err(|err| {
+                cold_path();
+                self.closed_or_poisoned(err)
+            })?;
+
+        // SAFETY: The claim is for exactly one initialized entry at its start.
+        let value =
+            unsafe { self.data()[claim.start() as usize].with_mut(|p| (*p).assume_init_take()) };
+        self.budget.release(self.budget.measure(&value));
+        self.return_claim_cons(claim);
+
+        Ok(value)
+    }
+
+    /// Try to claim the next item in the ring without consuming it.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful.
+    pub fn try_peek(&self) -> Result<Peek<'_, N, T, P, C>, Error>
+    where
+        C: Rewind,
+    {
+        self.active.check_consumer()?;
+        let claim = self
+            .cons_headtail
+            .move_head::<false, true, false, _>(
+                self.prod_headtail.deref(),
+                NonZeroU32::MIN,
+                self.mask(),
+            )
+            .map_err(|err| {
+                cold_path();
+                self.closed_or_poisoned(err)
+            })?;
+
+        // SAFETY: The claim is for exactly one initialized entry
+        Ok(unsafe { Peek::new(self, claim) })
+    }
+
+    /// Try to claim one uninitialized entry to send a value later.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Full`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful. If the byte
+    /// budget is exhausted it returns [`Error::OverBudget`].
+    pub fn try_reserve(&self) -> Result<Permit<'_, N, T, P, C>, Error>
+    where
+        P: Rewind,
+    {
+        self.active.check_producer()?;
+        self.budget.check()?;
+
+        let claim = self
+            .prod_headtail
+            .move_head::<true, true, false, _>(
+                self.cons_headtail.deref(),
+                NonZeroU32::MIN,
+                self.mask(),
+            )
+            .map_err(|err| {
+                cold_path();
+                self.closed_or_poisoned(err)
+            })?;
+
+        // SAFETY: The claim is a producer claim of exactly one entry in this ring.
+        Ok(unsafe { Permit::new(self, claim) })
+    }
+
+    /// Try to claim `n` uninitialized entries to write values in place.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Full`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful. It can also
+    /// return [`Error::NotEnoughSpace`], which can also be successful on a retry. If the byte
+    /// budget is exhausted it returns [`Error::OverBudget`].
+    #[cfg(not(feature = "_safe_maybeuninit"))]
+    pub fn try_claim_write(&self, n: usize) -> Result<WriteGuard<'_, N, T, P, C>, Error>
+    where
+        P: Rewind,
+    {
+        let Some(len) = NonZeroU32::new(claim_len(n)) else {
+            cold_path();
+            // SAFETY: A guard without a claim doesn't access the ring.
+            return Ok(unsafe { WriteGuard::new(self, None) });
+        };
+
+        self.active.check_producer()?;
+        self.budget.check()?;
+
+        let claim = self
+            .prod_headtail
+            .move_head::<true, true, false, _>(self.cons_headtail.deref(), len, self.mask())
+            .map_err(|err| {
+                cold_path();
+                self.closed_or_poisoned(err)
+            })?;
+
+        // SAFETY: The claim is a producer claim in this ring.
+        Ok(unsafe { WriteGuard::new(self, Some(claim)) })
+    }
+
+    /// Try to claim up to `n` items in the ring without consuming them.
+    ///
+    /// If `n` is zero the guard is empty.
+    ///
+    /// # Errors
+    /// Can return [`Error::Closed`], [`Error::Poisoned`], or [`Error::Empty`] if the ring is in
+    /// one of those states. The last one indicates that retrying can be successful.
+    #[cfg(not(feature = "_safe_maybeuninit"))]
+    pub fn try_peek_bulk(&self, n: usize) -> Result<PeekGuard<'_, N, T, P, C>, Error>
+    where
+        C: Rewind,
+    {
+        let Some(len) = NonZeroU32::new(claim_len(n)) else {
+            cold_path();
+            // SAFETY: A guard without a claim doesn't access the ring.
+            return Ok(unsafe { PeekGuard::new(self, None) });
+        };
+        self.active.check_consumer()?;
+        let claim = self
+            .cons_headtail
+            .move_head::<false, false, false, _>(self.prod_headtail.deref(), len, self.mask())
+            .map_err(|err| {
+                cold_path();
+                self.closed_or_poisoned(err)
+            })?;
+
+        // SAFETY: The claim is a consumer claim in this ring.
+        Ok(unsafe { PeekGuard::new(self, Some(claim)) })
+    }
+
+    /// Give back a producer [`Claim`] of which only the first `used` entries were written.
+    #[inline]
+    pub fn rewind_prod(&self, claim: Claim, used: u32)
+    where
+        P: Rewind,
+    {
+        self.prod_headtail.rewind(claim, used, self.mask());
+        #[cfg(feature = "metrics")]
+        self.sent_total.fetch_add(u64::from(used), Relaxed);
+        #[cfg(feature = "async")]
+        if used != 0 {
+            self.recv_wakers.wake_all();
+        }
+        if used != 0 {
+            self.notify_sent(used as usize);
+        }
+    }
+
+    /// Give back a [`Claim`] of which only the first `used` entries were consumed.
+    #[inline]
+    pub fn rewind_cons(&self, claim: Claim, used: u32)
+    where
+        C: Rewind,
+    {
+        self.cons_headtail.rewind(claim, used, self.mask());
+        #[cfg(feature = "metrics")]
+        self.recv_total.fetch_add(u64::from(used), Relaxed);
+        #[cfg(feature = "async")]
+        if used != 0 {
+            self.send_wakers.wake_all();
+        }
+    }
+
+    /// Return the first `used` entries of a [`Claim`] that were consumed, keeping the rest claimed.
+    #[inline]
+    pub fn advance_cons(&self, claim: &mut Claim, used: u32)
+    where
+        C: Advance,
+    {
+        self.cons_headtail.advance(claim, used, self.mask());
+        #[cfg(feature = "metrics")]
+        self.recv_total.fetch_add(u64::from(used), Relaxed);
+        #[cfg(feature = "async")]
+        self.send_wakers.wake_all();
+    }
+
     /// Used by [`RecvValues`] to return its [`Claim`].
     #[inline]
     pub fn return_claim_cons(&self, claim: Claim) {
-        self.cons_headtail.update_tail::<N>(claim);
+        #[cfg(feature = "metrics")]
+        let n = claim.entries();
+        self.cons_headtail.update_tail(claim, self.mask());
+        #[cfg(feature = "metrics")]
+        self.recv_total.fetch_add(u64::from(n), Relaxed);
+        #[cfg(feature = "async")]
+        self.send_wakers.wake_all();
+    }
+
+    /// Clear the poison of the ring, so it can be used again.
+    ///
+    /// Claims that were never returned are given up, see [`ModeInner::clear_poison`](crate::modes::ModeInner::clear_poison).
+    /// The values in those entries are leaked. The values between the consumer head and the
+    /// producer tail are kept, and the byte budget is recalculated from them. If `has_producers`
+    /// is `false` the producer tail is marked as finished again, so the channel stays closed.
+    ///
+    /// # Safety
+    /// The caller must be the only one with access to the ring, and nobody may hold a [`Claim`].
+    /// `has_producers` must be `true` if there is an active producer.
+    pub unsafe fn clear_poison(&self, has_producers: bool) {
+        // SAFETY: The caller guarantees nobody else accesses the headtails or holds a claim.
+        let (prod, cons) = unsafe {
+            (
+                self.prod_headtail.clear_poison::<true>(),
+                self.cons_headtail.clear_poison::<false>(),
+            )
+        };
+        if self.budget.is_limited() {
+            let data = self.data();
+            let len = prod.wrapping_sub(cons) as usize & self.mask() as usize;
+            let bytes = (0..len)
+                .map(|i| {
+                    let index = (cons as usize).wrapping_add(i) & self.mask() as usize;
+                    // SAFETY: Entries between the consumer head and the producer tail are
+                    //         initialized, and nobody else can access them. The value is put
+                    //         back right after measuring.
+                    unsafe {
+                        data[index].with_mut(|p| {
+                            let value = (*p).assume_init_take();
+                            let bytes = self.budget.measure(&value);
+                            (*p).write(value);
+                            bytes
+                        })
+                    }
+                })
+                .sum();
+            self.budget.set_bytes(bytes);
+        }
+        if !has_producers {
+            self.prod_headtail.mark_finished();
+        }
+        self.active.clear_poison();
     }
 
     /// Poison the ring.
     ///
     /// After calling this function every function will return [`Error::Poisoned`] or panic.
+    /// Handles that are dropped afterwards don't clean up the ring, so it's leaked unless the
+    /// poison is cleared with [`clear_poison`](Self::clear_poison).
     ///
     /// This **should** be called if a [`Receiver`], [`Sender`], or [`RecvValues`] panics while holding
     /// a [`Claim`]. Otherwise, the ring will be stuck.
     #[inline]
     pub fn poison(&self) {
         cold_path();
-        self.active.poison();
-        self.cons_headtail.mark_finished();
-        self.prod_headtail.mark_finished();
+        if self.active.poison() {
+            // Multiple handles can be dropped while panicking, only the first marks the tails.
+            return;
+        }
+        // A side can already be finished if all its handles were dropped before.
+        if !self.cons_headtail.is_finished() {
+            self.cons_headtail.mark_finished();
+        }
+        if !self.prod_headtail.is_finished() {
+            self.prod_headtail.mark_finished();
+        }
+        #[cfg(feature = "async")]
+        {
+            self.recv_wakers.wake_all();
+            self.send_wakers.wake_all();
+        }
+    }
+}
+
+/// The amount of entries to claim for a request of `n` entries.
+///
+/// A claim is at most `u32::MAX` entries, which is more than any ring holds. Larger requests are
+/// clamped instead of truncated, so they can't turn into a claim of the wrong size.
+#[inline]
+const fn claim_len(n: usize) -> u32 {
+    if n > u32::MAX as usize {
+        u32::MAX
+    } else {
+        n as u32
     }
 }
@@ -0,0 +1,281 @@
+//! Logic for looking at the next value in a channel without consuming it.
+//!
+//! A peek is a normal claim that is given back with [`Rewind::rewind`] instead of returned, so the
+//! same code serves [`Single`](crate::custom::modes::Single) and
+//! [`HeadTailSync`](crate::custom::modes::HeadTailSync). With `HeadTailSync` the other consumers
+//! wait until the claim is given back, so the peeked entries can't move while they're borrowed.
+#[cfg(not(feature = "_safe_maybeuninit"))]
+use crate::std::hint::cold_path;
+use crate::{
+    modes::{Claim, Mode, Rewind},
+    ring::Ring,
+};
+use core::ops::Deref;
+
+/// A view of the next value in the channel.
+///
+/// The value stays in the channel, unless it is taken with [`Peek::take`]. While this exists,
+/// no other value can be received from the channel.
+///
+/// Only consumers with a [`Rewind`] mode can peek, as the value must be given back when this is
+/// dropped.
+pub struct Peek<'a, const N: usize, T, P, C>
+where
+    P: Mode,
+    C: Rewind,
+{
+    /// The ring the value is from.
+    ring: &'a Ring<N, T, P, C>,
+    /// The claim of the entry and the value that was moved out of it.
+    ///
+    /// The value is put back in the entry when giving back the claim. This is `None` once the
+    /// claim is given back.
+    claim_and_value: Option<(Claim, T)>,
+}
+
+impl<'a, const N: usize, T, P, C> Peek<'a, N, T, P, C>
+where
+    P: Mode,
+    C: Rewind,
+{
+    /// Create a new peek from a claim of one entry.
+    ///
+    /// # Safety
+    /// `claim` must be a consumer claim of exactly one entry in `ring`.
+    #[inline]
+    pub(crate) unsafe fn new(ring: &'a Ring<N, T, P, C>, claim: Claim) -> Self {
+        debug_assert_eq!(claim.entries(), 1, "Can only peek one entry");
+        // SAFETY: The Claim guarantees we have exclusive access to this index and that
+        //         there is a valid, initialized item at the index.
+        let value =
+            unsafe { ring.data()[claim.start() as usize].with_mut(|p| (*p).assume_init_take()) };
+        Self {
+            ring,
+            claim_and_value: Some((claim, value)),
+        }
+    }
+
+    /// Take the value out of the channel.
+    #[must_use]
+    #[inline]
+    pub fn take(mut self) -> T {
+        let (claim, value) = self
+            .claim_and_value
+            .take()
+            .unwrap_or_else(|| unreachable!());
+        let budget = self.ring.budget();
+        budget.release(budget.measure(&value));
+        self.ring.return_claim_cons(claim);
+        value
+    }
+}
+
+impl<const N: usize, T, P, C> Deref for Peek<'_, N, T, P, C>
+where
+    P: Mode,
+    C: Rewind,
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        let (_, value) = self
+            .claim_and_value
+            .as_ref()
+            .unwrap_or_else(|| unreachable!());
+        value
+    }
+}
+
+impl<const N: usize, T, P, C> Drop for Peek<'_, N, T, P, C>
+where
+    P: Mode,
+    C: Rewind,
+{
+    #[inline]
+    fn drop(&mut self) {
+        if let Some((claim, value)) = self.claim_and_value.take() {
+            // SAFETY: The Claim guarantees we have exclusive access to this index and we took
+            //         the value out of it, so it's uninitialized.
+            unsafe {
+                self.ring.data()[claim.start() as usize].with_mut(|p| (*p).write(value));
+            }
+            self.ring.rewind_cons(claim, 0);
+        }
+    }
+}
+
+/// The next value in the channel, which is received again unless it's acknowledged.
+///
+/// The value is only removed from the channel with [`Ack::ack`]. If this is dropped without
+/// acknowledging, including when a panic unwinds while holding it, the value stays in the channel
+/// and is returned by the next receive. See [`Receiver::recv_ack`](crate::custom::Receiver::recv_ack).
+#[must_use = "The value stays in the channel unless it's acknowledged"]
+pub struct Ack<'a, const N: usize, T, P, C>
+where
+    P: Mode,
+    C: Rewind,
+{
+    /// The value, which is given back when this is dropped.
+    peek: Peek<'a, N, T, P, C>,
+}
+
+impl<'a, const N: usize, T, P, C> Ack<'a, N, T, P, C>
+where
+    P: Mode,
+    C: Rewind,
+{
+    /// Create a new acknowledgment from a peek.
+    #[inline]
+    pub(crate) const fn new(peek: Peek<'a, N, T, P, C>) -> Self {
+        Self { peek }
+    }
+
+    /// Acknowledge the value, removing it from the channel.
+    #[inline]
+    pub fn ack(self) -> T {
+        self.peek.take()
+    }
+}
+
+impl<const N: usize, T, P, C> Deref for Ack<'_, N, T, P, C>
+where
+    P: Mode,
+    C: Rewind,
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.peek
+    }
+}
+
+/// A view of the next values in the channel.
+///
+/// The values stay in the channel, unless they are consumed with [`PeekGuard::consume`]. While
+/// this exists, no other value can be received from the channel.
+///
+/// Only consumers with a [`Rewind`] mode can peek, as the values that are not consumed must be
+/// given back.
+#[cfg(not(feature = "_safe_maybeuninit"))]
+pub struct PeekGuard<'a, const N: usize, T, P, C>
+where
+    P: Mode,
+    C: Rewind,
+{
+    /// The ring the values are from.
+    ring: &'a Ring<N, T, P, C>,
+    /// The claim of the entries.
+    ///
+    /// This is `None` once the claim is given back.
+    claim: Option<Claim>,
+}
+
+#[cfg(not(feature = "_safe_maybeuninit"))]
+impl<'a, const N: usize, T, P, C> PeekGuard<'a, N, T, P, C>
+where
+    P: Mode,
+    C: Rewind,
+{
+    /// Create a new peek from a consumer claim.
+    ///
+    /// # Safety
+    /// `claim` must be a consumer claim in `ring`, or `None` for a guard without values.
+    #[inline]
+    pub(crate) const unsafe fn new(ring: &'a Ring<N, T, P, C>, claim: Option<Claim>) -> Self {
+        Self { ring, claim }
+    }
+
+    /// The amount of values that can be looked at.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.claim
+            .as_ref()
+            .map_or(0, |claim| claim.entries() as usize)
+    }
+
+    /// Are there no values to look at.
+    ///
+    /// This is only `true` after the claim is given back, or if the guard was created for zero
+    /// values.
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.claim.is_none()
+    }
+
+    /// Get the values as two slices.
+    ///
+    /// The first slice contains the values up to the end of the ring, the second one the values
+    /// that wrapped around to the start of the ring. The second slice is empty if the values
+    /// didn't wrap around.
+    #[must_use]
+    #[inline]
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let Some(claim) = &self.claim else {
+            cold_path();
+            return (&[], &[]);
+        };
+        let offset = claim.start() as usize;
+        let len = claim.entries() as usize;
+        let first = len.min(self.ring.size() - offset);
+
+        // SAFETY: `UnsafeCell` and `MaybeUninit` are `repr(transparent)`, so the data is laid out
+        //         as `[T]`. The Claim guarantees nobody else writes to these entries, and they
+        //         are initialized. `first <= size - offset` and `len - first <= offset` so both slices
+        //         stay inside the ring.
+        unsafe {
+            let data = self.ring.data().as_ptr().cast::<T>();
+            (
+                core::slice::from_raw_parts(data.add(offset), first),
+                core::slice::from_raw_parts(data, len - first),
+            )
+        }
+    }
+
+    /// Consume the first `count` values, the other values stay in the channel.
+    ///
+    /// The consumed values are dropped.
+    ///
+    /// # Panics
+    /// Will panic if `count` is larger than [`len`](Self::len).
+    #[inline]
+    pub fn consume(mut self, count: usize) {
+        assert!(count <= self.len(), "Consumed more values than were peeked");
+        let Some(claim) = self.claim.take() else {
+            cold_path();
+            return;
+        };
+        let data = self.ring.data();
+        let budget = self.ring.budget();
+        let mut bytes = 0;
+        for i in 0..count {
+            let index = (claim.start() as usize).wrapping_add(i) & self.ring.mask() as usize;
+            // SAFETY: The Claim guarantees we have exclusive access to this index and that
+            //         there is a valid, initialized item at the index.
+            let value = unsafe { data[index].with_mut(|p| (*p).assume_init_take()) };
+            if budget.is_limited() {
+                bytes += budget.measure(&value);
+            }
+            drop(value);
+        }
+        budget.release(bytes);
+        self.ring.rewind_cons(claim, count as u32);
+    }
+}
+
+#[cfg(not(feature = "_safe_maybeuninit"))]
+impl<const N: usize, T, P, C> Drop for PeekGuard<'_, N, T, P, C>
+where
+    P: Mode,
+    C: Rewind,
+{
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(claim) = self.claim.take() {
+            self.ring.rewind_cons(claim, 0);
+        }
+    }
+}
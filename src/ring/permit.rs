@@ -0,0 +1,82 @@
+//! Logic for reserving room in a channel before the value is ready.
+use crate::{
+    modes::{Claim, Mode, Rewind},
+    ring::Ring,
+    std::hint::cold_path,
+};
+
+/// A reserved slot in the channel, see [`Sender::reserve`](crate::custom::Sender::reserve).
+///
+/// Sending a value with [`Permit::send`] can only fail if the value doesn't fit in the byte budget
+/// of the channel, as the room is already claimed. If this is dropped instead, the slot is given
+/// back to the channel and nothing is sent. While this exists, no other value can be sent to the
+/// channel.
+///
+/// Only producers with a [`Rewind`] mode can reserve a slot, as the slot must be given back when
+/// this is dropped.
+pub struct Permit<'a, const N: usize, T, P, C>
+where
+    P: Rewind,
+    C: Mode,
+{
+    /// The ring the slot is from.
+    ring: &'a Ring<N, T, P, C>,
+    /// The claim of the slot.
+    ///
+    /// This is `None` once the claim is given back.
+    claim: Option<Claim>,
+}
+
+impl<'a, const N: usize, T, P, C> Permit<'a, N, T, P, C>
+where
+    P: Rewind,
+    C: Mode,
+{
+    /// Create a new permit from a claim of one entry.
+    ///
+    /// # Safety
+    /// `claim` must be a producer claim of exactly one entry in `ring`.
+    #[inline]
+    pub(crate) unsafe fn new(ring: &'a Ring<N, T, P, C>, claim: Claim) -> Self {
+        debug_assert_eq!(claim.entries(), 1, "Can only reserve one entry");
+        Self {
+            ring,
+            claim: Some(claim),
+        }
+    }
+
+    /// Send the value in the reserved slot.
+    ///
+    /// # Errors
+    /// Returns `value` if the channel has a byte budget and the value would bring it over the
+    /// limit. The slot is given back to the channel in that case.
+    #[inline]
+    pub fn send(mut self, value: T) -> Result<(), T> {
+        let budget = self.ring.budget();
+        // The bytes must be reserved before the value is visible, so consumers never subtract first
+        if budget.reserve(budget.measure(&value)).is_err() {
+            cold_path();
+            return Err(value);
+        }
+        let claim = self.claim.take().unwrap_or_else(|| unreachable!());
+        // SAFETY: The Claim guarantees we have exclusive access to this uninitialized index.
+        unsafe {
+            self.ring.data()[claim.start() as usize].with_mut(|p| (*p).write(value));
+        }
+        self.ring.rewind_prod(claim, 1);
+        Ok(())
+    }
+}
+
+impl<const N: usize, T, P, C> Drop for Permit<'_, N, T, P, C>
+where
+    P: Rewind,
+    C: Mode,
+{
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(claim) = self.claim.take() {
+            self.ring.rewind_prod(claim, 0);
+        }
+    }
+}
@@ -1,10 +1,12 @@
 //! Logic for reading from a channel through a iterator.
 use crate::{
     Error,
-    modes::{Claim, Mode},
+    modes::{Advance, Claim, Mode},
     ring::{Ring, active::Last},
     std::hint::cold_path,
 };
+#[cfg(not(feature = "_safe_maybeuninit"))]
+use core::ptr;
 
 /// A view into a part of the channel.
 ///
@@ -41,12 +43,20 @@ where
     ///
     /// # Errors
     /// Can return [`Error::Poisoned`] if the ring is poisoned and [`Error::TooManyConsumers`] if
-    /// there are already `u16::MAX - 1` consumers.
+    /// there are already `u16::MAX - 1` consumers. In both cases the ring is poisoned.
     #[inline]
     pub(crate) unsafe fn new(ring: *const Ring<N, T, P, C>, claim: Claim) -> Result<Self, Error> {
         // SAFETY: Caller guarantees the ring is valid
-        unsafe {
-            (&*ring).active().register_consumer()?;
+        if let Err(err) = unsafe { (&*ring).active().register_values() } {
+            cold_path();
+            // The claim can't be given back without a `Rewind` mode, so the ring is poisoned.
+            // The claimed entries are given up when the poison is cleared.
+            core::mem::forget(claim);
+            // SAFETY: Caller guarantees the ring is valid
+            unsafe {
+                (&*ring).poison();
+            }
+            return Err(err);
         }
         let offset = claim.start();
         Ok(Self {
@@ -69,6 +79,257 @@ where
     }
 }
 
+impl<const N: usize, T, P, C> RecvValues<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    /// Get the items that are left as two slices.
+    ///
+    /// The first slice contains the items up to the end of the ring, the second one the items
+    /// that wrapped around to the start of the ring. Like [`VecDeque::as_slices`](std::collections::VecDeque::as_slices),
+    /// the second slice is empty if the items didn't wrap around.
+    ///
+    /// The items stay in the iterator, so they're still returned by [`next`](Self::next) or
+    /// dropped when the iterator is dropped.
+    #[cfg(not(feature = "_safe_maybeuninit"))]
+    #[must_use]
+    #[inline]
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let Some((claim, ring)) = &self.claim_and_ring else {
+            cold_path();
+            return (&[], &[]);
+        };
+        let offset = self.offset as usize;
+        let left = (claim.entries() - self.consumed) as usize;
+
+        // SAFETY: RecvValues is registered as a consumer, so ring is a valid reference.
+        //         `UnsafeCell` and `MaybeUninit` are `repr(transparent)`, so the data is laid out
+        //         as `[T]`. The Claim guarantees nobody else writes to these entries, and the
+        //         `left` entries starting at `offset` are initialized. `first <= size - offset` and
+        //         `left - first <= offset` so both slices stay inside the ring.
+        unsafe {
+            let first = left.min((**ring).size() - offset);
+            let data = (**ring).data().as_ptr().cast::<T>();
+            (
+                core::slice::from_raw_parts(data.add(offset), first),
+                core::slice::from_raw_parts(data, left - first),
+            )
+        }
+    }
+
+    /// Give the entries of the items that are already consumed back to the channel.
+    ///
+    /// The items that are left stay claimed. Until the iterator is finished or dropped, the
+    /// entries of the consumed items can't be reused by the senders, so this allows a slow
+    /// consumer of a large claim to make room in the channel while it's busy. This is only
+    /// available for consumer modes that implement [`Advance`].
+    ///
+    /// ```
+    /// let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+    /// sender.try_send_slice(&[1, 2, 3]).unwrap();
+    /// let mut values = receiver.try_recv_bulk(3).unwrap();
+    /// assert_eq!(values.next(), Some(1));
+    /// // The entry of the consumed item is still claimed
+    /// assert_eq!(sender.try_send(4), Ok(Some(4)));
+    /// values.commit_consumed();
+    /// assert_eq!(sender.try_send(4), Ok(None));
+    /// assert_eq!(values.collect::<Vec<_>>(), [2, 3]);
+    /// ```
+    #[inline]
+    pub fn commit_consumed(&mut self)
+    where
+        C: Advance,
+    {
+        let Some((claim, ring)) = &mut self.claim_and_ring else {
+            cold_path();
+            return;
+        };
+        if self.consumed == 0 {
+            return;
+        }
+        // SAFETY: RecvValues is registered as a consumer, so ring is a valid reference.
+        //         The claim is returned once all items are consumed, so it still has items left.
+        unsafe {
+            (**ring).advance_cons(claim, self.consumed);
+        }
+        // The claim now starts at `offset`
+        self.consumed = 0;
+    }
+}
+
+impl<const N: usize, T, P, C> RecvValues<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    /// Iterate over the items that are left from the last to the first.
+    ///
+    /// The whole claim is still given back once every item is consumed or the iterator is
+    /// dropped, and the items that are left are dropped with it.
+    ///
+    /// ```
+    /// let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+    /// sender.try_send_slice(&[1, 2, 3]).unwrap();
+    /// let mut values = receiver.try_recv_bulk(3).unwrap();
+    /// assert_eq!(values.next(), Some(1));
+    /// assert_eq!(values.into_rev().collect::<Vec<_>>(), [3, 2]);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn into_rev(self) -> RevRecvValues<N, T, P, C> {
+        RevRecvValues { inner: self }
+    }
+
+    /// Take the last item out of the ring, without checking if the claim is finished.
+    ///
+    /// The item is counted as consumed, but the offset isn't moved. The items that are left are
+    /// still the `entries - consumed` items starting at `offset`, so dropping them stays the same.
+    ///
+    /// # Safety
+    /// `ring` must be the ring of the claim, and the claim must have `left` items left.
+    #[inline]
+    unsafe fn take_back(&mut self, ring: *const Ring<N, T, P, C>, left: u32) -> T {
+        // SAFETY: RecvValues is registered as a consumer, so ring is a valid reference
+        let offset = self.offset.wrapping_add(left - 1) & unsafe { (*ring).mask() };
+        // SAFETY: RecvValues is registered as a consumer, so ring is a valid reference
+        //         The Claim guarantees we have exclusive access to this index and that
+        //         there is a valid, initialized item at the index.
+        let value =
+            unsafe { (*ring).data()[offset as usize].with_mut(|p| (*p).assume_init_take()) };
+        // SAFETY: RecvValues is registered as a consumer, so ring is a valid reference
+        unsafe {
+            let budget = (*ring).budget();
+            budget.release(budget.measure(&value));
+        }
+
+        self.consumed += 1;
+        value
+    }
+}
+
+impl<const N: usize, T, P, C> RecvValues<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    /// Take the next item out of the ring, without checking if the claim is finished.
+    ///
+    /// # Safety
+    /// `ring` must be the ring of the claim, and the claim must still have items left.
+    #[inline]
+    unsafe fn take_next(&mut self, ring: *const Ring<N, T, P, C>) -> T {
+        // SAFETY: RecvValues is registered as a consumer, so ring is a valid reference
+        //         The Claim guarantees we have exclusive access to this index and that
+        //         there is a valid, initialized item at the index.
+        let value =
+            unsafe { (*ring).data()[self.offset as usize].with_mut(|p| (*p).assume_init_take()) };
+        // SAFETY: RecvValues is registered as a consumer, so ring is a valid reference
+        unsafe {
+            let budget = (*ring).budget();
+            budget.release(budget.measure(&value));
+        }
+
+        self.consumed += 1;
+        // SAFETY: RecvValues is registered as a consumer, so ring is a valid reference
+        self.offset = self.offset.wrapping_add(1) & unsafe { (*ring).mask() };
+        value
+    }
+
+    /// Drop the next `n` items, without checking if the claim is finished.
+    ///
+    /// The items are counted as consumed before they're dropped, so if the drop of an item panics
+    /// the items after it are leaked instead of dropped twice.
+    ///
+    /// # Safety
+    /// `ring` must be the ring of the claim, and the claim must still have at least `n` items left.
+    unsafe fn drop_next(&mut self, ring: *const Ring<N, T, P, C>, n: u32) {
+        // SAFETY: Ring is valid while we haven't unregistered.
+        let budget = unsafe { (*ring).budget() };
+        if budget.is_limited() {
+            for _ in 0..n {
+                // SAFETY: The caller guarantees there are `n` items left.
+                drop(unsafe { self.take_next(ring) });
+            }
+        } else {
+            let offset = self.offset;
+            self.consumed += n;
+            // SAFETY: RecvValues is registered as a consumer, so ring is a valid reference
+            self.offset = self.offset.wrapping_add(n) & unsafe { (*ring).mask() };
+            // Without drop glue the items can be left in place, unless the initialization is tracked
+            #[cfg(not(feature = "_safe_maybeuninit"))]
+            if core::mem::needs_drop::<T>() {
+                let offset = offset as usize;
+                // SAFETY: Ring is valid while we haven't unregistered.
+                //         `UnsafeCell` and `MaybeUninit` are `repr(transparent)`, so the data is
+                //         laid out as `[T]`. The Claim guarantees we have exclusive access to
+                //         these entries, and the caller that the `n` entries starting at `offset`
+                //         are initialized. `first <= size - offset` and `n - first <= offset` so both
+                //         runs stay inside the ring.
+                unsafe {
+                    let first = (n as usize).min((*ring).size() - offset);
+                    let data = (*ring).data().as_ptr().cast::<T>().cast_mut();
+                    ptr::drop_in_place(ptr::slice_from_raw_parts_mut(data.add(offset), first));
+                    ptr::drop_in_place(ptr::slice_from_raw_parts_mut(data, n as usize - first));
+                }
+            }
+            #[cfg(feature = "_safe_maybeuninit")]
+            for i in 0..n {
+                // SAFETY: RecvValues is registered as a consumer, so ring is a valid reference
+                let offset = offset.wrapping_add(i) & unsafe { (*ring).mask() };
+                // SAFETY: Ring is valid while we haven't unregistered.
+                //         The Claim guarantees we have exclusive access to this index and that
+                //         there is a valid, intialized item at the index.
+                unsafe {
+                    (*ring).data()[offset as usize].with_mut(|p| (*p).assume_init_drop());
+                };
+            }
+        }
+    }
+
+    /// Return the fully consumed claim and unregister from the ring.
+    ///
+    /// # Safety
+    /// `ring` must be the ring of the claim, and all items of the claim must be consumed.
+    unsafe fn finish(claim: Claim, ring: *const Ring<N, T, P, C>) {
+        cold_path();
+        // SAFETY: We're still registered so the ring must be valid
+        unsafe {
+            (*ring).return_claim_cons(claim);
+        }
+        // SAFETY: The caller guarantees the ring is valid and that we're still registered
+        unsafe {
+            Self::unregister(ring);
+        }
+    }
+
+    /// Unregister from the ring, cleaning it up if this was the last handle.
+    ///
+    /// # Safety
+    /// `ring` must be the ring of the claim, and `self` must still be registered.
+    unsafe fn unregister(ring: *const Ring<N, T, P, C>) {
+        // SAFETY: We're still registered so the ring must be valid
+        match unsafe { (*ring).active().unregister_consumer() } {
+            // The ring is leaked, unless the poison is cleared by the last handle.
+            Err(_) => cold_path(),
+            Ok(Last::InCategory) => {
+                // SAFETY: Even if another thread starts the ring cleanup, the cleanup will
+                //         wait for the tail being marked.
+                unsafe {
+                    (*ring).mark_cons_finished();
+                }
+            }
+            Ok(Last::InRing) => {
+                // SAFETY: `Last::InRing` guarantees that we're the last
+                unsafe {
+                    Ring::cleanup(ring);
+                }
+            }
+            Ok(Last::NotLast) => {}
+        }
+    }
+}
+
 impl<const N: usize, T, P, C> Iterator for RecvValues<N, T, P, C>
 where
     P: Mode,
@@ -82,42 +343,12 @@ where
     )]
     fn next(&mut self) -> Option<Self::Item> {
         if let Some((claim, ring)) = self.claim_and_ring.take() {
-            // SAFETY: RecvValues is registered as a consumer, so ring is a valid reference
-            //         The Claim guarantees we have exclusive access to this index and that
-            //         there is a valid, initialized item at the index.
-            let value = unsafe {
-                (*ring).data()[self.offset as usize].with_mut(|p| (*p).assume_init_take())
-            };
-
-            self.consumed += 1;
-            self.offset = self.offset.wrapping_add(1) & (N as u32 - 1);
+            // SAFETY: The claim is still in `self`, so it has items left.
+            let value = unsafe { self.take_next(ring) };
             if self.consumed >= claim.entries() {
-                cold_path();
-                // SAFETY: We're still registered so the ring must be valid
+                // SAFETY: All items have been consumed.
                 unsafe {
-                    (*ring).return_claim_cons(claim);
-                }
-                // SAFETY: We're still registered so the ring must be valid
-                match unsafe {
-                    (*ring)
-                        .active()
-                        .unregister_consumer()
-                        .expect("Ring is poisoned!")
-                } {
-                    Last::InCategory => {
-                        // SAFETY: Even if another thread starts the ring cleanup, the cleanup will
-                        //         wait for the tail being marked.
-                        unsafe {
-                            (*ring).mark_cons_finished();
-                        }
-                    }
-                    Last::InRing => {
-                        // SAFETY: `Last::InRing` guarantees that we're the last
-                        unsafe {
-                            Ring::cleanup(ring);
-                        }
-                    }
-                    Last::NotLast => {}
+                    Self::finish(claim, ring);
                 }
             } else {
                 self.claim_and_ring = Some((claim, ring));
@@ -139,56 +370,208 @@ where
         };
         (left, Some(left))
     }
+
+    #[expect(
+        clippy::missing_inline_in_public_items,
+        reason = "This function is too large too inline"
+    )]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let (claim, ring) = self.claim_and_ring.as_ref()?;
+        let (entries, ring) = (claim.entries(), *ring);
+        let skip = n.min((entries - self.consumed) as usize) as u32;
+        // SAFETY: `skip` is at most the amount of items left in the claim.
+        unsafe {
+            self.drop_next(ring, skip);
+        }
+        if self.consumed == entries {
+            if let Some((claim, ring)) = self.claim_and_ring.take() {
+                // SAFETY: All items have been consumed.
+                unsafe {
+                    Self::finish(claim, ring);
+                }
+            }
+            return None;
+        }
+        self.next()
+    }
+
+    #[expect(
+        clippy::missing_inline_in_public_items,
+        reason = "This function is too large too inline"
+    )]
+    fn fold<B, F>(mut self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        let Some((claim, ring)) = &self.claim_and_ring else {
+            cold_path();
+            return init;
+        };
+        let (left, ring) = ((claim.entries() - self.consumed) as usize, *ring);
+        // SAFETY: RecvValues is registered as a consumer, so ring is a valid reference
+        let first = left.min(unsafe { (*ring).size() } - self.offset as usize);
+
+        // The claim stays in `self` until all items are consumed, so if `f` panics the remaining
+        // items are dropped and the claim is returned by `Drop`.
+        let mut acc = init;
+        for _ in 0..first {
+            // SAFETY: The first `first` items are before the end of the ring.
+            acc = f(acc, unsafe { self.take_next(ring) });
+        }
+        debug_assert!(
+            left == first || self.offset == 0,
+            "The items after the wrap must start at the start of the ring"
+        );
+        for _ in first..left {
+            // SAFETY: The remaining items are at the start of the ring.
+            acc = f(acc, unsafe { self.take_next(ring) });
+        }
+
+        if let Some((claim, ring)) = self.claim_and_ring.take() {
+            // SAFETY: All items have been consumed.
+            unsafe {
+                Self::finish(claim, ring);
+            }
+        }
+        acc
+    }
 }
 
-impl<const N: usize, T, P, C> Drop for RecvValues<N, T, P, C>
+/// An iterator over the items of a [`RecvValues`] from the last to the first.
+///
+/// Created with [`RecvValues::into_rev`]. If this is dropped before being fully consumed, the
+/// items that are left will also be dropped.
+pub struct RevRecvValues<const N: usize, T, P, C>
 where
     P: Mode,
     C: Mode,
 {
+    /// The items, which are taken from the back.
+    ///
+    /// Its `consumed` also counts the items taken from the back, so it can't give back the
+    /// consumed entries early.
+    inner: RecvValues<N, T, P, C>,
+}
+
+impl<const N: usize, T, P, C> Iterator for RevRecvValues<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    type Item = T;
+
     #[expect(
         clippy::missing_inline_in_public_items,
         reason = "This function is too large too inline"
     )]
-    fn drop(&mut self) {
-        if let Some((claim, ring)) = self.claim_and_ring.take() {
-            cold_path();
-            while self.consumed != claim.entries() {
-                // SAFETY: Ring is valid while we haven't unregistered.
-                //         The Claim guarantees we have exclusive access to this index and that
-                //         there is a valid, intialized item at the index.
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((claim, ring)) = self.inner.claim_and_ring.take() {
+            let left = claim.entries() - self.inner.consumed;
+            // SAFETY: The claim is still in `self`, so it has `left` items left.
+            let value = unsafe { self.inner.take_back(ring, left) };
+            if self.inner.consumed >= claim.entries() {
+                // SAFETY: All items have been consumed.
                 unsafe {
-                    (*ring).data()[self.offset as usize].with_mut(|p| (*p).assume_init_drop());
-                };
-                self.consumed += 1;
-                self.offset = self.offset.wrapping_add(1) & (N as u32 - 1);
+                    RecvValues::finish(claim, ring);
+                }
+            } else {
+                self.inner.claim_and_ring = Some((claim, ring));
             }
+            Some(value)
+        } else {
+            cold_path();
+            None
+        }
+    }
 
-            // SAFETY: We're still registered so the ring must be valid
-            unsafe {
-                (*ring).return_claim_cons(claim);
-            }
-            // SAFETY: We're still registered so the ring must be valid
-            match unsafe {
-                (*ring)
-                    .active()
-                    .unregister_consumer()
-                    .expect("Ring was poisoned!")
-            } {
-                Last::InCategory => {
-                    // SAFETY: Even if another thread starts the ring cleanup, the cleanup will
-                    //         wait for the tail being marked.
-                    unsafe {
-                        (*ring).mark_cons_finished();
-                    }
-                }
-                Last::InRing => {
-                    // SAFETY: `Last::InRing` guarantees that we're the last
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<const N: usize, T, P, C> ExactSizeIterator for RevRecvValues<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+}
+
+// The claim is given back by `inner` and never put back, so `next` keeps returning `None`.
+impl<const N: usize, T, P, C> core::iter::FusedIterator for RevRecvValues<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+}
+
+#[cfg(feature = "trusted_len")]
+// SAFETY: The ExactSizeIterator implementation is always accurate
+unsafe impl<const N: usize, T, P, C> core::iter::TrustedLen for RevRecvValues<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+}
+
+impl<const N: usize, T, P, C> Drop for RecvValues<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    #[expect(
+        clippy::missing_inline_in_public_items,
+        reason = "This function is too large too inline"
+    )]
+    fn drop(&mut self) {
+        /// Poisons the ring and unregisters if dropping an item panics.
+        ///
+        /// The items after the panicking one are leaked, so the claim isn't returned.
+        struct Unwind<const N: usize, T, P, C>
+        where
+            P: Mode,
+            C: Mode,
+        {
+            /// The claim, taken out again once the items are dropped.
+            claim: Option<Claim>,
+            /// The ring of the claim.
+            ring: *const Ring<N, T, P, C>,
+        }
+
+        impl<const N: usize, T, P, C> Drop for Unwind<N, T, P, C>
+        where
+            P: Mode,
+            C: Mode,
+        {
+            fn drop(&mut self) {
+                if let Some(claim) = self.claim.take() {
+                    cold_path();
+                    // The claimed entries are given up when the poison is cleared.
+                    core::mem::forget(claim);
+                    // SAFETY: We're still registered so the ring must be valid
                     unsafe {
-                        Ring::cleanup(ring);
+                        (*self.ring).poison();
+                        RecvValues::unregister(self.ring);
                     }
                 }
-                Last::NotLast => {}
+            }
+        }
+
+        if let Some((claim, ring)) = self.claim_and_ring.take() {
+            cold_path();
+            let left = claim.entries() - self.consumed;
+            let mut unwind = Unwind {
+                claim: Some(claim),
+                ring,
+            };
+            // SAFETY: These are the items left in the claim.
+            unsafe {
+                self.drop_next(ring, left);
+            }
+            let claim = unwind.claim.take().unwrap_or_else(|| unreachable!());
+            // SAFETY: All items have been consumed.
+            unsafe {
+                Self::finish(claim, ring);
             }
         }
     }
@@ -201,6 +584,14 @@ where
 {
 }
 
+// Once the claim is given back it's never put back in `self`, so `next` keeps returning `None`.
+impl<const N: usize, T, P, C> core::iter::FusedIterator for RecvValues<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+}
+
 #[cfg(feature = "trusted_len")]
 // SAFETY: The ExactSizeIterator implementation is always accurate
 unsafe impl<const N: usize, T, P, C> core::iter::TrustedLen for RecvValues<N, T, P, C>
@@ -0,0 +1,54 @@
+//! A snapshot of the positions in the ring, for debugging.
+use crate::{modes::Mode, ring::Ring, std::sync::atomic::Ordering::SeqCst};
+
+/// A snapshot of the positions and counters of a channel, see [`Receiver::ring_stats`](crate::custom::Receiver::ring_stats).
+///
+/// The fields are loaded one by one, so under concurrent access they can be from slightly
+/// different moments. A head is ahead of its tail while values are being sent or received.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RingStats {
+    /// The position up to which values are claimed by the senders.
+    pub prod_head: u32,
+    /// The position up to which values are sent, without the finished mark.
+    pub prod_tail: u32,
+    /// The position up to which values are claimed by the receivers.
+    pub cons_head: u32,
+    /// The position up to which values are received, without the finished mark.
+    pub cons_tail: u32,
+    /// The amount of active senders.
+    pub producers: u16,
+    /// The amount of active receivers, including the iterators that still have values.
+    pub consumers: u16,
+    /// Is the producer tail marked as finished, which happens after the last sender is dropped.
+    pub prod_finished: bool,
+    /// Is the consumer tail marked as finished, which happens after the last receiver is dropped.
+    pub cons_finished: bool,
+    /// Is the ring poisoned.
+    pub poisoned: bool,
+}
+
+impl RingStats {
+    /// Take a snapshot of `ring`.
+    #[inline]
+    pub(crate) fn new<const N: usize, T, P, C>(ring: &Ring<N, T, P, C>) -> Self
+    where
+        P: Mode,
+        C: Mode,
+    {
+        let active = ring.active.load(SeqCst);
+        let prod_tail = ring.prod_headtail.load_tail(SeqCst);
+        let cons_tail = ring.cons_headtail.load_tail(SeqCst);
+        Self {
+            prod_head: ring.prod_headtail.load_head(SeqCst),
+            cons_head: ring.cons_headtail.load_head(SeqCst),
+            prod_tail: prod_tail & 0x7FFF_FFFF,
+            cons_tail: cons_tail & 0x7FFF_FFFF,
+            producers: active.producers,
+            consumers: active.consumers,
+            prod_finished: prod_tail & 0x8000_0000 != 0,
+            cons_finished: cons_tail & 0x8000_0000 != 0,
+            poisoned: active.poisoned,
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Memory for a ring that is provided by the caller instead of the allocator.
+use crate::{modes::Mode, ring::Ring};
+use core::mem::MaybeUninit;
+
+/// Uninitialized memory for a channel, see [`bounded_in`](crate::custom::bounded_in).
+///
+/// This has the size and alignment of the channel, so it can be placed in a `static` or in memory
+/// that is not managed by the global allocator. It can be created in a const context:
+/// ```
+/// use ringbeam::custom::{RingStorage, modes::Single};
+/// static mut STORAGE: RingStorage<8, u32, Single, Single> = RingStorage::new();
+/// ```
+///
+/// # Generics
+/// - `N`: the size of the channel.
+/// - `T`: the type being sent over the channel.
+/// - `P`: the synchronisation mode of the sender, see [`Mode`].
+/// - `C`: the synchronisation mode of the receiver, see [`Mode`].
+#[repr(transparent)]
+pub struct RingStorage<const N: usize, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    /// The ring, which is initialized when it's used for a channel.
+    ring: MaybeUninit<Ring<N, T, P, C>>,
+}
+
+impl<const N: usize, T, P, C> RingStorage<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    /// Create uninitialized memory for a channel.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            ring: MaybeUninit::uninit(),
+        }
+    }
+
+    /// Get a pointer to the memory of the ring.
+    #[inline]
+    pub(crate) const fn as_mut_ptr(&mut self) -> *mut Ring<N, T, P, C> {
+        self.ring.as_mut_ptr()
+    }
+}
+
+impl<const N: usize, T, P, C> Default for RingStorage<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
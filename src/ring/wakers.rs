@@ -0,0 +1,97 @@
+//! Wakers of tasks waiting on the ring.
+use crate::std::sync::{
+    Mutex,
+    atomic::{AtomicUsize, Ordering::SeqCst, fence},
+};
+use core::task::Waker;
+
+/// A list of tasks that are waiting for one side of the ring.
+///
+/// Every waiting [`AsyncSender`](crate::future::AsyncSender) or [`AsyncReceiver`](crate::future::AsyncReceiver)
+/// has at most one waker in the list, identified by the id it got from [`WakerList::next_id`].
+pub struct WakerList {
+    /// The amount of wakers in the list, so waking is cheap when nobody is waiting.
+    len: AtomicUsize,
+    /// The id for the next task.
+    next_id: AtomicUsize,
+    /// The wakers and the id of their task.
+    wakers: Mutex<Vec<(usize, Waker)>>,
+}
+
+impl WakerList {
+    /// Create an empty list.
+    #[cfg_attr(
+        not(any(feature = "_loom", feature = "_shuttle")),
+        expect(
+            clippy::missing_const_for_fn,
+            reason = "The types of loom and shuttle can't be created in a const context"
+        )
+    )]
+    pub fn new() -> Self {
+        Self {
+            len: AtomicUsize::new(0),
+            next_id: AtomicUsize::new(0),
+            wakers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Get a new task id.
+    pub fn next_id(&self) -> usize {
+        self.next_id.fetch_add(1, SeqCst)
+    }
+
+    /// Register the waker for the task with `id`, replacing any previous waker of that task.
+    ///
+    /// The caller must check the ring again after registering, otherwise it can miss a wake-up
+    /// that happened just before the registration.
+    pub fn register(&self, id: usize, waker: &Waker) {
+        let mut wakers = self
+            .wakers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some((_, registered)) = wakers.iter_mut().find(|(other, _)| *other == id) {
+            registered.clone_from(waker);
+        } else {
+            wakers.push((id, waker.clone()));
+            self.len.store(wakers.len(), SeqCst);
+        }
+        drop(wakers);
+        // Pairs with the fence in `wake_all`, so either the check after registering sees the
+        // change to the ring or `wake_all` sees the registration.
+        fence(SeqCst);
+    }
+
+    /// Remove the waker of the task with `id`.
+    pub fn unregister(&self, id: usize) {
+        if self.len.load(SeqCst) == 0 {
+            return;
+        }
+        let mut wakers = self
+            .wakers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        wakers.retain(|(other, _)| *other != id);
+        self.len.store(wakers.len(), SeqCst);
+    }
+
+    /// Wake all registered tasks, removing them from the list.
+    ///
+    /// This must be called after the change to the ring the tasks are waiting for.
+    pub fn wake_all(&self) {
+        // Pairs with the fence in `register`.
+        fence(SeqCst);
+        if self.len.load(SeqCst) == 0 {
+            return;
+        }
+        let mut wakers = self
+            .wakers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let woken = core::mem::take(&mut *wakers);
+        self.len.store(0, SeqCst);
+        drop(wakers);
+        for (_, waker) in woken {
+            waker.wake();
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! Logic for writing values in place into a channel.
+use crate::{
+    modes::{Claim, Mode, Rewind},
+    ring::Ring,
+    std::hint::cold_path,
+};
+use core::mem::MaybeUninit;
+
+/// A claim on uninitialized entries of the channel, to write values in place.
+///
+/// The values are only sent once they're published with [`WriteGuard::commit`]. If this is
+/// dropped instead, the entries are given back to the channel and nothing is sent. Values that
+/// were written to the entries are not dropped in that case.
+///
+/// Only producers with a [`Rewind`] mode can claim entries, as the entries must be given back
+/// when this is dropped. While this exists, no other value can be sent to the channel.
+pub struct WriteGuard<'a, const N: usize, T, P, C>
+where
+    P: Rewind,
+    C: Mode,
+{
+    /// The ring the entries are from.
+    ring: &'a Ring<N, T, P, C>,
+    /// The claim of the entries.
+    ///
+    /// This is `None` if no entries were claimed or once the claim is given back.
+    claim: Option<Claim>,
+}
+
+impl<'a, const N: usize, T, P, C> WriteGuard<'a, N, T, P, C>
+where
+    P: Rewind,
+    C: Mode,
+{
+    /// Create a new guard from a producer claim.
+    ///
+    /// # Safety
+    /// `claim` must be a producer claim in `ring`, or `None` for a guard without entries.
+    #[inline]
+    pub(crate) const unsafe fn new(ring: &'a Ring<N, T, P, C>, claim: Option<Claim>) -> Self {
+        Self { ring, claim }
+    }
+
+    /// The amount of claimed entries.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.claim
+            .as_ref()
+            .map_or(0, |claim| claim.entries() as usize)
+    }
+
+    /// Are there no claimed entries.
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.claim.is_none()
+    }
+
+    /// Get the claimed entries as two slices.
+    ///
+    /// The first slice contains the entries up to the end of the ring, the second one the entries
+    /// that wrapped around to the start of the ring. The second slice is empty if the entries
+    /// didn't wrap around.
+    #[must_use]
+    #[inline]
+    pub fn as_mut_slices(&mut self) -> (&mut [MaybeUninit<T>], &mut [MaybeUninit<T>]) {
+        let Some(claim) = &self.claim else {
+            cold_path();
+            return (&mut [], &mut []);
+        };
+        let offset = claim.start() as usize;
+        let len = claim.entries() as usize;
+        let first = len.min(self.ring.size() - offset);
+
+        // SAFETY: `UnsafeCell` and our `MaybeUninit` are `repr(transparent)`, so the data is laid
+        //         out as `[core::mem::MaybeUninit<T>]`. The Claim gives us exclusive access to
+        //         these entries. `first <= size - offset` and `len - first <= offset` so both slices
+        //         stay inside the ring and don't overlap.
+        unsafe {
+            let data = self
+                .ring
+                .data()
+                .as_ptr()
+                .cast::<MaybeUninit<T>>()
+                .cast_mut();
+            (
+                core::slice::from_raw_parts_mut(data.add(offset), first),
+                core::slice::from_raw_parts_mut(data, len - first),
+            )
+        }
+    }
+
+    /// Send the first `count` claimed entries.
+    ///
+    /// The other entries are given back to the channel, values written to them are not dropped.
+    ///
+    /// # Errors
+    /// Returns the guard if the channel has a byte budget and the values would bring it over the
+    /// limit. Nothing is sent in that case, so a smaller `count` can be committed instead.
+    ///
+    /// # Safety
+    /// The first `count` entries, in the order of [`as_mut_slices`](Self::as_mut_slices), must be
+    /// initialized.
+    ///
+    /// # Panics
+    /// Will panic if `count` is larger than the amount of claimed entries.
+    #[inline]
+    pub unsafe fn commit(mut self, count: usize) -> Result<(), Self> {
+        assert!(
+            count <= self.len(),
+            "Committed more entries than were claimed"
+        );
+        let Some(claim) = &self.claim else {
+            cold_path();
+            return Ok(());
+        };
+        let budget = self.ring.budget();
+        if budget.is_limited() {
+            let data = self.ring.data().as_ptr().cast::<T>();
+            let bytes = (0..count)
+                .map(|i| {
+                    let index =
+                        (claim.start() as usize).wrapping_add(i) & self.ring.mask() as usize;
+                    // SAFETY: The caller guarantees the first `count` entries are initialized
+                    //         and the Claim gives us exclusive access to them.
+                    budget.measure(unsafe { &*data.add(index) })
+                })
+                .sum();
+            // The bytes must be reserved before the values are visible, so consumers never subtract first
+            if budget.reserve(bytes).is_err() {
+                cold_path();
+                return Err(self);
+            }
+        }
+        let claim = self.claim.take().unwrap_or_else(|| unreachable!());
+        self.ring.rewind_prod(claim, count as u32);
+        Ok(())
+    }
+}
+
+impl<const N: usize, T, P, C> Drop for WriteGuard<'_, N, T, P, C>
+where
+    P: Rewind,
+    C: Mode,
+{
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(claim) = self.claim.take() {
+            self.ring.rewind_prod(claim, 0);
+        }
+    }
+}
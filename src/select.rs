@@ -0,0 +1,151 @@
+//! Receiving from whichever of several channels has a value.
+//!
+//! The receivers of the channels can have different sizes and modes, so they're added to a
+//! [`Selector`] as a [`TryRecv`] trait object.
+use crate::{Backoff, Error, consumer::Receiver, modes::Mode, std::hint::cold_path};
+
+/// A receiver that can be polled for a value of `T`.
+pub trait TryRecv<T> {
+    /// Try to get one item from the channel.
+    ///
+    /// # Errors
+    /// See [`Receiver::try_recv`].
+    fn try_recv(&self) -> Result<T, Error>;
+}
+
+impl<const N: usize, T, P, C> TryRecv<T> for Receiver<N, T, P, C>
+where
+    P: Mode,
+    C: Mode,
+{
+    #[inline]
+    fn try_recv(&self) -> Result<T, Error> {
+        Self::try_recv(self)
+    }
+}
+
+/// Receive from the first of several channels that has a value.
+///
+/// The channels are polled round-robin, starting after the channel that returned the last
+/// value, so a busy channel can't starve the others.
+///
+/// # Example
+/// ```
+/// use ringbeam::select::Selector;
+///
+/// let (sender_a, receiver_a) = ringbeam::spsc::bounded::<4, u32>();
+/// let (sender_b, receiver_b) = ringbeam::mpmc::bounded::<16, u32>();
+/// let mut selector = Selector::new().add(&receiver_a).add(&receiver_b);
+///
+/// sender_b.try_send(2).unwrap();
+/// assert_eq!(selector.try_recv_any(), Some((1, 2)));
+/// assert_eq!(selector.try_recv_any(), None);
+/// ```
+pub struct Selector<'a, T> {
+    /// The receivers, in the order they were added.
+    receivers: Vec<&'a dyn TryRecv<T>>,
+    /// The index of the receiver that is polled first.
+    next: usize,
+}
+
+impl<T> Default for Selector<'_, T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T> Selector<'a, T> {
+    /// Create a selector without any receivers.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            receivers: Vec::new(),
+            next: 0,
+        }
+    }
+
+    /// Add a receiver to the selector.
+    ///
+    /// The index of the receiver is the amount of receivers that were added before it.
+    #[expect(
+        clippy::should_implement_trait,
+        reason = "Adding a receiver is not an addition"
+    )]
+    #[must_use]
+    #[inline]
+    pub fn add(mut self, receiver: &'a dyn TryRecv<T>) -> Self {
+        self.receivers.push(receiver);
+        self
+    }
+
+    /// Try to get one item from any of the channels.
+    ///
+    /// # Returns
+    /// The index of the receiver and the item, or `None` if none of the channels had an item.
+    #[inline]
+    pub fn try_recv_any(&mut self) -> Option<(usize, T)> {
+        self.poll().ok()
+    }
+
+    /// Get one item from any of the channels, waiting while all channels are empty.
+    ///
+    /// # Returns
+    /// The index of the receiver and the item.
+    ///
+    /// # Errors
+    /// Returns [`Error::Closed`] once all channels are closed and empty, and [`Error::Poisoned`]
+    /// if one of the rings is poisoned. It can also return [`Error::TooManyConsumers`] if one of
+    /// the rings already has `u16::MAX - 1` consumers.
+    #[inline]
+    pub fn recv_any(&mut self) -> Result<(usize, T), Error> {
+        self.recv_any_with_backoff(&mut Backoff::new())
+    }
+
+    /// Get one item from any of the channels, waiting with `backoff` while all channels are empty.
+    ///
+    /// # Returns
+    /// The index of the receiver and the item.
+    ///
+    /// # Errors
+    /// See [`recv_any`](Self::recv_any).
+    #[inline]
+    pub fn recv_any_with_backoff(&mut self, backoff: &mut Backoff) -> Result<(usize, T), Error> {
+        loop {
+            match self.poll() {
+                Err(Error::Empty) => backoff.snooze(),
+                result => return result,
+            }
+        }
+    }
+
+    /// Poll every receiver once, starting at `self.next`.
+    ///
+    /// # Errors
+    /// Returns [`Error::Empty`] if at least one channel is still open, and [`Error::Closed`] if
+    /// all channels are closed. Any other error of a receiver is returned immediately.
+    fn poll(&mut self) -> Result<(usize, T), Error> {
+        let len = self.receivers.len();
+        let mut result = Err(Error::Closed);
+        for i in 0..len {
+            let index = (self.next + i) % len;
+            match self.receivers[index].try_recv() {
+                Ok(value) => {
+                    self.next = (index + 1) % len;
+                    return Ok((index, value));
+                }
+                Err(Error::Empty) => result = Err(Error::Empty),
+                Err(Error::Closed) => {}
+                Err(error) => {
+                    cold_path();
+                    return Err(error);
+                }
+            }
+        }
+        if len != 0 {
+            self.next = (self.next + 1) % len;
+        }
+        result
+    }
+}
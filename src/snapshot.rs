@@ -0,0 +1,85 @@
+//! Persisting the pending items of a channel with `serde`.
+//!
+//! A live ring can't be serialized, as other threads can be sending and receiving while it's
+//! read. Instead, the items are drained from the channel with [`Receiver::drain_to_vec`] into a
+//! [`ChannelSnapshot`], which can be serialized. A fresh channel can be refilled from the snapshot
+//! with [`Sender::send_iter_all`].
+//!
+//! A snapshot is a point-in-time copy: items sent after draining are not part of it. To get
+//! everything, stop (or drop) the producers before draining.
+//!
+//! ```
+//! # use ringbeam::snapshot::ChannelSnapshot;
+//! let (sender, receiver) = ringbeam::spsc::bounded::<8, u32>();
+//! sender.try_send_slice(&[1, 2, 3]).unwrap();
+//! drop(sender);
+//!
+//! let snapshot = ChannelSnapshot::from(receiver.drain_to_vec().unwrap());
+//! // Serialize the snapshot and restart
+//!
+//! let (sender, receiver) = ringbeam::spsc::bounded::<8, u32>();
+//! assert_eq!(sender.send_iter_all(snapshot), Ok(3));
+//! assert_eq!(receiver.try_recv(), Ok(1));
+//! ```
+//!
+//! [`Receiver::drain_to_vec`]: crate::custom::Receiver::drain_to_vec
+//! [`Sender::send_iter_all`]: crate::custom::Sender::send_iter_all
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The pending items of a channel, in the order they would have been received.
+///
+/// Serializes as a sequence of the items.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ChannelSnapshot<T>(pub Vec<T>);
+
+impl<T> ChannelSnapshot<T> {
+    /// Get the items of the snapshot.
+    #[must_use]
+    #[inline]
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T> From<Vec<T>> for ChannelSnapshot<T> {
+    #[inline]
+    fn from(items: Vec<T>) -> Self {
+        Self(items)
+    }
+}
+
+impl<T> IntoIterator for ChannelSnapshot<T> {
+    type Item = T;
+    type IntoIter = <Vec<T> as IntoIterator>::IntoIter;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<T> Serialize for ChannelSnapshot<T>
+where
+    T: Serialize,
+{
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for ChannelSnapshot<T>
+where
+    T: Deserialize<'de>,
+{
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::deserialize(deserializer).map(Self)
+    }
+}
@@ -4,9 +4,15 @@
 //! various types need to be replaced with types that check the model. Doing this in every file
 //! becomes messy, so we mimic the standard library here for the types that can be replaced.
 //!
+//! The check for unwinding, `thread::panicking`, is also routed through here so it has a
+//! fallback without the `std` feature.
+//!
 //! It's also used to conditionally use the `cold_path` hint which is currently unstable, so if the
 //! feature `cold_path` is not enabled it's just an empty function.
 //!
+//! The atomics of `loom` can't be created in a `const` context, so [`const_fn_unless_loom`]
+//! defines functions that are only `const` without it.
+//!
 //! Finally, there is an alternative `MaybeUninit` type which does track if the inner type is
 //! initialized. This allows tests to catch more problems and is not intended to be enabled by
 //! downstream crates.
@@ -45,6 +51,11 @@ pub mod cell {
                 Self(core::cell::UnsafeCell::new(data))
             }
 
+            /// Get a const pointer to the wrapped value.
+            pub fn with<R>(&self, f: impl FnOnce(*const T) -> R) -> R {
+                f(self.0.get())
+            }
+
             /// Get a mutable pointer to the wrapped value.
             pub fn with_mut<R>(&self, f: impl FnOnce(*mut T) -> R) -> R {
                 f(self.0.get())
@@ -88,7 +99,8 @@ pub mod mem {
         ///
         /// This version does *not* have the same size as T.
         pub struct MaybeUninit<T> {
-            mutex: Mutex<(std::mem::MaybeUninit<T>, bool)>,
+            /// The value and if it's initialized.
+            mutex: Mutex<(core::mem::MaybeUninit<T>, bool)>,
         }
 
         impl<T> MaybeUninit<T> {
@@ -96,7 +108,7 @@ pub mod mem {
             #[must_use]
             pub const fn uninit() -> Self {
                 Self {
-                    mutex: Mutex::new((std::mem::MaybeUninit::uninit(), false)),
+                    mutex: Mutex::new((core::mem::MaybeUninit::uninit(), false)),
                 }
             }
             /// Extract T from the container.
@@ -114,7 +126,8 @@ pub mod mem {
                     .expect("There is a concurrent access!");
                 assert!(guard.1, "Container is not initialized!");
                 guard.1 = false;
-                let taken = std::mem::replace(&mut guard.0, std::mem::MaybeUninit::uninit());
+                let taken = core::mem::replace(&mut guard.0, core::mem::MaybeUninit::uninit());
+                drop(guard);
                 // SAFETY: the assert checked that it's initialized
                 unsafe { taken.assume_init() }
             }
@@ -123,6 +136,10 @@ pub mod mem {
             ///
             /// # Panics
             /// Can panic if another thread is currently reading it.
+            #[expect(
+                clippy::needless_pass_by_ref_mut,
+                reason = "The function signature matches `core::mem::MaybeUninit`"
+            )]
             pub fn write(&mut self, value: T) {
                 let mut guard = self
                     .mutex
@@ -157,6 +174,7 @@ pub mod mem {
     }
 
     #[cfg(not(feature = "_safe_maybeuninit"))]
+    /// A thin wrapper around [`core::mem::MaybeUninit`] with the same API as `safe_maybe_uninit`.
     mod unsafe_maybe_uninit {
         /// See [`MaybeUninit`](core::mem::MaybeUninit).
         #[repr(transparent)]
@@ -187,30 +205,66 @@ pub mod mem {
             pub const fn write(&mut self, value: T) {
                 self.inner.write(value);
             }
-
-            /// Drop T from the container.
-            ///
-            /// # Safety
-            /// See [`MaybeUninit::assume_init_drop`](core::mem::MaybeUninit::assume_init_drop)
-            pub unsafe fn assume_init_drop(&mut self) {
-                // SAFETY: Guaranteed by caller
-                unsafe {
-                    self.inner.assume_init_drop();
-                }
-            }
         }
     }
 }
 
+/// Native threads.
+pub mod thread {
+    #[cfg(feature = "_loom")]
+    pub use loom::thread::yield_now;
+    #[cfg(feature = "_shuttle")]
+    pub use shuttle::thread::yield_now;
+    #[cfg(feature = "std")]
+    pub use std::thread::panicking;
+    #[cfg(not(any(feature = "_loom", feature = "_shuttle")))]
+    pub use std::thread::yield_now;
+
+    /// Always `false` as unwinding can't be detected without `std`.
+    ///
+    /// Targets without `std` usually abort on panic, so there is never a drop during unwinding.
+    #[cfg(not(feature = "std"))]
+    pub const fn panicking() -> bool {
+        false
+    }
+}
+
 /// Synchronization primitives.
 pub mod sync {
+    #[cfg(feature = "_loom")]
+    pub use loom::sync::Arc;
+    #[cfg(all(feature = "async", feature = "_loom"))]
+    pub use loom::sync::Mutex;
+    #[cfg(feature = "_shuttle")]
+    pub use shuttle::sync::Arc;
+    #[cfg(all(feature = "async", feature = "_shuttle"))]
+    pub use shuttle::sync::Mutex;
+    #[cfg(not(any(feature = "_loom", feature = "_shuttle")))]
+    pub use std::sync::Arc;
+    #[cfg(all(feature = "async", not(any(feature = "_loom", feature = "_shuttle"))))]
+    pub use std::sync::Mutex;
+
     /// Atomic types.
     pub mod atomic {
         #[cfg(not(any(feature = "_loom", feature = "_shuttle")))]
-        pub use core::sync::atomic::{AtomicU32, AtomicU64, Ordering, fence};
+        pub use core::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering, fence};
         #[cfg(feature = "_loom")]
-        pub use loom::sync::atomic::{AtomicU32, AtomicU64, Ordering, fence};
+        pub use loom::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering, fence};
         #[cfg(feature = "_shuttle")]
-        pub use shuttle::sync::atomic::{AtomicU32, AtomicU64, Ordering, fence};
+        pub use shuttle::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering, fence};
     }
 }
+
+/// Define a `const fn`, which is a normal `fn` with `loom` as its atomics can't be created in a
+/// `const` context.
+macro_rules! const_fn_unless_loom {
+    ($(#[$attr:meta])* $vis:vis fn $($rest:tt)*) => {
+        #[cfg(not(feature = "_loom"))]
+        $(#[$attr])*
+        $vis const fn $($rest)*
+        #[cfg(feature = "_loom")]
+        $(#[$attr])*
+        $vis fn $($rest)*
+    };
+}
+pub(crate) use const_fn_unless_loom;
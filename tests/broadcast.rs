@@ -0,0 +1,187 @@
+#![allow(
+    clippy::missing_panics_doc,
+    clippy::missing_inline_in_public_items,
+    missing_docs,
+    reason = "It's a test"
+)]
+
+mod common;
+
+use common::{items, model, thread};
+use ringbeam::Error;
+
+#[test]
+pub fn test_broadcast_every_receiver_gets_every_value() {
+    model(|| {
+        let (sender, receiver) = ringbeam::broadcast::bounded::<8, u8>(2);
+        let receiver2 = receiver.clone();
+        for i in 0..5 {
+            assert_eq!(sender.try_send(i), Ok(None));
+        }
+        for i in 0..5 {
+            assert_eq!(receiver.try_recv(), Ok(i));
+        }
+        assert_eq!(receiver.try_recv(), Err(Error::Empty));
+        assert_eq!(receiver2.len(), 5);
+        for i in 0..5 {
+            assert_eq!(receiver2.try_recv(), Ok(i));
+        }
+        assert!(receiver2.is_empty());
+    });
+}
+
+#[test]
+pub fn test_broadcast_clone_starts_at_same_position() {
+    model(|| {
+        let (sender, receiver) = ringbeam::broadcast::bounded::<8, u8>(2);
+        sender.try_send(1).unwrap();
+        sender.try_send(2).unwrap();
+        assert_eq!(receiver.try_recv(), Ok(1));
+        let receiver2 = receiver.clone();
+        assert_eq!(receiver2.try_recv(), Ok(2));
+        assert_eq!(receiver2.try_recv(), Err(Error::Empty));
+        assert_eq!(receiver.try_recv(), Ok(2));
+    });
+}
+
+#[test]
+pub fn test_broadcast_sender_subscribe_gets_new_values() {
+    model(|| {
+        let (sender, receiver) = ringbeam::broadcast::bounded::<8, u8>(2);
+        sender.try_send(1).unwrap();
+        let receiver2 = sender.subscribe().unwrap();
+        sender.try_send(2).unwrap();
+        assert_eq!(receiver2.try_recv(), Ok(2));
+        assert_eq!(receiver.try_recv(), Ok(1));
+        assert_eq!(receiver.try_recv(), Ok(2));
+    });
+}
+
+#[test]
+pub fn test_broadcast_full_until_slowest_receiver_reads() {
+    model(|| {
+        let (sender, receiver) = ringbeam::broadcast::bounded::<4, u8>(2);
+        let receiver2 = receiver.clone();
+        for i in 0..3 {
+            assert_eq!(sender.try_send(i), Ok(None));
+        }
+        for i in 0..3 {
+            assert_eq!(receiver.try_recv(), Ok(i));
+        }
+        assert_eq!(sender.try_send(3), Ok(Some(3)));
+        assert_eq!(receiver2.try_recv(), Ok(0));
+        assert_eq!(sender.try_send(3), Ok(None));
+        // A dropped receiver is no longer waited for
+        drop(receiver2);
+        assert_eq!(sender.try_send(4), Ok(None));
+        assert_eq!(sender.try_send(5), Ok(None));
+        assert_eq!(sender.try_send(6), Ok(Some(6)));
+    });
+}
+
+#[test]
+pub fn test_broadcast_closed() {
+    model(|| {
+        let (sender, receiver) = ringbeam::broadcast::bounded::<8, u8>(1);
+        sender.try_send(1).unwrap();
+        drop(sender);
+        assert_eq!(receiver.try_recv(), Ok(1));
+        assert_eq!(receiver.try_recv(), Err(Error::Closed));
+
+        let (sender, receiver) = ringbeam::broadcast::bounded::<8, u8>(1);
+        drop(receiver);
+        assert_eq!(sender.try_send(1), Err(Error::Closed));
+    });
+}
+
+#[test]
+pub fn test_broadcast_too_many_receivers() {
+    model(|| {
+        let (sender, receiver) = ringbeam::broadcast::bounded::<8, u8>(2);
+        let receiver2 = receiver.subscribe().unwrap();
+        assert_eq!(receiver.subscribe().unwrap_err(), Error::TooManyConsumers);
+        assert_eq!(sender.subscribe().unwrap_err(), Error::TooManyConsumers);
+        // The cursor of a dropped receiver is free again
+        drop(receiver2);
+        assert!(sender.subscribe().is_ok());
+    });
+}
+
+#[test]
+pub fn test_broadcast_try_send_recv_interleaved() {
+    model(|| {
+        let (sender, receiver) = ringbeam::broadcast::bounded::<4, u8>(2);
+        let receiver2 = receiver.clone();
+        let handle = thread::spawn(move || {
+            for i in 0..items(100) {
+                loop {
+                    match receiver.try_recv() {
+                        Ok(val) => {
+                            assert_eq!(val, i);
+                            break;
+                        }
+                        Err(Error::Empty) => thread::yield_now(),
+                        Err(err) => panic!("{err:?}"),
+                    }
+                }
+            }
+        });
+        let handle2 = thread::spawn(move || {
+            let mut next = 0;
+            for i in 0..items(100) {
+                loop {
+                    match sender.try_send(i) {
+                        Ok(None) => break,
+                        Ok(_) => {
+                            // The other receiver reads on this thread, when it's the slowest
+                            if let Ok(val) = receiver2.try_recv() {
+                                assert_eq!(val, next);
+                                next += 1;
+                            }
+                            thread::yield_now();
+                        }
+                        Err(err) => panic!("{err:?}"),
+                    }
+                }
+            }
+            drop(sender);
+            while let Ok(val) = receiver2.try_recv() {
+                assert_eq!(val, next);
+                next += 1;
+            }
+            assert_eq!(next, items(100));
+        });
+        handle.join().unwrap();
+        handle2.join().unwrap();
+    });
+}
+
+#[test]
+pub fn test_broadcast_subscribe_while_sending() {
+    model(|| {
+        let (sender, receiver) = ringbeam::broadcast::bounded::<2, u8>(2);
+        // Move the receiver to the second cursor, so its clone takes the first one, which the
+        // sender checks before the cursor of the receiver
+        let receiver = {
+            let clone = receiver.clone();
+            drop(receiver);
+            clone
+        };
+        sender.try_send(0).unwrap();
+        let handle = thread::spawn(move || {
+            while sender.try_send(1) == Ok(Some(1)) {
+                thread::yield_now();
+            }
+        });
+        let receiver2 = receiver.clone();
+        assert_eq!(receiver.try_recv(), Ok(0));
+        // The sender must wait for the clone, even if it checked the first cursor before the clone
+        // took it and the second one after the receiver moved
+        assert!(receiver2.len() <= receiver2.capacity());
+        assert_eq!(receiver2.try_recv(), Ok(0));
+        handle.join().unwrap();
+        assert_eq!(receiver.try_recv(), Ok(1));
+        assert_eq!(receiver2.try_recv(), Ok(1));
+        assert_eq!(receiver2.try_recv(), Err(Error::Closed));
+    });
+}
@@ -0,0 +1,223 @@
+#![allow(
+    clippy::missing_panics_doc,
+    clippy::missing_inline_in_public_items,
+    missing_docs,
+    reason = "It's a test"
+)]
+// These tests use native threads or panics, which the model checkers don't support
+#![cfg(not(any(feature = "_loom", feature = "_shuttle")))]
+
+use core::time::Duration;
+use ringbeam::{
+    Error, Measure,
+    custom::modes::{Multi, Single},
+};
+use std::thread;
+
+#[test]
+pub fn test_budget_try_send_over_budget() {
+    let (sender, receiver) =
+        ringbeam::custom::bounded_with_budget::<64, Vec<u8>, Single, Single>(10);
+    assert_eq!(sender.try_send(vec![0; 6]), Ok(None));
+    assert_eq!(sender.try_send(vec![0; 6]), Ok(Some(vec![0; 6])));
+    assert_eq!(
+        sender.try_send_or_len(vec![0; 5]),
+        Ok(Err((vec![0; 5], 62)))
+    );
+    assert_eq!(sender.try_send(vec![0; 4]), Ok(None));
+    assert_eq!(sender.len_bytes(), 10);
+    assert_eq!(receiver.len_bytes(), 10);
+
+    assert_eq!(receiver.try_recv().unwrap().len(), 6);
+    assert_eq!(receiver.len_bytes(), 4);
+    assert_eq!(sender.try_send(vec![0; 6]), Ok(None));
+}
+
+#[test]
+pub fn test_budget_released_when_full() {
+    let (sender, receiver) =
+        ringbeam::custom::bounded_with_budget::<2, String, Single, Single>(100);
+    assert_eq!(sender.try_send("a".repeat(10)), Ok(None));
+    assert_eq!(sender.try_send("b".repeat(10)), Ok(Some("b".repeat(10))));
+    assert_eq!(sender.len_bytes(), 10);
+    drop(receiver);
+}
+
+#[test]
+pub fn test_budget_bulk_and_dropped_values() {
+    let (sender, receiver) =
+        ringbeam::custom::bounded_with_budget::<64, Vec<u8>, Single, Single>(10);
+    // The values of an iterator can't be measured before they're taken, so nothing is taken
+    let mut values = vec![vec![0; 4], vec![0; 4], vec![0; 4]].into_iter();
+    assert_eq!(sender.try_send_bulk(&mut values), Err(Error::OverBudget));
+    assert_eq!(sender.try_send_burst(&mut values), Err(Error::OverBudget));
+    assert_eq!(values.len(), 3);
+    assert_eq!(sender.len_bytes(), 0);
+
+    let (sent, rest, error) = sender.try_send_prefix(values.collect()).unwrap_err();
+    assert_eq!((sent, rest, error), (2, vec![vec![0; 4]], Error::Full));
+    assert_eq!(sender.len_bytes(), 8);
+    let mut rest = sender.push_iter([vec![0; 2], vec![0; 1]]);
+    assert_eq!(rest.next(), Some(vec![0; 1]));
+    assert_eq!(sender.len_bytes(), 10);
+
+    let mut recv = receiver.try_recv_bulk(3).unwrap();
+    assert_eq!(recv.next().map(|v| v.len()), Some(4));
+    assert_eq!(receiver.len_bytes(), 6);
+    drop(recv);
+    assert_eq!(receiver.len_bytes(), 0);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Bytes(usize);
+
+impl Measure for Bytes {
+    fn measure(&self) -> usize {
+        self.0
+    }
+}
+
+#[test]
+pub fn test_budget_slices() {
+    let (sender, receiver) = ringbeam::custom::bounded_with_budget::<4, Bytes, Single, Single>(10);
+    let values = [Bytes(4), Bytes(4), Bytes(4)];
+    assert_eq!(sender.try_send_slice(&values), Err(Error::OverBudget));
+    assert_eq!(
+        sender.try_send_vectored(&[&values[..1], &values[1..]]),
+        Err(Error::OverBudget)
+    );
+    assert_eq!(sender.len_bytes(), 0);
+    assert_eq!(sender.try_send_slice_burst(&values), Ok(2));
+    assert_eq!(sender.len_bytes(), 8);
+    assert_eq!(sender.try_send_slice_burst(&values), Err(Error::OverBudget));
+
+    // Only one value fits in the ring, the bytes of the other one are released again
+    assert_eq!(sender.try_send_slice(&[Bytes(1)]), Ok(1));
+    assert_eq!(receiver.try_recv(), Ok(Bytes(4)));
+    assert_eq!(sender.try_send_slice_burst(&[Bytes(1), Bytes(1)]), Ok(1));
+    assert_eq!(sender.len_bytes(), 6);
+}
+
+#[test]
+pub fn test_budget_permit_and_write_guard() {
+    let (mut sender, receiver) =
+        ringbeam::custom::bounded_with_budget::<8, Vec<u8>, Single, Single>(10);
+    assert_eq!(
+        sender.reserve().unwrap().send(vec![0; 11]),
+        Err(vec![0; 11])
+    );
+    // The slot was given back
+    assert_eq!(sender.len(), 0);
+    assert_eq!(sender.reserve().unwrap().send(vec![0; 6]), Ok(()));
+
+    let mut guard = sender.claim_write(2).unwrap();
+    let (first, _) = guard.as_mut_slices();
+    first[0].write(vec![0; 3]);
+    first[1].write(vec![0; 3]);
+    // SAFETY: Both entries are initialized.
+    let Err(mut guard) = (unsafe { guard.commit(2) }) else {
+        panic!("The values don't fit in the budget");
+    };
+    // SAFETY: The second entry is initialized and isn't committed.
+    unsafe {
+        guard.as_mut_slices().0[1].assume_init_drop();
+    }
+    // SAFETY: The first entry is initialized.
+    assert!(unsafe { guard.commit(1) }.is_ok());
+    assert_eq!(sender.len_bytes(), 9);
+    assert_eq!(receiver.try_recv_burst(8).unwrap().count(), 2);
+    assert_eq!(receiver.len_bytes(), 0);
+}
+
+#[test]
+pub fn test_budget_send_larger_than_limit() {
+    let (sender, receiver) = ringbeam::custom::bounded_with_budget::<8, Vec<u8>, Single, Single>(4);
+    // Waiting can't help for a value that doesn't even fit in an empty budget
+    assert_eq!(sender.send(vec![0; 10]), Err(Error::OverBudget));
+    #[cfg(feature = "std")]
+    assert_eq!(
+        sender.send_with_progress_timeout(vec![0; 10], Duration::from_secs(10)),
+        Err((Error::OverBudget, vec![0; 10]))
+    );
+    assert_eq!(
+        sender.send_iter_all([vec![0; 4], vec![0; 10]]),
+        Err((1, Error::OverBudget))
+    );
+    assert_eq!(sender.len_bytes(), 4);
+
+    // A value that fits in an empty budget waits for room
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        let value = receiver.recv();
+        (receiver, value)
+    });
+    assert_eq!(sender.send(vec![0; 3]), Ok(()));
+    let (receiver, value) = handle.join().unwrap();
+    assert_eq!(value.map(|v| v.len()), Ok(4));
+    assert_eq!(receiver.len_bytes(), 3);
+}
+
+#[test]
+pub fn test_budget_unlimited() {
+    let (sender, receiver) = ringbeam::mpmc::bounded::<64, Vec<u8>>();
+    assert_eq!(sender.try_send(vec![0; 1000]), Ok(None));
+    assert_eq!(receiver.len_bytes(), 0);
+}
+
+#[test]
+pub fn test_budget_mpmc_stress() {
+    const LIMIT: usize = 1024;
+    const PER_PRODUCER: usize = 2000;
+    let (sender, receiver) =
+        ringbeam::custom::bounded_with_budget::<64, Vec<u8>, Multi, Multi>(LIMIT);
+
+    let producers = (0..4)
+        .map(|p| {
+            let sender = sender.clone();
+            thread::spawn(move || {
+                for i in 0..PER_PRODUCER {
+                    let mut value = vec![0; (i * 7 + p) % 200];
+                    loop {
+                        match sender.try_send(value) {
+                            Ok(None) => break,
+                            Ok(Some(v)) => value = v,
+                            Err(err) => panic!("{err:?}"),
+                        }
+                        assert!(sender.len_bytes() <= LIMIT);
+                        thread::yield_now();
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(sender);
+
+    let consumers = (0..4)
+        .map(|_| {
+            let receiver = receiver.clone();
+            thread::spawn(move || {
+                let mut count = 0;
+                loop {
+                    match receiver.try_recv() {
+                        Ok(_) => count += 1,
+                        Err(Error::Empty) => thread::yield_now(),
+                        Err(Error::Closed) => break,
+                        Err(err) => panic!("{err:?}"),
+                    }
+                    assert!(receiver.len_bytes() <= LIMIT);
+                }
+                count
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for producer in producers {
+        producer.join().unwrap();
+    }
+    let mut total = 0;
+    for consumer in consumers {
+        total += consumer.join().unwrap();
+    }
+    assert_eq!(total, 4 * PER_PRODUCER);
+    assert_eq!(receiver.len_bytes(), 0);
+}
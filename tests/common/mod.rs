@@ -0,0 +1,68 @@
+//! The harness shared by the integration tests.
+//!
+//! A test wraps its body in [`model`] and spawns its threads with [`thread::spawn`]. Normally
+//! the body runs once. With the `_loom` or `_shuttle` feature, the checker runs the body many
+//! times to explore the interleavings of the threads:
+//! ```text
+//! cargo test --release --features _loom
+//! cargo test --release --features _shuttle
+//! ```
+//! Loom explores at most [`LOOM_PREEMPTIONS`] preemptions per run, unless `LOOM_MAX_PREEMPTIONS`
+//! is set. Shuttle runs [`SHUTTLE_ITERATIONS`] random schedules.
+#![allow(dead_code, reason = "Not every test uses everything")]
+
+/// Native threads, or the threads of the model checker.
+pub mod thread {
+    #[cfg(feature = "_loom")]
+    pub use loom::thread::{spawn, yield_now};
+    #[cfg(feature = "_shuttle")]
+    pub use shuttle::thread::{spawn, yield_now};
+    #[cfg(not(any(feature = "_loom", feature = "_shuttle")))]
+    pub use std::thread::{spawn, yield_now};
+}
+
+/// The default preemption bound for loom.
+///
+/// Most bugs need only a few preemptions, and with more than one the tests with three threads
+/// don't finish in reasonable time.
+pub const LOOM_PREEMPTIONS: usize = 1;
+
+/// The amount of random schedules that shuttle tries.
+pub const SHUTTLE_ITERATIONS: usize = 1000;
+
+/// Run the test `f`, exploring the interleavings of its threads if a model checker is enabled.
+pub fn model<F>(f: F)
+where
+    F: Fn() + Send + Sync + 'static,
+{
+    #[cfg(feature = "_loom")]
+    {
+        let mut builder = loom::model::Builder::new();
+        if builder.preemption_bound.is_none() {
+            builder.preemption_bound = Some(LOOM_PREEMPTIONS);
+        }
+        builder.check(f);
+    }
+    #[cfg(feature = "_shuttle")]
+    shuttle::check_random(f, SHUTTLE_ITERATIONS);
+    #[cfg(not(any(feature = "_loom", feature = "_shuttle")))]
+    f();
+}
+
+/// The amount of values sent in a loop when a model checker is enabled.
+///
+/// Every value multiplies the amount of interleavings, so the tests only send a few.
+pub const MODEL_ITEMS: u8 = 2;
+
+/// Get the amount of values to send in a loop, `n` normally or [`MODEL_ITEMS`] with a model
+/// checker.
+pub fn items<T>(n: T) -> T
+where
+    T: From<u8> + Ord,
+{
+    if cfg!(any(feature = "_loom", feature = "_shuttle")) {
+        n.min(T::from(MODEL_ITEMS))
+    } else {
+        n
+    }
+}
@@ -0,0 +1,130 @@
+#![allow(
+    clippy::missing_panics_doc,
+    clippy::missing_inline_in_public_items,
+    missing_docs,
+    reason = "It's a test"
+)]
+#![cfg(feature = "async")]
+
+use core::{
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+use ringbeam::{
+    Error,
+    custom::modes::Single,
+    future::{AsyncReceiver, AsyncSender},
+};
+use std::{
+    sync::Arc,
+    task::Wake,
+    thread::{self, Thread},
+};
+
+/// Wakes the thread that is blocked in [`block_on`].
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Run a future to completion on the current thread.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[test]
+pub fn test_future_send_recv() {
+    let (sender, receiver) = ringbeam::spsc::bounded::<4, u32>();
+    let mut sender = AsyncSender::new(sender);
+    let mut receiver = AsyncReceiver::new(receiver);
+    let handle = thread::spawn(move || {
+        block_on(async {
+            for i in 0..100 {
+                sender.send(i).await.unwrap();
+            }
+        });
+    });
+    block_on(async {
+        for i in 0..100 {
+            assert_eq!(receiver.recv().await, Ok(i));
+        }
+        assert_eq!(receiver.recv().await, Err(Error::Closed));
+    });
+    handle.join().unwrap();
+}
+
+#[test]
+pub fn test_future_send_larger_than_budget() {
+    let (sender, _receiver) =
+        ringbeam::custom::bounded_with_budget::<8, Vec<u8>, Single, Single>(4);
+    let mut sender = AsyncSender::new(sender);
+    assert_eq!(block_on(sender.send(vec![0; 10])), Err(Error::OverBudget));
+    assert_eq!(block_on(sender.send(vec![0; 4])), Ok(()));
+}
+
+#[cfg(feature = "futures")]
+#[test]
+pub fn test_future_stream() {
+    use core::pin::Pin;
+    use futures_core::Stream as _;
+
+    let (sender, receiver) = ringbeam::spsc::bounded::<4, u32>();
+    let mut receiver = AsyncReceiver::new(receiver);
+    let handle = thread::spawn(move || {
+        for i in 0..100 {
+            sender.send(i).unwrap();
+        }
+    });
+    let next = |receiver: &mut AsyncReceiver<_, _, _, _>| {
+        block_on(core::future::poll_fn(|cx| {
+            Pin::new(&mut *receiver).poll_next(cx)
+        }))
+    };
+    for i in 0..100 {
+        assert_eq!(next(&mut receiver), Some(Ok(i)));
+    }
+    // The stream ends once the channel is closed and empty
+    assert_eq!(next(&mut receiver), None);
+    handle.join().unwrap();
+}
+
+#[test]
+pub fn test_future_receiver_dropped() {
+    let (sender, receiver) = ringbeam::spsc::bounded::<2, u32>();
+    let mut sender = AsyncSender::new(sender);
+    let handle = thread::spawn(move || {
+        block_on(async {
+            sender.send(1).await.unwrap();
+            sender.send(2).await
+        })
+    });
+    while receiver.is_empty() {
+        thread::yield_now();
+    }
+    drop(receiver);
+    assert_eq!(handle.join().unwrap(), Err(Error::Closed));
+}
+
+#[test]
+pub fn test_future_poll_recv_pending() {
+    let (sender, receiver) = ringbeam::spsc::bounded::<2, u32>();
+    let mut receiver = AsyncReceiver::new(receiver);
+    let waker = Waker::noop();
+    let mut cx = Context::from_waker(waker);
+    assert_eq!(receiver.poll_recv(&mut cx), Poll::Pending);
+    sender.try_send(1).unwrap();
+    assert_eq!(receiver.poll_recv(&mut cx), Poll::Ready(Ok(1)));
+    let receiver = receiver.into_inner();
+    assert_eq!(receiver.try_recv(), Err(Error::Empty));
+}
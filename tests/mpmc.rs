@@ -1,24 +1,14 @@
-#![allow(clippy::missing_panics_doc, missing_docs, reason = "It's a test")]
+#![allow(
+    clippy::missing_panics_doc,
+    clippy::missing_inline_in_public_items,
+    missing_docs,
+    reason = "It's a test"
+)]
 
-use ringbeam::Error;
+mod common;
 
-#[cfg(feature = "_loom")]
-mod thread {
-    pub use loom::thread::{spawn, yield_now};
-}
-#[cfg(not(feature = "_loom"))]
-mod thread {
-    pub use std::thread::{spawn, yield_now};
-}
-#[cfg(feature = "_loom")]
-use loom::model::model;
-#[cfg(not(feature = "_loom"))]
-fn model<F>(f: F)
-where
-    F: Fn() + Send + Sync + 'static,
-{
-    f();
-}
+use common::{items, model, thread};
+use ringbeam::Error;
 
 #[test]
 pub fn test_mpmc_try_send_recv_sequential() {
@@ -35,7 +25,7 @@ pub fn test_mpmc_try_send_recv_interleaved_1() {
     model(|| {
         let (sender, receiver) = ringbeam::mpmc::bounded::<64, u8>();
         let handle = thread::spawn(move || {
-            for i in 0..100 {
+            for i in 0..items(100) {
                 loop {
                     match receiver.try_recv() {
                         Ok(val) => {
@@ -49,7 +39,7 @@ pub fn test_mpmc_try_send_recv_interleaved_1() {
             }
         });
         let handle2 = thread::spawn(move || {
-            for i in 0..100 {
+            for i in 0..items(100) {
                 loop {
                     match sender.try_send(i) {
                         Ok(None) => break,
@@ -85,14 +75,14 @@ pub fn test_mpmc_try_send_recv_interleaved_2() {
                     Err(Error::Empty) => thread::yield_now(),
                     Err(err) => panic!("{err:?}"),
                 }
-                if i == 100 && j == 101 {
+                if i == items(100) && j == items(100) + 1 {
                     break;
                 }
             }
         });
         let sender2 = sender.clone();
         let handle2 = thread::spawn(move || {
-            for i in 0..100u8 {
+            for i in 0..items(100u8) {
                 if i.is_multiple_of(2) {
                     loop {
                         match sender2.try_send(i) {
@@ -105,7 +95,7 @@ pub fn test_mpmc_try_send_recv_interleaved_2() {
             }
         });
         let handle3 = thread::spawn(move || {
-            for i in 0..100u8 {
+            for i in 0..items(100u8) {
                 if !i.is_multiple_of(2) {
                     loop {
                         match sender.try_send(i) {
@@ -122,3 +112,629 @@ pub fn test_mpmc_try_send_recv_interleaved_2() {
         handle3.join().unwrap();
     });
 }
+
+#[test]
+pub fn test_mpmc_closed_after_drain() {
+    model(|| {
+        let (sender, receiver) = ringbeam::mpmc::bounded::<8, u8>();
+        let handle = thread::spawn(move || {
+            for i in 0..items(100) {
+                loop {
+                    match sender.try_send(i) {
+                        Ok(None) => break,
+                        Ok(_) => thread::yield_now(),
+                        Err(err) => panic!("{err:?}"),
+                    }
+                }
+            }
+        });
+        let mut count = 0;
+        loop {
+            match receiver.try_recv() {
+                Ok(val) => {
+                    assert_eq!(val, count);
+                    count += 1;
+                }
+                Err(Error::Empty) => thread::yield_now(),
+                Err(Error::Closed) => break,
+                Err(err) => panic!("{err:?}"),
+            }
+        }
+        assert_eq!(count, items(100));
+        handle.join().unwrap();
+        // Closed is sticky
+        assert_eq!(receiver.try_recv(), Err(Error::Closed));
+    });
+}
+
+#[test]
+// Far too many interleavings for the model checkers
+#[cfg(not(any(feature = "_loom", feature = "_shuttle")))]
+pub fn test_mpmc_spin_limit_oversubscribed() {
+    use ringbeam::custom::modes::{Multi, SpinLimit};
+
+    const PER_PRODUCER: u64 = 200;
+    // Many more threads than cores, so the threads that wait for an earlier claim often wait for
+    // a thread that isn't running
+    let threads = 8 * std::thread::available_parallelism().map_or(1, usize::from);
+    let (sender, receiver) = ringbeam::custom::bounded_with_config::<8, u64, Multi, Multi>(
+        SpinLimit::new(0),
+        SpinLimit::new(0),
+    );
+    let producers = (0..threads)
+        .map(|_| {
+            let sender = sender.clone();
+            thread::spawn(move || {
+                for i in 1..=PER_PRODUCER {
+                    sender.send(i).unwrap();
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(sender);
+    #[expect(
+        clippy::needless_collect,
+        reason = "The consumers must run at the same time"
+    )]
+    let consumers = (0..threads)
+        .map(|_| {
+            let receiver = receiver.clone();
+            thread::spawn(move || {
+                let (mut count, mut sum) = (0_u64, 0_u64);
+                for value in &receiver {
+                    count += 1;
+                    sum += value;
+                }
+                (count, sum)
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(receiver);
+    for producer in producers {
+        producer.join().unwrap();
+    }
+    let (count, sum) = consumers
+        .into_iter()
+        .map(|consumer| consumer.join().unwrap())
+        .fold((0, 0), |(count, sum), (c, s)| (count + c, sum + s));
+    assert_eq!(count, threads as u64 * PER_PRODUCER);
+    assert_eq!(sum, threads as u64 * PER_PRODUCER * (PER_PRODUCER + 1) / 2);
+}
+
+#[test]
+// Far too many interleavings for the model checkers
+#[cfg(not(any(feature = "_loom", feature = "_shuttle")))]
+pub fn test_mpmc_contention() {
+    const PER_PRODUCER: u64 = 500;
+    let (sender, receiver) = ringbeam::mpmc::bounded::<64, u64>();
+    let producers = (0..4)
+        .map(|_| {
+            let sender = sender.clone();
+            thread::spawn(move || {
+                for i in 1..=PER_PRODUCER {
+                    sender.send(i).unwrap();
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(sender);
+    #[expect(
+        clippy::needless_collect,
+        reason = "The consumers must run at the same time"
+    )]
+    let consumers = (0..4)
+        .map(|_| {
+            let receiver = receiver.clone();
+            thread::spawn(move || {
+                let (mut count, mut sum) = (0_u64, 0_u64);
+                loop {
+                    match receiver.try_recv_burst(8) {
+                        Ok(values) => {
+                            for value in values {
+                                count += 1;
+                                sum += value;
+                            }
+                        }
+                        Err(Error::Empty) => thread::yield_now(),
+                        Err(Error::Closed) => break (count, sum),
+                        Err(err) => panic!("{err:?}"),
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(receiver);
+    for producer in producers {
+        producer.join().unwrap();
+    }
+    let (count, sum) = consumers
+        .into_iter()
+        .map(|consumer| consumer.join().unwrap())
+        .fold((0, 0), |(count, sum), (c, s)| (count + c, sum + s));
+    assert_eq!(count, 4 * PER_PRODUCER);
+    assert_eq!(sum, 4 * PER_PRODUCER * (PER_PRODUCER + 1) / 2);
+}
+
+#[test]
+pub fn test_mpmc_weak() {
+    model(|| {
+        let (sender, receiver) = ringbeam::mpmc::bounded::<8, u8>();
+        let sender_weak = sender.downgrade();
+        let receiver_weak = receiver.downgrade();
+        let sender2 = sender_weak.upgrade().unwrap();
+        drop(sender);
+        assert!(!receiver.is_closed());
+        assert_eq!(sender2.try_send(1), Ok(None));
+        drop(sender2);
+        // Weak senders don't keep the channel open
+        assert!(receiver.is_closed());
+        assert!(sender_weak.upgrade().is_none());
+        let receiver_weak2 = receiver_weak.clone();
+        let receiver2 = receiver_weak2.upgrade().unwrap();
+        drop(receiver);
+        assert_eq!(receiver2.try_recv(), Ok(1));
+        assert_eq!(receiver2.try_recv(), Err(Error::Closed));
+        // The last weak handle cleans up the ring
+        drop(receiver2);
+        assert!(receiver_weak.upgrade().is_none());
+        drop(sender_weak);
+        drop(receiver_weak);
+        drop(receiver_weak2);
+    });
+}
+
+#[test]
+pub fn test_mpmc_close() {
+    model(|| {
+        let (sender, receiver) = ringbeam::mpmc::bounded::<8, u8>();
+        let sender2 = sender.clone();
+        let receiver2 = receiver.clone();
+        sender.try_send(1).unwrap();
+        sender.close();
+        // Every sender is closed, but the receivers can drain the channel
+        assert!(sender2.is_closed());
+        assert!(receiver.is_closed());
+        assert_eq!(sender2.try_send(2), Err(Error::Closed));
+        assert_eq!(receiver2.try_recv(), Ok(1));
+        assert_eq!(receiver.try_recv(), Err(Error::Closed));
+        assert_eq!(receiver.try_recv_bulk(2).err(), Some(Error::Closed));
+        // Closing again and dropping the closed handles doesn't unregister twice
+        sender2.close();
+        drop(sender);
+        drop(sender2);
+        assert_eq!(receiver2.try_recv(), Err(Error::Closed));
+
+        let (sender, receiver) = ringbeam::mpmc::bounded::<8, u8>();
+        let receiver2 = receiver.clone();
+        sender.try_send(1).unwrap();
+        let handle = thread::spawn(move || {
+            while sender.try_send(2) != Err(Error::Closed) {
+                thread::yield_now();
+            }
+            assert!(sender.is_closed());
+        });
+        receiver.close();
+        assert_eq!(receiver2.try_recv(), Err(Error::Closed));
+        handle.join().unwrap();
+        drop(receiver);
+        drop(receiver2);
+    });
+}
+
+#[test]
+pub fn test_mpmc_claim_ends_at_wrap() {
+    model(|| {
+        let (sender, receiver) = ringbeam::mpmc::bounded::<8, u8>();
+        assert_eq!(sender.try_send_slice(&[0, 1, 2, 3, 4]), Ok(5));
+        assert_eq!(receiver.try_recv_bulk(5).unwrap().count(), 5);
+        // The claims end exactly at the end of the ring, so the tails wrap to 0
+        assert_eq!(sender.try_send_bulk(&mut (5..8)), Ok(3));
+        assert_eq!(
+            receiver.try_recv_bulk(3).unwrap().collect::<Vec<_>>(),
+            [5, 6, 7]
+        );
+        assert_eq!(receiver.try_recv(), Err(Error::Empty));
+        assert_eq!(sender.try_send(8), Ok(None));
+        assert_eq!(receiver.try_recv(), Ok(8));
+        assert_eq!(sender.len(), 0);
+    });
+}
+
+#[test]
+pub fn test_mpmc_recv_bulk_not_enough_items_and_closed() {
+    model(|| {
+        let (sender, receiver) = ringbeam::mpmc::bounded::<8, u8>();
+        let sender2 = sender.clone();
+        assert_eq!(sender.try_send_slice(&[1, 2, 3]), Ok(3));
+        // Closing with a sender that is still alive doesn't mark the tail as finished
+        sender.close();
+        assert_eq!(
+            receiver.try_recv_bulk(5).err(),
+            Some(Error::NotEnoughItemsAndClosed)
+        );
+        assert_eq!(
+            receiver.try_recv_burst(5).unwrap().collect::<Vec<_>>(),
+            [1, 2, 3]
+        );
+        assert_eq!(receiver.try_recv_bulk(5).err(), Some(Error::Closed));
+        drop(sender2);
+    });
+}
+
+#[test]
+pub fn test_mpmc_recv_bulk_closed_while_receiving() {
+    model(|| {
+        let (sender, receiver) = ringbeam::mpmc::bounded::<8, u8>();
+        let handle = thread::spawn(move || {
+            for i in 0..items(100) {
+                loop {
+                    match sender.try_send(i) {
+                        Ok(None) => break,
+                        Ok(_) => thread::yield_now(),
+                        Err(err) => panic!("{err:?}"),
+                    }
+                }
+            }
+        });
+        let mut values = Vec::new();
+        loop {
+            match receiver.try_recv_bulk(3) {
+                Ok(bulk) => values.extend(bulk),
+                Err(Error::Empty | Error::NotEnoughItems) => thread::yield_now(),
+                Err(Error::NotEnoughItemsAndClosed) => {
+                    // The rest doesn't make a full bulk, so take it in a burst
+                    values.extend(receiver.try_recv_burst(3).unwrap());
+                    assert_eq!(receiver.try_recv_bulk(3).err(), Some(Error::Closed));
+                    break;
+                }
+                Err(Error::Closed) => break,
+                Err(err) => panic!("{err:?}"),
+            }
+        }
+        handle.join().unwrap();
+        assert_eq!(values, (0..items(100)).collect::<Vec<_>>());
+    });
+}
+
+#[test]
+pub fn test_mpmc_close_and_drain() {
+    model(|| {
+        let (sender, receiver) = ringbeam::mpmc::bounded::<8, u8>();
+        let receiver2 = receiver.clone();
+        assert_eq!(sender.try_send_slice(&[1, 2, 3]), Ok(3));
+        assert_eq!(receiver.close_and_drain(), [1, 2, 3]);
+        assert!(sender.is_closed());
+        assert_eq!(sender.try_send(4), Err(Error::Closed));
+        assert_eq!(receiver2.try_recv(), Err(Error::Closed));
+        assert_eq!(receiver2.close_and_drain(), Vec::<u8>::new());
+    });
+}
+
+#[test]
+pub fn test_mpmc_fill_to_capacity() {
+    model(|| {
+        let (sender, receiver) = ringbeam::mpmc::bounded::<8, u8>();
+        assert_eq!(sender.capacity(), 7);
+        for i in 0..7 {
+            assert_eq!(sender.try_send(i), Ok(None));
+        }
+        assert!(sender.is_full());
+        assert_eq!(receiver.len(), 7);
+        assert_eq!(sender.try_send(7), Ok(Some(7)));
+        assert_eq!(receiver.try_recv(), Ok(0));
+        assert_eq!(sender.try_send(7), Ok(None));
+        assert_eq!(
+            receiver.try_recv_bulk(7).unwrap().collect::<Vec<_>>(),
+            (1..8).collect::<Vec<_>>()
+        );
+    });
+}
+
+#[test]
+pub fn test_mpmc_wait_for_consumers() {
+    model(|| {
+        let (sender, receiver) = ringbeam::mpmc::bounded::<4, u8>();
+        let handle = thread::spawn(move || {
+            let receiver2 = receiver.clone();
+            let mut values = Vec::new();
+            loop {
+                match receiver.try_recv() {
+                    Ok(value) => values.push(value),
+                    Err(Error::Empty) => thread::yield_now(),
+                    Err(Error::Closed) => break,
+                    Err(err) => panic!("{err:?}"),
+                }
+            }
+            drop(receiver2);
+            values
+        });
+        assert_eq!(sender.wait_for_consumers(2), Ok(()));
+        sender.try_send_slice(&[1, 2]).unwrap();
+        drop(sender);
+        assert_eq!(handle.join().unwrap(), [1, 2]);
+    });
+}
+
+#[test]
+pub fn test_mpmc_wait_for_consumers_closed() {
+    model(|| {
+        let (sender, receiver) = ringbeam::mpmc::bounded::<4, u8>();
+        assert_eq!(sender.wait_for_consumers(1), Ok(()));
+        drop(receiver);
+        assert_eq!(sender.wait_for_consumers(1), Err(Error::Closed));
+        let (sender, receiver) = ringbeam::mpmc::bounded::<4, u8>();
+        receiver.close();
+        assert_eq!(sender.wait_for_consumers(2), Err(Error::Closed));
+    });
+}
+
+#[test]
+pub fn test_mpmc_from_raw() {
+    model(|| {
+        let (sender, receiver) = ringbeam::mpmc::bounded::<4, u8>();
+        let ptr = receiver.as_raw();
+        assert_eq!(ptr, sender.as_raw());
+        // SAFETY: The pointer is from a channel with the same generics, which is still alive.
+        let sender2 = unsafe { ringbeam::mpmc::Sender::<4, u8>::from_raw(ptr) }.unwrap();
+        // SAFETY: The pointer is from a channel with the same generics, which is still alive.
+        let receiver2 = unsafe { ringbeam::mpmc::Receiver::<4, u8>::from_raw(ptr) }.unwrap();
+        sender2.try_send(1).unwrap();
+        assert_eq!(receiver.try_recv(), Ok(1));
+        drop(sender);
+        sender2.try_send(2).unwrap();
+        drop(sender2);
+        assert_eq!(receiver2.try_recv(), Ok(2));
+        assert_eq!(receiver2.try_recv(), Err(Error::Closed));
+        // SAFETY: The pointer is from a channel with the same generics, which is still alive.
+        let sender3 = unsafe { ringbeam::mpmc::Sender::<4, u8>::from_raw(ptr) };
+        assert!(matches!(sender3, Err(Error::Closed)));
+    });
+}
+
+#[test]
+pub fn test_mpmc_same_channel() {
+    model(|| {
+        let (sender, receiver) = ringbeam::mpmc::bounded::<4, u8>();
+        let (other_sender, other_receiver) = ringbeam::mpmc::bounded::<4, u8>();
+        let senders = [sender.clone(), other_sender, sender];
+        assert!(senders[0].same_channel(&senders[2]));
+        assert!(!senders[0].same_channel(&senders[1]));
+        assert!(receiver.same_channel(&receiver.clone()));
+        assert!(!receiver.same_channel(&other_receiver));
+        // SAFETY: The pointer is from a channel with the same generics, which is still alive.
+        let from_raw = unsafe { ringbeam::mpmc::Receiver::<4, u8>::from_raw(receiver.as_raw()) };
+        assert!(receiver.same_channel(&from_raw.unwrap()));
+    });
+}
+
+#[test]
+pub fn test_mpmc_head_wraps_while_claiming() {
+    model(|| {
+        // The second producer moves the head a full round of 4 entries, so a producer that's
+        // descheduled while claiming finds the head at the same position again.
+        let (sender, receiver) = ringbeam::mpmc::bounded::<4, u8>();
+        let sender2 = sender.clone();
+        let receiver2 = receiver.clone();
+        let handle = thread::spawn(move || sender.try_send(10).unwrap().is_none());
+        let handle2 = thread::spawn(move || {
+            let mut taken = Vec::new();
+            for i in 0..4 {
+                if i == 3 {
+                    taken.push(receiver2.try_recv().unwrap());
+                }
+                while sender2.try_send(i).unwrap().is_some() {
+                    taken.push(receiver2.try_recv().unwrap());
+                }
+            }
+            taken
+        });
+        let sent = handle.join().unwrap();
+        let mut values = handle2.join().unwrap();
+        while let Ok(val) = receiver.try_recv() {
+            values.push(val);
+        }
+        // Every value is received once, and the values of one producer are in order
+        assert_eq!(values.contains(&10), sent, "{values:?}");
+        values.retain(|val| *val != 10);
+        assert_eq!(values, [0, 1, 2, 3]);
+    });
+}
+
+#[test]
+pub fn test_mpmc_try_recv_contiguous() {
+    model(|| {
+        let (sender, receiver) = ringbeam::mpmc::bounded::<4, u8>();
+        sender.try_send_slice(&[0; 3]).unwrap();
+        assert_eq!(receiver.try_recv_burst(3).unwrap().count(), 3);
+        // The items are at the last entry and the first two entries of the ring
+        sender.try_send_slice(&[3, 4, 5]).unwrap();
+
+        let receiver2 = receiver.clone();
+        let handle = thread::spawn(move || {
+            receiver2
+                .try_recv_contiguous(4)
+                .unwrap()
+                .collect::<Vec<_>>()
+        });
+        let values = receiver.try_recv_contiguous(4).unwrap().collect::<Vec<_>>();
+        let values2 = handle.join().unwrap();
+        // Whoever claims first stops at the end of the ring
+        assert!(
+            (values == [3] && values2 == [4, 5]) || (values == [4, 5] && values2 == [3]),
+            "{values:?} {values2:?}"
+        );
+    });
+}
+
+#[test]
+pub fn test_mpmc_on_close() {
+    use core::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+    use ringbeam::custom::{bounded_with_on_close, modes::Multi};
+
+    static CLOSED: AtomicUsize = AtomicUsize::new(0);
+
+    model(|| {
+        let before = CLOSED.load(Relaxed);
+        let (sender, receiver) = bounded_with_on_close::<8, u8, Multi, Multi>(|| {
+            CLOSED.fetch_add(1, Relaxed);
+        });
+        let weak = receiver.downgrade();
+        // Race the last sender against the last receiver
+        let handle = thread::spawn(move || drop(sender));
+        drop(receiver);
+        handle.join().unwrap();
+        // The weak handle keeps the channel alive
+        assert_eq!(CLOSED.load(Relaxed), before);
+        drop(weak);
+        assert_eq!(CLOSED.load(Relaxed), before + 1);
+    });
+}
+
+#[test]
+pub fn test_mpmc_commit_consumed() {
+    model(|| {
+        let (sender, receiver) = ringbeam::mpmc::bounded::<8, u8>();
+        let receiver2 = receiver.clone();
+        assert_eq!(sender.try_send_slice(&[0, 1, 2, 3, 4, 5, 6]), Ok(7));
+        let mut first = receiver.try_recv_bulk(4).unwrap();
+        let mut second = receiver2.try_recv_bulk(3).unwrap();
+        assert_eq!(first.next(), Some(0));
+        assert_eq!(first.next(), Some(1));
+        first.commit_consumed();
+        assert_eq!(sender.try_send_slice(&[7, 8]), Ok(2));
+        assert_eq!(sender.try_send(9), Ok(Some(9)));
+
+        // The second claim is after the rest of the first claim
+        assert_eq!(second.next(), Some(4));
+        assert_eq!(first.collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(sender.try_send_slice(&[9, 10]), Ok(2));
+        assert_eq!(sender.try_send(11), Ok(Some(11)));
+        assert_eq!(second.collect::<Vec<_>>(), [5, 6]);
+        assert_eq!(sender.try_send_slice(&[11, 12, 13]), Ok(3));
+        assert_eq!(
+            receiver.try_recv_burst(8).unwrap().collect::<Vec<_>>(),
+            [7, 8, 9, 10, 11, 12, 13]
+        );
+    });
+}
+
+#[test]
+pub fn test_mpmc_commit_consumed_threaded() {
+    model(|| {
+        let (sender, receiver) = ringbeam::mpmc::bounded::<4, usize>();
+        let total = items(20);
+        let handle = thread::spawn(move || {
+            for i in 0..total {
+                sender.send(i).unwrap();
+            }
+        });
+        let mut expected = 0;
+        while expected < total {
+            match receiver.try_recv_burst(3) {
+                Ok(mut values) => {
+                    while let Some(value) = values.next() {
+                        assert_eq!(value, expected);
+                        expected += 1;
+                        values.commit_consumed();
+                    }
+                }
+                Err(Error::Empty) => thread::yield_now(),
+                Err(err) => panic!("{err:?}"),
+            }
+        }
+        handle.join().unwrap();
+    });
+}
+
+#[test]
+pub fn test_mpmc_downgrade_to_single() {
+    model(|| {
+        let (sender, receiver) = ringbeam::mpmc::bounded::<4, usize>();
+        let sender2 = sender.clone();
+        let receiver2 = receiver.clone();
+        let total = items(10);
+        let handle = thread::spawn(move || {
+            for i in 0..total {
+                sender2.send(i).unwrap();
+            }
+        });
+        let handle2 = thread::spawn(move || {
+            for i in 0..total {
+                assert_eq!(receiver2.recv(), Ok(i));
+            }
+        });
+        let sender = sender.try_downgrade_to_single().unwrap_err();
+        handle.join().unwrap();
+        handle2.join().unwrap();
+
+        let sender = sender.try_downgrade_to_single().unwrap();
+        let receiver = receiver.try_downgrade_to_single().unwrap();
+        let handle = thread::spawn(move || {
+            for i in 0..total {
+                sender.send(i).unwrap();
+            }
+        });
+        for i in 0..total {
+            assert_eq!(receiver.recv(), Ok(i));
+        }
+        handle.join().unwrap();
+        assert_eq!(receiver.recv(), Err(Error::Closed));
+    });
+}
+
+#[test]
+pub fn test_mpmc_downgrade_to_single_from_raw() {
+    model(|| {
+        let (sender, receiver) = ringbeam::mpmc::bounded::<4, u8>();
+        let ptr = sender.as_raw();
+        let sender = sender.try_downgrade_to_single().unwrap();
+        let receiver = receiver.try_downgrade_to_single().unwrap();
+        // SAFETY: The pointer is from a channel with the same generics, which is still alive.
+        let sender2 = unsafe { ringbeam::mpmc::Sender::<4, u8>::from_raw(ptr) };
+        assert!(matches!(sender2, Err(Error::TooManyProducers)));
+        // SAFETY: The pointer is from a channel with the same generics, which is still alive.
+        let receiver2 = unsafe { ringbeam::mpmc::Receiver::<4, u8>::from_raw(ptr) };
+        assert!(matches!(receiver2, Err(Error::TooManyConsumers)));
+
+        // Receiving a batch still registers the values with the ring
+        assert_eq!(sender.try_send_slice(&[1, 2, 3]), Ok(3));
+        let values = receiver.try_recv_bulk(3).unwrap();
+        drop((sender, receiver));
+        assert_eq!(values.collect::<Vec<_>>(), [1, 2, 3]);
+    });
+}
+
+#[test]
+pub fn test_mpmc_bounded_dyn() {
+    model(|| {
+        let (sender, receiver) = ringbeam::mpmc::bounded_dyn::<u8>(3);
+        assert_eq!(sender.capacity(), 3);
+        let handles = (0..2)
+            .map(|_| {
+                let sender = sender.clone();
+                thread::spawn(move || {
+                    for i in 0..items(100) {
+                        while sender.try_send(i) != Ok(None) {
+                            thread::yield_now();
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        drop(sender);
+        let mut sum = 0_usize;
+        loop {
+            match receiver.try_recv() {
+                Ok(val) => sum += usize::from(val),
+                Err(Error::Empty) => thread::yield_now(),
+                Err(Error::Closed) => break,
+                Err(err) => panic!("{err:?}"),
+            }
+        }
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        let n = usize::from(items(100_u8));
+        assert_eq!(sum, n * (n - 1));
+    });
+}
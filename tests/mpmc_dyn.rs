@@ -0,0 +1,81 @@
+#![allow(
+    clippy::missing_panics_doc,
+    clippy::missing_inline_in_public_items,
+    missing_docs,
+    reason = "It's a test"
+)]
+
+mod common;
+
+use common::{items, model, thread};
+use ringbeam::Error;
+
+use core::num::NonZeroU32;
+use ringbeam::custom::modes::{DynMode, DynModeSettings, Fairness, MaxHeadTailDistance, SpinLimit};
+
+/// Every mode that can be chosen at runtime.
+const SETTINGS: [DynModeSettings; 4] = [
+    DynModeSettings::Multi(SpinLimit::new(0)),
+    DynModeSettings::Multi(SpinLimit::new(u32::MAX)),
+    DynModeSettings::HeadTailSync(Fairness::Fifo),
+    DynModeSettings::RelaxedTailSync(MaxHeadTailDistance::new(NonZeroU32::MIN)),
+];
+
+#[test]
+pub fn test_mpmc_dyn_try_send_recv_sequential() {
+    for producer in SETTINGS {
+        for consumer in SETTINGS {
+            model(move || {
+                let (sender, receiver) =
+                    ringbeam::custom::bounded_with_config::<4, u8, DynMode, DynMode>(
+                        producer, consumer,
+                    );
+                assert_eq!(sender.try_send_slice(&[1, 2, 3]), Ok(3));
+                assert_eq!(sender.try_send(4), Ok(Some(4)));
+                assert_eq!(receiver.try_recv(), Ok(1));
+                assert_eq!(
+                    receiver.try_recv_bulk(2).unwrap().collect::<Vec<_>>(),
+                    [2, 3]
+                );
+                assert_eq!(receiver.try_recv(), Err(Error::Empty));
+                drop(sender);
+                assert_eq!(receiver.try_recv(), Err(Error::Closed));
+            });
+        }
+    }
+}
+
+#[test]
+pub fn test_mpmc_dyn_try_send_recv_interleaved() {
+    model(|| {
+        let (sender, receiver) = ringbeam::custom::bounded_with_config::<4, u8, DynMode, DynMode>(
+            DynModeSettings::RelaxedTailSync(MaxHeadTailDistance::default()),
+            DynModeSettings::HeadTailSync(Fairness::Unfair),
+        );
+        let handle = thread::spawn(move || {
+            for i in 0..items(8) {
+                loop {
+                    match sender.try_send(i) {
+                        Ok(None) => break,
+                        Ok(_) => thread::yield_now(),
+                        Err(err) => panic!("{err:?}"),
+                    }
+                }
+            }
+        });
+        let mut expected = 0;
+        loop {
+            match receiver.try_recv() {
+                Ok(value) => {
+                    assert_eq!(value, expected);
+                    expected += 1;
+                }
+                Err(Error::Empty) => thread::yield_now(),
+                Err(Error::Closed) => break,
+                Err(err) => panic!("{err:?}"),
+            }
+        }
+        assert_eq!(expected, items(8));
+        handle.join().unwrap();
+    });
+}
@@ -1,27 +1,17 @@
-#![allow(clippy::missing_panics_doc, missing_docs, reason = "It's a test")]
+#![allow(
+    clippy::missing_panics_doc,
+    clippy::missing_inline_in_public_items,
+    missing_docs,
+    reason = "It's a test"
+)]
 
+mod common;
+
+use common::{items, model, thread};
 use ringbeam::Error;
 
-#[cfg(feature = "_loom")]
-mod thread {
-    pub use loom::thread::{spawn, yield_now};
-}
-#[cfg(not(feature = "_loom"))]
-mod thread {
-    pub use std::thread::{spawn, yield_now};
-}
-#[cfg(feature = "_loom")]
-use loom::model::model;
 use ringbeam::custom::modes::HeadTailSync;
 
-#[cfg(not(feature = "_loom"))]
-fn model<F>(f: F)
-where
-    F: Fn() + Send + Sync + 'static,
-{
-    f();
-}
-
 #[test]
 pub fn test_mpmc_hts_try_send_recv_sequential() {
     model(|| {
@@ -37,7 +27,7 @@ pub fn test_mpmc_hts_try_send_recv_interleaved_1() {
     model(|| {
         let (sender, receiver) = ringbeam::custom::bounded::<64, u8, HeadTailSync, HeadTailSync>();
         let handle = thread::spawn(move || {
-            for i in 0..100 {
+            for i in 0..items(100) {
                 loop {
                     match receiver.try_recv() {
                         Ok(val) => {
@@ -51,7 +41,7 @@ pub fn test_mpmc_hts_try_send_recv_interleaved_1() {
             }
         });
         let handle2 = thread::spawn(move || {
-            for i in 0..100 {
+            for i in 0..items(100) {
                 loop {
                     match sender.try_send(i) {
                         Ok(None) => break,
@@ -87,14 +77,14 @@ pub fn test_mpmc_hts_try_send_recv_interleaved_2() {
                     Err(Error::Empty) => thread::yield_now(),
                     Err(err) => panic!("{err:?}"),
                 }
-                if i == 100 && j == 101 {
+                if i == items(100) && j == items(100) + 1 {
                     break;
                 }
             }
         });
         let sender2 = sender.clone();
         let handle2 = thread::spawn(move || {
-            for i in 0..100u8 {
+            for i in 0..items(100u8) {
                 if i.is_multiple_of(2) {
                     loop {
                         match sender2.try_send(i) {
@@ -107,7 +97,7 @@ pub fn test_mpmc_hts_try_send_recv_interleaved_2() {
             }
         });
         let handle3 = thread::spawn(move || {
-            for i in 0..100u8 {
+            for i in 0..items(100u8) {
                 if !i.is_multiple_of(2) {
                     loop {
                         match sender.try_send(i) {
@@ -124,3 +114,205 @@ pub fn test_mpmc_hts_try_send_recv_interleaved_2() {
         handle3.join().unwrap();
     });
 }
+
+#[test]
+pub fn test_mpmc_hts_peek() {
+    model(|| {
+        let (sender, mut receiver) =
+            ringbeam::custom::bounded::<64, u8, HeadTailSync, HeadTailSync>();
+        let receiver2 = receiver.clone();
+        sender.try_send(1).unwrap();
+        sender.try_send(2).unwrap();
+        {
+            let peek = receiver.peek().unwrap();
+            assert_eq!(*peek, 1);
+        }
+        assert_eq!(receiver2.try_recv(), Ok(1));
+        assert_eq!(receiver.peek().unwrap().take(), 2);
+        assert_eq!(receiver2.try_recv(), Err(Error::Empty));
+    });
+}
+
+#[test]
+#[cfg(not(feature = "_safe_maybeuninit"))]
+pub fn test_mpmc_hts_peek_bulk() {
+    model(|| {
+        let (sender, mut receiver) =
+            ringbeam::custom::bounded::<8, u8, HeadTailSync, HeadTailSync>();
+        let receiver2 = receiver.clone();
+        // Move the heads to 6, so the peeked region wraps around the end of the ring
+        assert_eq!(sender.try_send_slice(&[0; 6]), Ok(6));
+        assert_eq!(receiver2.try_recv_bulk(6).unwrap().count(), 6);
+        assert_eq!(sender.try_send_slice(&[0, 1, 2, 3, 4]), Ok(5));
+
+        let guard = receiver.peek_bulk(8).unwrap();
+        assert_eq!(guard.as_slices(), (&[0, 1][..], &[2, 3, 4][..]));
+        guard.consume(2);
+
+        // The rest is left for the other receivers
+        assert_eq!(receiver2.try_recv(), Ok(2));
+        let guard = receiver.peek_bulk(8).unwrap();
+        assert_eq!(guard.as_slices(), (&[3, 4][..], &[][..]));
+        drop(guard);
+        assert_eq!(
+            receiver2.try_recv_bulk(2).unwrap().collect::<Vec<_>>(),
+            [3, 4]
+        );
+        assert_eq!(receiver.peek_bulk(1).err(), Some(Error::Empty));
+    });
+}
+
+#[test]
+pub fn test_mpmc_hts_commit_consumed() {
+    model(|| {
+        let (sender, receiver) = ringbeam::custom::bounded::<4, u8, HeadTailSync, HeadTailSync>();
+        let receiver2 = receiver.clone();
+        assert_eq!(sender.try_send_slice(&[1, 2, 3]), Ok(3));
+        let mut values = receiver.try_recv_bulk(3).unwrap();
+        assert_eq!(values.next(), Some(1));
+        values.commit_consumed();
+        // Committing twice without consuming does nothing
+        values.commit_consumed();
+        assert_eq!(sender.try_send(4), Ok(None));
+        assert_eq!(sender.try_send(5), Ok(Some(5)));
+        assert_eq!(values.collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(receiver2.try_recv(), Ok(4));
+    });
+}
+
+#[test]
+pub fn test_mpmc_hts_try_recv_if() {
+    model(|| {
+        let (sender, mut receiver) =
+            ringbeam::custom::bounded::<64, u8, HeadTailSync, HeadTailSync>();
+        let receiver2 = receiver.clone();
+        sender.try_send(1).unwrap();
+        sender.try_send(2).unwrap();
+        assert_eq!(receiver.try_recv_if(|v| *v == 2), Ok(None));
+        assert_eq!(receiver2.try_recv(), Ok(1));
+        assert_eq!(receiver.try_recv_if(|v| *v == 2), Ok(Some(2)));
+        assert_eq!(receiver2.try_recv(), Err(Error::Empty));
+    });
+}
+
+#[test]
+pub fn test_mpmc_hts_closed_after_drain() {
+    model(|| {
+        let (sender, receiver) = ringbeam::custom::bounded::<8, u8, HeadTailSync, HeadTailSync>();
+        let handle = thread::spawn(move || {
+            for i in 0..items(100) {
+                loop {
+                    match sender.try_send(i) {
+                        Ok(None) => break,
+                        Ok(_) => thread::yield_now(),
+                        Err(err) => panic!("{err:?}"),
+                    }
+                }
+            }
+        });
+        let mut count = 0;
+        loop {
+            match receiver.try_recv() {
+                Ok(val) => {
+                    assert_eq!(val, count);
+                    count += 1;
+                }
+                Err(Error::Empty) => thread::yield_now(),
+                Err(Error::Closed) => break,
+                Err(err) => panic!("{err:?}"),
+            }
+        }
+        assert_eq!(count, items(100));
+        handle.join().unwrap();
+        // Closed is sticky
+        assert_eq!(receiver.try_recv(), Err(Error::Closed));
+    });
+}
+
+#[test]
+// Uses native threads that need to run at the same time
+#[cfg(not(any(feature = "_loom", feature = "_shuttle")))]
+pub fn test_mpmc_hts_fifo_fairness() {
+    use ringbeam::custom::modes::Fairness;
+
+    const PRODUCERS: usize = 4;
+    const PER_PRODUCER: usize = 20;
+    let (sender, receiver) =
+        ringbeam::custom::bounded_with_config::<512, usize, HeadTailSync, HeadTailSync>(
+            Fairness::Fifo,
+            Fairness::default(),
+        );
+    let barrier = std::sync::Arc::new(std::sync::Barrier::new(PRODUCERS));
+    let handles: Vec<_> = (0..PRODUCERS)
+        .map(|producer| {
+            let mut sender = sender.clone();
+            let barrier = barrier.clone();
+            std::thread::spawn(move || {
+                barrier.wait();
+                for _ in 0..PER_PRODUCER {
+                    let permit = sender.reserve().unwrap();
+                    // Hold on to the claim, so the other producers line up behind it
+                    std::thread::yield_now();
+                    permit.send(producer).unwrap();
+                }
+            })
+        })
+        .collect();
+    drop(sender);
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    let mut sent = [0; PRODUCERS];
+    while let Ok(producer) = receiver.try_recv() {
+        sent[producer] += 1;
+        // Once a producer is done, the others must have had their turns as well
+        if sent[producer] == PER_PRODUCER {
+            for count in sent {
+                assert!(
+                    count >= PER_PRODUCER / 2,
+                    "A producer was starved: {sent:?}"
+                );
+            }
+        }
+    }
+    assert_eq!(sent, [PER_PRODUCER; PRODUCERS]);
+}
+
+#[test]
+pub fn test_mpmc_hts_fill_to_capacity() {
+    model(|| {
+        let (sender, receiver) = ringbeam::custom::bounded::<8, u8, HeadTailSync, HeadTailSync>();
+        assert_eq!(sender.capacity(), 7);
+        for i in 0..7 {
+            assert_eq!(sender.try_send(i), Ok(None));
+        }
+        assert!(sender.is_full());
+        assert_eq!(receiver.len(), 7);
+        assert_eq!(sender.try_send(7), Ok(Some(7)));
+        assert_eq!(receiver.try_recv(), Ok(0));
+        assert_eq!(sender.try_send(7), Ok(None));
+        assert_eq!(
+            receiver.try_recv_bulk(7).unwrap().collect::<Vec<_>>(),
+            (1..8).collect::<Vec<_>>()
+        );
+    });
+}
+
+#[test]
+pub fn test_mpmc_hts_try_send_from() {
+    model(|| {
+        let (sender, receiver) = ringbeam::custom::bounded::<8, u8, HeadTailSync, HeadTailSync>();
+        let sender2 = sender.clone();
+        let handle = thread::spawn(move || {
+            let mut values = (0..4).filter(|v| v % 2 == 0);
+            assert_eq!(sender2.try_send_from(&mut values, 7), Ok(2));
+        });
+        let mut values = (10..14).filter(|v| v % 2 == 0);
+        assert_eq!(sender.try_send_from(&mut values, 7), Ok(2));
+        handle.join().unwrap();
+        // The room that wasn't used by one sender is used by the other
+        let mut values = receiver.try_recv_burst(8).unwrap().collect::<Vec<_>>();
+        values.sort_unstable();
+        assert_eq!(values, [0, 2, 10, 12]);
+    });
+}
@@ -1,26 +1,17 @@
-#![allow(clippy::missing_panics_doc, missing_docs, reason = "It's a test")]
+#![allow(
+    clippy::missing_panics_doc,
+    clippy::missing_inline_in_public_items,
+    missing_docs,
+    reason = "It's a test"
+)]
 
-use ringbeam::Error;
+mod common;
 
-#[cfg(feature = "_loom")]
-mod thread {
-    pub use loom::thread::{spawn, yield_now};
-}
-#[cfg(not(feature = "_loom"))]
-mod thread {
-    pub use std::thread::{spawn, yield_now};
-}
-#[cfg(feature = "_loom")]
-use loom::model::model;
-use ringbeam::custom::modes::RelaxedTailSync;
+use common::{items, model, thread};
+use ringbeam::Error;
 
-#[cfg(not(feature = "_loom"))]
-fn model<F>(f: F)
-where
-    F: Fn() + Send + Sync + 'static,
-{
-    f();
-}
+use core::num::NonZeroU32;
+use ringbeam::custom::modes::{MaxHeadTailDistance, RelaxedTailSync};
 
 #[test]
 pub fn test_mpmc_rts_try_send_recv_sequential() {
@@ -39,7 +30,7 @@ pub fn test_mpmc_rts_try_send_recv_interleaved_1() {
         let (sender, receiver) =
             ringbeam::custom::bounded::<64, u8, RelaxedTailSync, RelaxedTailSync>();
         let handle = thread::spawn(move || {
-            for i in 0..100 {
+            for i in 0..items(100) {
                 loop {
                     match receiver.try_recv() {
                         Ok(val) => {
@@ -53,7 +44,7 @@ pub fn test_mpmc_rts_try_send_recv_interleaved_1() {
             }
         });
         let handle2 = thread::spawn(move || {
-            for i in 0..100 {
+            for i in 0..items(100) {
                 loop {
                     match sender.try_send(i) {
                         Ok(None) => break,
@@ -90,14 +81,14 @@ pub fn test_mpmc_rts_try_send_recv_interleaved_2() {
                     Err(Error::Empty) => thread::yield_now(),
                     Err(err) => panic!("{err:?}"),
                 }
-                if i == 100 && j == 101 {
+                if i == items(100) && j == items(100) + 1 {
                     break;
                 }
             }
         });
         let sender2 = sender.clone();
         let handle2 = thread::spawn(move || {
-            for i in 0..100u8 {
+            for i in 0..items(100u8) {
                 if i.is_multiple_of(2) {
                     loop {
                         match sender2.try_send(i) {
@@ -110,7 +101,7 @@ pub fn test_mpmc_rts_try_send_recv_interleaved_2() {
             }
         });
         let handle3 = thread::spawn(move || {
-            for i in 0..100u8 {
+            for i in 0..items(100u8) {
                 if !i.is_multiple_of(2) {
                     loop {
                         match sender.try_send(i) {
@@ -127,3 +118,91 @@ pub fn test_mpmc_rts_try_send_recv_interleaved_2() {
         handle3.join().unwrap();
     });
 }
+
+#[test]
+pub fn test_mpmc_rts_max_head_tail_distance() {
+    model(|| {
+        let half = items(50);
+        let distance = MaxHeadTailDistance::new(NonZeroU32::new(1).unwrap());
+        let (sender, receiver) =
+            ringbeam::custom::bounded_with_config::<8, u8, RelaxedTailSync, RelaxedTailSync>(
+                distance, distance,
+            );
+        let handle = thread::spawn(move || {
+            let mut total = 0u32;
+            while let Ok(value) = receiver.recv() {
+                total += u32::from(value);
+            }
+            total
+        });
+        let sender2 = sender.clone();
+        let handle2 = thread::spawn(move || {
+            for i in 0..half {
+                sender2.send(i).unwrap();
+            }
+        });
+        for i in half..2 * half {
+            sender.send(i).unwrap();
+        }
+        drop(sender);
+        handle2.join().unwrap();
+        let total = handle.join().unwrap();
+        assert_eq!(total, (0..2 * half).map(u32::from).sum::<u32>());
+    });
+}
+
+#[test]
+pub fn test_mpmc_rts_closed_after_drain() {
+    model(|| {
+        let (sender, receiver) =
+            ringbeam::custom::bounded::<8, u8, RelaxedTailSync, RelaxedTailSync>();
+        let handle = thread::spawn(move || {
+            for i in 0..items(100) {
+                loop {
+                    match sender.try_send(i) {
+                        Ok(None) => break,
+                        Ok(_) => thread::yield_now(),
+                        Err(err) => panic!("{err:?}"),
+                    }
+                }
+            }
+        });
+        let mut count = 0;
+        loop {
+            match receiver.try_recv() {
+                Ok(val) => {
+                    assert_eq!(val, count);
+                    count += 1;
+                }
+                Err(Error::Empty) => thread::yield_now(),
+                Err(Error::Closed) => break,
+                Err(err) => panic!("{err:?}"),
+            }
+        }
+        assert_eq!(count, items(100));
+        handle.join().unwrap();
+        // Closed is sticky
+        assert_eq!(receiver.try_recv(), Err(Error::Closed));
+    });
+}
+
+#[test]
+pub fn test_mpmc_rts_fill_to_capacity() {
+    model(|| {
+        let (sender, receiver) =
+            ringbeam::custom::bounded::<8, u8, RelaxedTailSync, RelaxedTailSync>();
+        assert_eq!(sender.capacity(), 7);
+        for i in 0..7 {
+            assert_eq!(sender.try_send(i), Ok(None));
+        }
+        assert!(sender.is_full());
+        assert_eq!(receiver.len(), 7);
+        assert_eq!(sender.try_send(7), Ok(Some(7)));
+        assert_eq!(receiver.try_recv(), Ok(0));
+        assert_eq!(sender.try_send(7), Ok(None));
+        assert_eq!(
+            receiver.try_recv_bulk(7).unwrap().collect::<Vec<_>>(),
+            (1..8).collect::<Vec<_>>()
+        );
+    });
+}
@@ -1,24 +1,14 @@
-#![allow(clippy::missing_panics_doc, missing_docs, reason = "It's a test")]
+#![allow(
+    clippy::missing_panics_doc,
+    clippy::missing_inline_in_public_items,
+    missing_docs,
+    reason = "It's a test"
+)]
 
-use ringbeam::Error;
+mod common;
 
-#[cfg(feature = "_loom")]
-mod thread {
-    pub use loom::thread::{spawn, yield_now};
-}
-#[cfg(not(feature = "_loom"))]
-mod thread {
-    pub use std::thread::{spawn, yield_now};
-}
-#[cfg(feature = "_loom")]
-use loom::model::model;
-#[cfg(not(feature = "_loom"))]
-fn model<F>(f: F)
-where
-    F: Fn() + Send + Sync + 'static,
-{
-    f();
-}
+use common::{items, model, thread};
+use ringbeam::Error;
 
 #[test]
 pub fn test_mpsc_try_send_recv_sequential() {
@@ -35,7 +25,7 @@ pub fn test_mpsc_try_send_recv_interleaved_1() {
     model(|| {
         let (sender, receiver) = ringbeam::mpsc::bounded::<64, u8>();
         let handle = thread::spawn(move || {
-            for i in 0..100 {
+            for i in 0..items(100) {
                 loop {
                     match receiver.try_recv() {
                         Ok(val) => {
@@ -49,7 +39,7 @@ pub fn test_mpsc_try_send_recv_interleaved_1() {
             }
         });
         let handle2 = thread::spawn(move || {
-            for i in 0..100 {
+            for i in 0..items(100) {
                 loop {
                     match sender.try_send(i) {
                         Ok(None) => break,
@@ -85,14 +75,14 @@ pub fn test_mpsc_try_send_recv_interleaved_2() {
                     Err(Error::Empty) => thread::yield_now(),
                     Err(err) => panic!("{err:?}"),
                 }
-                if i == 100 && j == 101 {
+                if i == items(100) && j == items(100) + 1 {
                     break;
                 }
             }
         });
         let sender2 = sender.clone();
         let handle2 = thread::spawn(move || {
-            for i in 0..100u8 {
+            for i in 0..items(100u8) {
                 if i.is_multiple_of(2) {
                     loop {
                         match sender2.try_send(i) {
@@ -105,7 +95,7 @@ pub fn test_mpsc_try_send_recv_interleaved_2() {
             }
         });
         let handle3 = thread::spawn(move || {
-            for i in 0..100u8 {
+            for i in 0..items(100u8) {
                 if !i.is_multiple_of(2) {
                     loop {
                         match sender.try_send(i) {
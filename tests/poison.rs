@@ -0,0 +1,170 @@
+#![allow(
+    clippy::missing_panics_doc,
+    clippy::missing_inline_in_public_items,
+    missing_docs,
+    reason = "It's a test"
+)]
+// These tests use native threads or panics, which the model checkers don't support
+#![cfg(not(any(feature = "_loom", feature = "_shuttle")))]
+
+use core::{
+    panic::AssertUnwindSafe,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering::Relaxed},
+};
+use ringbeam::{
+    Error, Measure,
+    custom::modes::{Multi, Single},
+};
+use std::panic::catch_unwind;
+
+/// A value that panics when it's measured while armed.
+struct Tripwire(u32);
+
+/// Is the [`Tripwire`] armed.
+static ARMED: AtomicBool = AtomicBool::new(false);
+
+impl Measure for Tripwire {
+    fn measure(&self) -> usize {
+        assert!(!ARMED.load(Relaxed), "Tripped over {}", self.0);
+        1
+    }
+}
+
+#[test]
+pub fn test_poison_clear_last_receiver() {
+    let (sender, mut receiver) = ringbeam::spsc::bounded::<8, u32>();
+    assert_eq!(sender.try_send_slice(&[1, 2]), Ok(2));
+    let result = catch_unwind(AssertUnwindSafe(move || {
+        let _sender = sender;
+        panic!("Panic while holding the sender");
+    }));
+    assert!(result.is_err());
+    assert!(receiver.is_poisoned());
+    assert_eq!(receiver.try_recv(), Err(Error::Poisoned));
+
+    assert_eq!(receiver.try_clear_poison(), Ok(()));
+    assert!(!receiver.is_poisoned());
+    assert_eq!(receiver.try_recv(), Ok(1));
+    assert_eq!(receiver.try_recv(), Ok(2));
+    assert_eq!(receiver.try_recv(), Err(Error::Closed));
+    // Clearing a channel that isn't poisoned does nothing
+    assert_eq!(receiver.try_clear_poison(), Ok(()));
+}
+
+#[test]
+pub fn test_poison_clear_after_panic_mid_claim() {
+    let (mut sender, receiver) =
+        ringbeam::custom::bounded_with_budget::<8, Tripwire, Single, Multi>(100);
+    for i in 0..4 {
+        assert!(matches!(sender.try_send(Tripwire(i)), Ok(None)));
+    }
+    let mut spare = receiver.clone();
+    assert!(!sender.is_poisoned());
+
+    // Panic while the first two values are claimed
+    ARMED.store(true, Relaxed);
+    let result = catch_unwind(AssertUnwindSafe(move || {
+        let mut out = Vec::new();
+        let _ = receiver.try_recv_burst_into(2, &mut out);
+    }));
+    ARMED.store(false, Relaxed);
+    assert!(result.is_err());
+    assert!(sender.is_poisoned());
+    assert!(spare.is_poisoned());
+    assert_eq!(spare.try_recv().err(), Some(Error::Poisoned));
+    assert_eq!(sender.try_send(Tripwire(4)).err(), Some(Error::Poisoned));
+
+    // The sender is still around
+    assert_eq!(spare.try_clear_poison(), Err(Error::Poisoned));
+    assert_eq!(spare.try_clear_poison_with(&mut sender), Ok(()));
+
+    // The claimed values are lost, the others are still there
+    assert_eq!(spare.len_bytes(), 2);
+    assert_eq!(spare.try_recv().map(|v| v.0), Ok(2));
+    assert_eq!(spare.try_recv().map(|v| v.0), Ok(3));
+    assert_eq!(spare.try_recv().err(), Some(Error::Empty));
+
+    // The channel can be used like before
+    assert!(!sender.is_poisoned());
+    assert!(!sender.is_closed());
+    assert!(matches!(sender.try_send(Tripwire(5)), Ok(None)));
+    let other = spare.clone();
+    assert_eq!(other.try_recv().map(|v| v.0), Ok(5));
+    assert_eq!(spare.len_bytes(), 0);
+}
+
+/// A value that counts how often it's dropped, and panics when measuring the value `2` while armed.
+struct Counted(u32);
+
+/// The amount of dropped [`Counted`] values.
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+/// Is [`Counted`] armed.
+static COUNTED_ARMED: AtomicBool = AtomicBool::new(false);
+
+impl Measure for Counted {
+    fn measure(&self) -> usize {
+        assert!(
+            !(COUNTED_ARMED.load(Relaxed) && self.0 == 2),
+            "Measured the value 2"
+        );
+        1
+    }
+}
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(1, Relaxed);
+    }
+}
+
+#[test]
+pub fn test_poison_recv_array_drops_taken() {
+    let (sender, receiver) =
+        ringbeam::custom::bounded_with_budget::<8, Counted, Single, Single>(100);
+    for i in 0..4 {
+        assert!(matches!(sender.try_send(Counted(i)), Ok(None)));
+    }
+    COUNTED_ARMED.store(true, Relaxed);
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        receiver.try_recv_array::<4>().map(drop)
+    }));
+    COUNTED_ARMED.store(false, Relaxed);
+    assert!(result.is_err());
+    // The first three values were taken and dropped, the last one is still in the ring
+    assert_eq!(DROPPED.load(Relaxed), 3);
+}
+
+/// A value that panics when the value `2` is dropped.
+struct Bomb(u32);
+
+/// The amount of dropped [`Bomb`] values.
+static BOMBS_DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+impl Drop for Bomb {
+    fn drop(&mut self) {
+        BOMBS_DROPPED.fetch_add(1, Relaxed);
+        assert!(self.0 != 2, "Dropped the value 2");
+    }
+}
+
+#[test]
+pub fn test_poison_recv_values_drop_panics() {
+    let (mut sender, mut receiver) = ringbeam::spsc::bounded::<8, Bomb>();
+    for i in 0..5 {
+        assert!(matches!(sender.try_send(Bomb(i)), Ok(None)));
+    }
+    let values = receiver.try_recv_bulk(5).unwrap();
+    let result = catch_unwind(AssertUnwindSafe(move || drop(values)));
+    assert!(result.is_err());
+    // The values after the panicking one may be leaked instead of dropped
+    assert!(BOMBS_DROPPED.load(Relaxed) >= 3);
+    // The claim wasn't returned, so the ring is poisoned instead of stuck
+    assert!(receiver.is_poisoned());
+    assert_eq!(receiver.try_recv().err(), Some(Error::Poisoned));
+    assert_eq!(sender.try_send(Bomb(5)).err(), Some(Error::Poisoned));
+
+    assert_eq!(receiver.try_clear_poison_with(&mut sender), Ok(()));
+    assert!(matches!(sender.try_send(Bomb(6)), Ok(None)));
+    assert_eq!(receiver.try_recv().map(|v| v.0), Ok(6));
+}
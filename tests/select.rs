@@ -0,0 +1,53 @@
+#![allow(
+    clippy::missing_panics_doc,
+    clippy::missing_inline_in_public_items,
+    missing_docs,
+    reason = "It's a test"
+)]
+// These tests use native threads or panics, which the model checkers don't support
+#![cfg(not(any(feature = "_loom", feature = "_shuttle")))]
+
+use ringbeam::{Error, select::Selector};
+use std::thread;
+
+#[test]
+pub fn test_select_round_robin() {
+    let (sender_a, receiver_a) = ringbeam::spsc::bounded::<8, u32>();
+    let (sender_b, receiver_b) = ringbeam::mpmc::bounded::<16, u32>();
+    let mut selector = Selector::new().add(&receiver_a).add(&receiver_b);
+    assert_eq!(selector.try_recv_any(), None);
+
+    for i in 0..3 {
+        sender_a.try_send(i).unwrap();
+        sender_b.try_send(10 + i).unwrap();
+    }
+    // A busy channel doesn't starve the other one
+    let values = core::iter::from_fn(|| selector.try_recv_any()).collect::<Vec<_>>();
+    assert_eq!(values, [(1, 10), (0, 0), (1, 11), (0, 1), (1, 12), (0, 2)]);
+}
+
+#[test]
+pub fn test_select_recv_any_closed() {
+    let (sender_a, receiver_a) = ringbeam::spsc::bounded::<8, u32>();
+    let (sender_b, receiver_b) = ringbeam::spsc::bounded::<8, u32>();
+    let handle = thread::spawn(move || {
+        for i in 0..50 {
+            sender_a.send(i).unwrap();
+            sender_b.send(100 + i).unwrap();
+        }
+    });
+    let mut selector = Selector::new().add(&receiver_a).add(&receiver_b);
+    let mut total = 0;
+    loop {
+        match selector.recv_any() {
+            Ok((index, value)) => {
+                assert_eq!(index, usize::from(value >= 100));
+                total += 1;
+            }
+            Err(Error::Closed) => break,
+            Err(err) => panic!("{err:?}"),
+        }
+    }
+    assert_eq!(total, 100);
+    handle.join().unwrap();
+}
@@ -0,0 +1,192 @@
+#![allow(
+    clippy::missing_panics_doc,
+    clippy::missing_inline_in_public_items,
+    missing_docs,
+    reason = "It's a test"
+)]
+//! Every combination of producer and consumer mode under the schedulers of shuttle.
+//!
+//! The small cases are explored depth first, the larger cases with the PCT scheduler which finds
+//! bugs that need a few specific preemptions. Run them with:
+//! ```text
+//! cargo test --release --features _shuttle --test shuttle_mpmc
+//! ```
+#![cfg(feature = "_shuttle")]
+
+use core::mem::ManuallyDrop;
+use ringbeam::{
+    Error,
+    custom::{
+        Receiver, Sender,
+        modes::{HeadTailSync, Mode, Multi, RelaxedTailSync, Single},
+    },
+};
+use shuttle::{Config, MaxSteps, Runner, scheduler::DfsScheduler, thread};
+
+/// The maximum amount of schedules the depth first search explores.
+const DFS_ITERATIONS: usize = 2000;
+
+/// The steps after which the depth first search gives up on a schedule.
+///
+/// The search also tries schedules where a waiting thread spins forever while the thread it
+/// waits for never runs. Those are skipped instead of reported as a livelock.
+const DFS_MAX_STEPS: usize = 2000;
+
+/// The amount of schedules the PCT scheduler tries.
+const PCT_ITERATIONS: usize = 500;
+
+/// The amount of preemptions the PCT scheduler inserts, the bug depth it can find.
+const PCT_DEPTH: usize = 3;
+
+/// The values sent by `sender` in the test, tagged with the sender.
+fn values(sender: usize, per_sender: usize) -> impl Iterator<Item = usize> {
+    (0..per_sender).map(move |i| sender * per_sender + i)
+}
+
+/// Send from every sender to every receiver at the same time.
+///
+/// Asserts that every value is received exactly once, and that a receiver gets the values of a
+/// sender in the order they were sent. A deadlock fails the test in shuttle.
+///
+/// The handles are only dropped when a thread finishes. When the depth first search stops a
+/// schedule, shuttle unwinds the threads that are still running outside of the execution, and
+/// dropping a handle there would touch the shuttle atomics and abort the test.
+fn exchange<const N: usize, P, C>(
+    senders: Vec<ManuallyDrop<Sender<N, usize, P, C>>>,
+    receivers: Vec<ManuallyDrop<Receiver<N, usize, P, C>>>,
+    per_sender: usize,
+) where
+    P: Mode + 'static,
+    C: Mode + 'static,
+{
+    let total = senders.len() * per_sender;
+    let sending = senders
+        .into_iter()
+        .enumerate()
+        .map(|(id, sender)| {
+            thread::spawn(move || {
+                for value in values(id, per_sender) {
+                    sender
+                        .send(value)
+                        .expect("The channel closed while sending");
+                }
+                drop(ManuallyDrop::into_inner(sender));
+            })
+        })
+        .collect::<Vec<_>>();
+    let receiving = receivers
+        .into_iter()
+        .map(|receiver| {
+            thread::spawn(move || {
+                let mut received = Vec::new();
+                loop {
+                    match receiver.recv() {
+                        Ok(value) => received.push(value),
+                        Err(Error::Closed) => break,
+                        Err(err) => panic!("Unexpected error {err:?}"),
+                    }
+                }
+                drop(ManuallyDrop::into_inner(receiver));
+                received
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for handle in sending {
+        handle.join().expect("A sender panicked");
+    }
+    let mut all = Vec::with_capacity(total);
+    for handle in receiving {
+        let received = handle.join().expect("A receiver panicked");
+        for pair in received.windows(2) {
+            if pair[0] / per_sender == pair[1] / per_sender {
+                assert!(pair[0] < pair[1], "Values of a sender out of order");
+            }
+        }
+        all.extend(received);
+    }
+    all.sort_unstable();
+    assert_eq!(
+        all,
+        (0..total).collect::<Vec<_>>(),
+        "Values lost or duplicated"
+    );
+}
+
+/// Explore the schedules of `f` depth first, like [`shuttle::check_dfs`] but skipping schedules
+/// that take more than [`DFS_MAX_STEPS`].
+fn check_dfs<F>(f: F)
+where
+    F: Fn() + Send + Sync + 'static,
+{
+    let mut config = Config::new();
+    config.max_steps = MaxSteps::ContinueAfter(DFS_MAX_STEPS);
+    Runner::new(DfsScheduler::new(Some(DFS_ITERATIONS), false), config).run(f);
+}
+
+/// Create `n` handles from `handle`, which is only cloned if the mode allows more than one.
+///
+/// The handles are wrapped in [`ManuallyDrop`], see [`exchange`].
+macro_rules! handles {
+    (Single, $handle:expr, $n:expr) => {
+        vec![ManuallyDrop::new($handle)]
+    };
+    ($mode:ident, $handle:expr, $n:expr) => {{
+        let handle = ManuallyDrop::new($handle);
+        let mut handles = (1..$n)
+            .map(|_| ManuallyDrop::new((*handle).clone()))
+            .collect::<Vec<_>>();
+        handles.push(handle);
+        handles
+    }};
+}
+
+/// Generate the tests for a producer and consumer mode.
+///
+/// The small case fills the ring of 4 entries, the large case wraps around a ring of 4 entries
+/// several times with more threads on every side that isn't [`Single`].
+macro_rules! matrix {
+    ($name:ident, $prod:ident, $cons:ident) => {
+        mod $name {
+            use super::*;
+
+            #[test]
+            fn dfs() {
+                check_dfs(|| {
+                    let (sender, receiver) = ringbeam::custom::bounded::<4, usize, $prod, $cons>();
+                    exchange(handles!($prod, sender, 2), handles!($cons, receiver, 2), 2);
+                });
+            }
+
+            #[test]
+            fn pct() {
+                shuttle::check_pct(
+                    || {
+                        let (sender, receiver) =
+                            ringbeam::custom::bounded::<4, usize, $prod, $cons>();
+                        exchange(handles!($prod, sender, 3), handles!($cons, receiver, 3), 4);
+                    },
+                    PCT_ITERATIONS,
+                    PCT_DEPTH,
+                );
+            }
+        }
+    };
+}
+
+matrix!(single_single, Single, Single);
+matrix!(single_multi, Single, Multi);
+matrix!(single_hts, Single, HeadTailSync);
+matrix!(single_rts, Single, RelaxedTailSync);
+matrix!(multi_single, Multi, Single);
+matrix!(multi_multi, Multi, Multi);
+matrix!(multi_hts, Multi, HeadTailSync);
+matrix!(multi_rts, Multi, RelaxedTailSync);
+matrix!(hts_single, HeadTailSync, Single);
+matrix!(hts_multi, HeadTailSync, Multi);
+matrix!(hts_hts, HeadTailSync, HeadTailSync);
+matrix!(hts_rts, HeadTailSync, RelaxedTailSync);
+matrix!(rts_single, RelaxedTailSync, Single);
+matrix!(rts_multi, RelaxedTailSync, Multi);
+matrix!(rts_hts, RelaxedTailSync, HeadTailSync);
+matrix!(rts_rts, RelaxedTailSync, RelaxedTailSync);
@@ -1,24 +1,14 @@
-#![allow(clippy::missing_panics_doc, missing_docs, reason = "It's a test")]
+#![allow(
+    clippy::missing_panics_doc,
+    clippy::missing_inline_in_public_items,
+    missing_docs,
+    reason = "It's a test"
+)]
 
-use ringbeam::Error;
+mod common;
 
-#[cfg(feature = "_loom")]
-mod thread {
-    pub use loom::thread::{spawn, yield_now};
-}
-#[cfg(not(feature = "_loom"))]
-mod thread {
-    pub use std::thread::{spawn, yield_now};
-}
-#[cfg(feature = "_loom")]
-use loom::model::model;
-#[cfg(not(feature = "_loom"))]
-fn model<F>(f: F)
-where
-    F: Fn() + Send + Sync + 'static,
-{
-    f();
-}
+use common::{items, model, thread};
+use ringbeam::Error;
 
 #[test]
 pub fn test_spmc_try_send_recv_sequential() {
@@ -35,7 +25,7 @@ pub fn test_spmc_try_send_recv_interleaved_1() {
     model(|| {
         let (sender, receiver) = ringbeam::spmc::bounded::<64, u8>();
         let handle = thread::spawn(move || {
-            for i in 0..100 {
+            for i in 0..items(100) {
                 loop {
                     match receiver.try_recv() {
                         Ok(val) => {
@@ -49,7 +39,7 @@ pub fn test_spmc_try_send_recv_interleaved_1() {
             }
         });
         let handle2 = thread::spawn(move || {
-            for i in 0..100 {
+            for i in 0..items(100) {
                 loop {
                     match sender.try_send(i) {
                         Ok(None) => break,
@@ -65,6 +55,8 @@ pub fn test_spmc_try_send_recv_interleaved_1() {
 }
 
 #[test]
+// Two consumers spinning on an empty ring exceed the branches loom allows
+#[cfg(not(feature = "_loom"))]
 pub fn test_spmc_try_send_recv_interleaved_2() {
     model(|| {
         let (send_result, recv_result) = std::sync::mpsc::channel::<usize>();
@@ -100,7 +92,7 @@ pub fn test_spmc_try_send_recv_interleaved_2() {
             send_result.send(total).unwrap();
         });
         let handle3 = thread::spawn(move || {
-            for i in 0..100u8 {
+            for i in 0..items(100u8) {
                 loop {
                     match sender.try_send(i) {
                         Ok(None) => break,
@@ -114,7 +106,48 @@ pub fn test_spmc_try_send_recv_interleaved_2() {
         handle2.join().unwrap();
         handle3.join().unwrap();
         let got = recv_result.iter().reduce(|acc, x| acc + x).unwrap();
-        let expected = (0..100usize).reduce(|acc, x| acc + x).unwrap();
+        let expected = (0..items(100usize)).reduce(|acc, x| acc + x).unwrap();
         assert_eq!(got, expected);
     });
 }
+
+#[test]
+pub fn test_spmc_try_send_overwrite() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spmc::bounded::<4, u8>();
+        for i in 0..3 {
+            assert_eq!(sender.try_send_overwrite(i), Ok(None));
+        }
+        assert_eq!(sender.try_send_overwrite(3), Ok(Some(0)));
+        assert_eq!(sender.try_send_overwrite(4), Ok(Some(1)));
+        let values = receiver.try_recv_burst(4).unwrap().collect::<Vec<_>>();
+        assert_eq!(values, [2, 3, 4]);
+    });
+}
+
+#[test]
+pub fn test_spmc_try_send_overwrite_concurrent() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spmc::bounded::<4, u32>();
+        let handle = thread::spawn(move || {
+            let mut last = None;
+            loop {
+                match receiver.try_recv() {
+                    Ok(value) => {
+                        // Values can be skipped, but never seen twice or out of order
+                        assert!(last < Some(value), "{last:?} >= {value}");
+                        last = Some(value);
+                    }
+                    Err(Error::Empty) => thread::yield_now(),
+                    Err(Error::Closed) => break,
+                    Err(err) => panic!("{err:?}"),
+                }
+            }
+        });
+        for i in 0..items(1000) {
+            sender.try_send_overwrite(i).unwrap();
+        }
+        drop(sender);
+        handle.join().unwrap();
+    });
+}
@@ -1,29 +1,14 @@
-#![allow(clippy::missing_panics_doc, missing_docs, reason = "It's a test")]
+#![allow(
+    clippy::missing_panics_doc,
+    clippy::missing_inline_in_public_items,
+    missing_docs,
+    reason = "It's a test"
+)]
 
-use ringbeam::Error;
+mod common;
 
-#[cfg(feature = "_loom")]
-mod thread {
-    pub use loom::thread::{spawn, yield_now};
-}
-#[cfg(not(feature = "_loom"))]
-mod thread {
-    pub use std::thread::{spawn, yield_now};
-}
-#[cfg(feature = "_loom")]
-use loom::model::model;
-#[cfg(not(feature = "_loom"))]
-fn model<F>(f: F)
-where
-    F: Fn() + Send + Sync + 'static,
-{
-    #[cfg(feature = "_shuttle")]
-    {
-        shuttle::check_dfs(f, None);
-    }
-    #[cfg(not(feature = "_shuttle"))]
-    f();
-}
+use common::{items, model, thread};
+use ringbeam::Error;
 
 #[test]
 pub fn test_spsc_try_send_recv_sequential() {
@@ -40,7 +25,7 @@ pub fn test_spsc_try_send_recv_interleaved() {
     model(|| {
         let (sender, receiver) = ringbeam::spsc::bounded::<64, u8>();
         let handle = thread::spawn(move || {
-            for i in 0..100 {
+            for i in 0..items(100) {
                 loop {
                     match receiver.try_recv() {
                         Ok(val) => {
@@ -54,7 +39,7 @@ pub fn test_spsc_try_send_recv_interleaved() {
             }
         });
         let handle2 = thread::spawn(move || {
-            for i in 0..100 {
+            for i in 0..items(100) {
                 loop {
                     match sender.try_send(i) {
                         Ok(None) => break,
@@ -68,3 +53,1530 @@ pub fn test_spsc_try_send_recv_interleaved() {
         handle2.join().unwrap();
     });
 }
+
+#[test]
+pub fn test_spsc_try_send_vectored_wrap() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, u8>();
+        // Move the head to 5, so the ring wraps in the middle of the second non-empty slice
+        assert_eq!(sender.try_send_bulk(&mut [0; 5].into_iter()), Ok(5));
+        assert_eq!(receiver.try_recv_bulk(5).unwrap().count(), 5);
+
+        assert_eq!(
+            sender.try_send_vectored(&[&[1, 2], &[], &[3, 4, 5], &[]]),
+            Ok(5)
+        );
+        let values = receiver.try_recv_bulk(5).unwrap().collect::<Vec<_>>();
+        assert_eq!(values, [1, 2, 3, 4, 5]);
+    });
+}
+
+#[test]
+pub fn test_spsc_try_send_vectored_not_enough_space() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, u8>();
+        assert_eq!(sender.try_send_vectored(&[&[1, 2, 3], &[4, 5]]), Ok(5));
+        assert_eq!(
+            sender.try_send_vectored(&[&[6], &[7, 8]]),
+            Err(Error::NotEnoughSpace)
+        );
+        assert_eq!(sender.try_send_vectored(&[&[], &[]]), Ok(0));
+
+        let values = receiver.try_recv_burst(8).unwrap().collect::<Vec<_>>();
+        assert_eq!(values, [1, 2, 3, 4, 5]);
+    });
+}
+
+#[test]
+pub fn test_spsc_try_recv_vectored_wrap() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, u8>();
+        assert_eq!(
+            receiver.try_recv_vectored(&mut [&mut [0; 2]]),
+            Err(Error::Empty)
+        );
+        // Move the tail to 5, so the ring wraps in the middle of the second non-empty slice
+        assert_eq!(sender.try_send_bulk(&mut [0; 5].into_iter()), Ok(5));
+        assert_eq!(receiver.try_recv_bulk(5).unwrap().count(), 5);
+
+        assert_eq!(sender.try_send_slice(&[1, 2, 3, 4, 5, 6]), Ok(6));
+        let (mut first, mut second, mut third) = ([0; 2], [0; 3], [0; 4]);
+        assert_eq!(
+            receiver.try_recv_vectored(&mut [&mut first, &mut [], &mut second, &mut third]),
+            Ok(6)
+        );
+        assert_eq!(first, [1, 2]);
+        assert_eq!(second, [3, 4, 5]);
+        assert_eq!(third, [6, 0, 0, 0]);
+        assert_eq!(receiver.try_recv_vectored(&mut [&mut [], &mut []]), Ok(0));
+    });
+}
+
+#[test]
+pub fn test_spsc_try_send_slice() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, u32>();
+        assert_eq!(sender.try_send_slice(&[1, 2, 3, 4, 5]), Ok(5));
+        assert_eq!(
+            sender.try_send_slice(&[6, 7, 8]),
+            Err(Error::NotEnoughSpace)
+        );
+        assert_eq!(sender.try_send_slice_burst(&[6, 7, 8]), Ok(2));
+        assert_eq!(sender.try_send_slice_burst(&[8]), Err(Error::Full));
+
+        let values = receiver.try_recv_burst(8).unwrap().collect::<Vec<_>>();
+        assert_eq!(values, [1, 2, 3, 4, 5, 6, 7]);
+
+        // The head is now at 7, so this wraps around the end of the ring
+        assert_eq!(sender.try_send_slice(&[8, 9, 10]), Ok(3));
+        let values = receiver.try_recv_burst(8).unwrap().collect::<Vec<_>>();
+        assert_eq!(values, [8, 9, 10]);
+    });
+}
+
+#[test]
+pub fn test_spsc_try_recv_slice() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, u32>();
+        let mut out = [0; 4];
+        assert_eq!(receiver.try_recv_slice(&mut out), Err(Error::Empty));
+
+        // Move the tail to 6, so the next read wraps around the end of the ring
+        assert_eq!(sender.try_send_slice(&[0; 6]), Ok(6));
+        assert_eq!(receiver.try_recv_slice(&mut [0; 6]), Ok(6));
+
+        assert_eq!(sender.try_send_slice(&[1, 2, 3]), Ok(3));
+        assert_eq!(receiver.try_recv_slice(&mut out), Ok(3));
+        assert_eq!(out, [1, 2, 3, 0]);
+        assert_eq!(receiver.try_recv_slice(&mut []), Ok(0));
+
+        drop(sender);
+        assert_eq!(receiver.try_recv_slice(&mut out), Err(Error::Closed));
+    });
+}
+
+#[test]
+#[cfg(not(feature = "_safe_maybeuninit"))]
+pub fn test_spsc_recv_values_as_slices() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, String>();
+        // Move the tail to 6, so the claimed region wraps around the end of the ring
+        assert_eq!(
+            sender.try_send_bulk(&mut (0..6).map(|i| i.to_string())),
+            Ok(6)
+        );
+        assert_eq!(receiver.try_recv_bulk(6).unwrap().count(), 6);
+
+        assert_eq!(
+            sender.try_send_bulk(&mut (6..10).map(|i| i.to_string())),
+            Ok(4)
+        );
+        let mut values = receiver.try_recv_bulk(4).unwrap();
+        let (first, second) = values.as_slices();
+        assert_eq!(first, ["6", "7"]);
+        assert_eq!(second, ["8", "9"]);
+        assert_eq!(values.next().as_deref(), Some("6"));
+        let (first, second) = values.as_slices();
+        assert_eq!(first, ["7"]);
+        assert_eq!(second, ["8", "9"]);
+        assert_eq!(values.next().as_deref(), Some("7"));
+        let (first, second) = values.as_slices();
+        assert_eq!(first, ["8", "9"]);
+        assert!(second.is_empty());
+        drop(values);
+
+        assert_eq!(receiver.try_recv_burst(1).err(), Some(Error::Empty));
+    });
+}
+
+#[test]
+#[cfg(not(feature = "_safe_maybeuninit"))]
+pub fn test_spsc_try_recv_contiguous() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, u8>();
+        sender.try_send_slice(&[0; 6]).unwrap();
+        assert_eq!(receiver.try_recv_burst(6).unwrap().count(), 6);
+
+        sender.try_send_slice(&[6, 7, 8, 9]).unwrap();
+        let values = receiver.try_recv_contiguous(8).unwrap();
+        // Stops at the end of the ring, even though there are more items
+        assert_eq!(values.as_slices(), (&[6, 7][..], &[][..]));
+        drop(values);
+        let values = receiver.try_recv_contiguous(1).unwrap();
+        assert_eq!(values.as_slices(), (&[8][..], &[][..]));
+        drop(values);
+        assert_eq!(
+            receiver.try_recv_contiguous(8).unwrap().collect::<Vec<_>>(),
+            [9]
+        );
+        assert_eq!(receiver.try_recv_contiguous(8).err(), Some(Error::Empty));
+    });
+}
+
+#[test]
+#[cfg(not(feature = "_safe_maybeuninit"))]
+pub fn test_spsc_claim_write() {
+    model(|| {
+        let (mut sender, receiver) = ringbeam::spsc::bounded::<8, String>();
+        // Move the tail to 6, so the claimed region wraps around the end of the ring
+        assert_eq!(
+            sender.try_send_bulk(&mut (0..6).map(|i| i.to_string())),
+            Ok(6)
+        );
+        assert_eq!(receiver.try_recv_bulk(6).unwrap().count(), 6);
+
+        let mut guard = sender.claim_write(4).unwrap();
+        assert_eq!(guard.len(), 4);
+        let (first, second) = guard.as_mut_slices();
+        assert_eq!((first.len(), second.len()), (2, 2));
+        first[0].write("a".to_owned());
+        first[1].write("b".to_owned());
+        second[0].write("c".to_owned());
+        // SAFETY: The first three entries are initialized.
+        assert!(unsafe { guard.commit(3) }.is_ok());
+        assert_eq!(
+            receiver.try_recv_burst(8).unwrap().collect::<Vec<_>>(),
+            ["a", "b", "c"]
+        );
+
+        // Dropping the guard sends nothing
+        drop(sender.claim_write(7).unwrap());
+        assert_eq!(receiver.try_recv().err(), Some(Error::Empty));
+        assert_eq!(sender.claim_write(8).err(), Some(Error::NotEnoughSpace));
+        assert!(sender.claim_write(0).unwrap().is_empty());
+
+        drop(receiver);
+        assert_eq!(sender.claim_write(1).err(), Some(Error::Closed));
+    });
+}
+
+#[test]
+pub fn test_spsc_recv_into() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, String>();
+        let mut out = Vec::new();
+        assert_eq!(
+            sender.try_send_bulk(&mut (0..5).map(|i| i.to_string())),
+            Ok(5)
+        );
+        assert_eq!(receiver.try_recv_bulk_into(2, &mut out), Ok(2));
+        assert_eq!(
+            receiver.try_recv_bulk_into(4, &mut out),
+            Err(Error::NotEnoughItems)
+        );
+        assert_eq!(receiver.try_recv_burst_into(4, &mut out), Ok(3));
+        assert_eq!(out, ["0", "1", "2", "3", "4"]);
+
+        // The buffer is reused without allocating again
+        let capacity = out.capacity();
+        out.clear();
+        assert_eq!(
+            sender.try_send_bulk(&mut (5..9).map(|i| i.to_string())),
+            Ok(4)
+        );
+        assert_eq!(receiver.try_recv_burst_into(4, &mut out), Ok(4));
+        assert_eq!(out, ["5", "6", "7", "8"]);
+        assert_eq!(out.capacity(), capacity);
+
+        assert_eq!(receiver.try_recv_burst_into(4, &mut out), Err(Error::Empty));
+        drop(sender);
+        assert_eq!(
+            receiver.try_recv_burst_into(4, &mut out),
+            Err(Error::Closed)
+        );
+    });
+}
+
+#[test]
+pub fn test_spsc_drain_to_vec() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, String>();
+        assert_eq!(receiver.drain_to_vec(), Ok(Vec::new()));
+        assert_eq!(
+            sender.try_send_bulk(&mut (0..3).map(|i| i.to_string())),
+            Ok(3)
+        );
+        drop(sender);
+        assert_eq!(receiver.drain_to_vec().unwrap(), ["0", "1", "2"]);
+        assert_eq!(receiver.drain_to_vec(), Err(Error::Closed));
+    });
+}
+
+#[test]
+pub fn test_spsc_one_byte() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+        // Wrap around the end of the ring a few times
+        for i in 0..10 {
+            assert_eq!(sender.try_send_slice(&[i, i + 1]), Ok(2));
+            let mut out = [0; 2];
+            assert_eq!(receiver.try_recv_slice(&mut out), Ok(2));
+            assert_eq!(out, [i, i + 1]);
+        }
+        sender.try_send(42).unwrap();
+        assert_eq!(receiver.try_recv(), Ok(42));
+    });
+}
+
+#[test]
+pub fn test_spsc_odd_size() {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Odd([u8; 13]);
+    const { assert!(size_of::<Odd>() == 13) };
+
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<4, Odd>();
+        for i in 0..10 {
+            let values = [Odd([i; 13]), Odd([i + 1; 13])];
+            assert_eq!(sender.try_send_slice(&values), Ok(2));
+            let mut out = [Odd([0; 13]); 2];
+            assert_eq!(receiver.try_recv_slice(&mut out), Ok(2));
+            assert_eq!(out, values);
+        }
+        sender.try_send(Odd([42; 13])).unwrap();
+        assert_eq!(receiver.try_recv(), Ok(Odd([42; 13])));
+    });
+}
+
+#[test]
+#[cfg(not(feature = "_safe_maybeuninit"))]
+pub fn test_spsc_peek_bulk() {
+    model(|| {
+        let (sender, mut receiver) = ringbeam::spsc::bounded::<8, String>();
+        // Move the tail to 6, so the peeked region wraps around the end of the ring
+        assert_eq!(
+            sender.try_send_bulk(&mut (0..6).map(|i| i.to_string())),
+            Ok(6)
+        );
+        assert_eq!(receiver.try_recv_bulk(6).unwrap().count(), 6);
+        assert_eq!(
+            sender.try_send_bulk(&mut (0..4).map(|i| i.to_string())),
+            Ok(4)
+        );
+
+        let guard = receiver.peek_bulk(8).unwrap();
+        assert_eq!(guard.len(), 4);
+        let (first, second) = guard.as_slices();
+        assert_eq!(first, ["0", "1"]);
+        assert_eq!(second, ["2", "3"]);
+        drop(guard);
+
+        let guard = receiver.peek_bulk(3).unwrap();
+        assert_eq!(guard.len(), 3);
+        guard.consume(1);
+        let guard = receiver.peek_bulk(3).unwrap();
+        assert_eq!(
+            guard.as_slices(),
+            (&["1".to_owned()][..], &["2".to_owned(), "3".to_owned()][..])
+        );
+        guard.consume(3);
+        assert_eq!(receiver.peek_bulk(1).err(), Some(Error::Empty));
+
+        let guard = receiver.peek_bulk(0).unwrap();
+        assert!(guard.is_empty());
+        assert_eq!(guard.as_slices(), (&[][..], &[][..]));
+        guard.consume(0);
+    });
+}
+
+#[test]
+pub fn test_spsc_recv_values_nth_and_fold() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, String>();
+        // Move the tail to 5, so the claimed region wraps around the end of the ring
+        assert_eq!(
+            sender.try_send_bulk(&mut (0..5).map(|i| i.to_string())),
+            Ok(5)
+        );
+        assert_eq!(receiver.try_recv_bulk(5).unwrap().count(), 5);
+
+        assert_eq!(
+            sender.try_send_bulk(&mut (0..6).map(|i| i.to_string())),
+            Ok(6)
+        );
+        let mut values = receiver.try_recv_bulk(6).unwrap();
+        assert_eq!(values.nth(1).as_deref(), Some("1"));
+        assert_eq!(values.len(), 4);
+        let joined = values.fold(String::new(), |acc, value| acc + &value);
+        assert_eq!(joined, "2345");
+
+        assert_eq!(
+            sender.try_send_bulk(&mut (0..3).map(|i| i.to_string())),
+            Ok(3)
+        );
+        let mut values = receiver.try_recv_bulk(3).unwrap();
+        assert_eq!(values.nth(3), None);
+        assert_eq!(values.next(), None);
+        assert_eq!(receiver.try_recv().err(), Some(Error::Empty));
+        assert_eq!(receiver.len(), 0);
+    });
+}
+
+#[test]
+pub fn test_spsc_peek() {
+    model(|| {
+        let (sender, mut receiver) = ringbeam::spsc::bounded::<8, String>();
+        assert_eq!(receiver.peek().err(), Some(Error::Empty));
+
+        sender.try_send("first".to_owned()).unwrap();
+        sender.try_send("second".to_owned()).unwrap();
+        {
+            let peek = receiver.peek().unwrap();
+            assert_eq!(*peek, "first");
+        }
+        // Dropping the peek left the value in the channel
+        let peek = receiver.peek().unwrap();
+        assert_eq!(peek.take(), "first");
+        assert_eq!(receiver.try_recv().unwrap(), "second");
+        assert_eq!(receiver.peek().err(), Some(Error::Empty));
+    });
+}
+
+#[test]
+pub fn test_spsc_len() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+        assert_eq!(sender.capacity(), 3);
+        assert_eq!(receiver.capacity(), 3);
+        assert!(sender.is_empty() && receiver.is_empty());
+
+        sender.try_send(1).unwrap();
+        assert_eq!(sender.len(), 1);
+        assert_eq!(receiver.len(), 1);
+        sender.try_send(2).unwrap();
+        sender.try_send(3).unwrap();
+        assert!(sender.is_full() && receiver.is_full());
+
+        // Values that are being read are still counted
+        let mut values = receiver.try_recv_bulk(2).unwrap();
+        assert_eq!(receiver.len(), 3);
+        assert_eq!(values.next(), Some(1));
+        assert_eq!(values.next(), Some(2));
+        assert_eq!(receiver.len(), 1);
+
+        // The length is still correct after wrapping around
+        sender.try_send(4).unwrap();
+        sender.try_send(5).unwrap();
+        assert_eq!(receiver.len(), 3);
+        assert_eq!(receiver.try_recv_burst(3).unwrap().count(), 3);
+        assert!(receiver.is_empty());
+    });
+}
+
+#[test]
+pub fn test_spsc_send_recv_blocking() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+        let handle = thread::spawn(move || {
+            for i in 0..items(8) {
+                sender.send(i).unwrap();
+            }
+        });
+        for i in 0..items(8) {
+            assert_eq!(receiver.recv(), Ok(i));
+        }
+        handle.join().unwrap();
+        assert_eq!(receiver.recv(), Err(Error::Closed));
+    });
+}
+
+#[test]
+// The model checkers don't know about time
+#[cfg(all(feature = "std", not(any(feature = "_loom", feature = "_shuttle"))))]
+pub fn test_spsc_recv_timeout() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+        let timeout = core::time::Duration::from_millis(10);
+        assert_eq!(receiver.recv_timeout(timeout), Err(Error::Timeout));
+        sender.try_send(1).unwrap();
+        assert_eq!(receiver.recv_timeout(timeout), Ok(1));
+        drop(sender);
+        // Closing doesn't wait for the timeout
+        assert_eq!(
+            receiver.recv_timeout(core::time::Duration::MAX),
+            Err(Error::Closed)
+        );
+    });
+}
+
+#[test]
+// The model checkers don't know about time
+#[cfg(all(feature = "std", not(any(feature = "_loom", feature = "_shuttle"))))]
+pub fn test_spsc_recv_bulk_timeout() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+        let timeout = core::time::Duration::from_millis(10);
+        assert_eq!(
+            receiver.recv_bulk_timeout(3, timeout).err(),
+            Some(Error::Timeout)
+        );
+        // A partial batch is returned after the timeout
+        sender.try_send_slice(&[1, 2]).unwrap();
+        assert_eq!(
+            receiver
+                .recv_bulk_timeout(3, timeout)
+                .unwrap()
+                .collect::<Vec<_>>(),
+            [1, 2]
+        );
+        // A full batch doesn't wait for the timeout
+        sender.try_send_slice(&[3, 4, 5]).unwrap();
+        assert_eq!(
+            receiver
+                .recv_bulk_timeout(3, core::time::Duration::MAX)
+                .unwrap()
+                .collect::<Vec<_>>(),
+            [3, 4, 5]
+        );
+        // Neither does a batch larger than the capacity
+        sender.try_send_slice(&[6, 7, 8]).unwrap();
+        assert_eq!(
+            receiver
+                .recv_bulk_timeout(8, core::time::Duration::MAX)
+                .unwrap()
+                .collect::<Vec<_>>(),
+            [6, 7, 8]
+        );
+        // Closing doesn't wait for the timeout
+        sender.try_send(9).unwrap();
+        drop(sender);
+        assert_eq!(
+            receiver
+                .recv_bulk_timeout(3, core::time::Duration::MAX)
+                .unwrap()
+                .collect::<Vec<_>>(),
+            [9]
+        );
+        assert_eq!(
+            receiver
+                .recv_bulk_timeout(3, core::time::Duration::MAX)
+                .err(),
+            Some(Error::Closed)
+        );
+    });
+}
+
+#[test]
+// The flag is not an atomic the model checkers know about
+#[cfg(not(any(feature = "_loom", feature = "_shuttle")))]
+pub fn test_spsc_recv_cancellable() {
+    use core::sync::atomic::{AtomicBool, Ordering::Relaxed};
+    use std::sync::Arc;
+
+    let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+    let cancel = Arc::new(AtomicBool::new(false));
+    sender.try_send(1).unwrap();
+    assert_eq!(receiver.recv_cancellable(&cancel), Ok(1));
+    let cancel2 = cancel.clone();
+    let handle = std::thread::spawn(move || cancel2.store(true, Relaxed));
+    assert_eq!(receiver.recv_cancellable(&cancel), Err(Error::Cancelled));
+    handle.join().unwrap();
+    // Values that are already there are still received
+    sender.try_send(2).unwrap();
+    assert_eq!(receiver.recv_cancellable(&cancel), Ok(2));
+    drop(sender);
+    assert_eq!(receiver.recv_cancellable(&cancel), Err(Error::Closed));
+}
+
+#[test]
+pub fn test_spsc_recv_bulk_not_enough_items_and_closed() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, u8>();
+        assert_eq!(sender.try_send_slice(&[1, 2, 3]), Ok(3));
+        assert_eq!(receiver.try_recv_bulk(5).err(), Some(Error::NotEnoughItems));
+        drop(sender);
+        // Retrying won't help, but the items that are left can still be taken
+        assert_eq!(
+            receiver.try_recv_bulk(5).err(),
+            Some(Error::NotEnoughItemsAndClosed)
+        );
+        assert_eq!(
+            receiver.try_recv_burst(5).unwrap().collect::<Vec<_>>(),
+            [1, 2, 3]
+        );
+        assert_eq!(receiver.try_recv_bulk(5).err(), Some(Error::Closed));
+    });
+}
+
+#[test]
+pub fn test_spsc_closed_after_drain() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, u8>();
+        let handle = thread::spawn(move || {
+            for i in 0..items(100) {
+                loop {
+                    match sender.try_send(i) {
+                        Ok(None) => break,
+                        Ok(_) => thread::yield_now(),
+                        Err(err) => panic!("{err:?}"),
+                    }
+                }
+            }
+        });
+        let mut count = 0;
+        loop {
+            match receiver.try_recv() {
+                Ok(val) => {
+                    assert_eq!(val, count);
+                    count += 1;
+                }
+                Err(Error::Empty) => thread::yield_now(),
+                Err(Error::Closed) => break,
+                Err(err) => panic!("{err:?}"),
+            }
+        }
+        assert_eq!(count, items(100));
+        handle.join().unwrap();
+        // Closed is sticky
+        assert_eq!(receiver.try_recv(), Err(Error::Closed));
+    });
+}
+
+#[test]
+pub fn test_spsc_is_closed() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+        assert!(!sender.is_closed());
+        assert!(!receiver.is_closed());
+
+        let handle = thread::spawn(move || {
+            let mut sent = 0;
+            while !sender.is_closed() {
+                if sender.try_send(1) == Ok(None) {
+                    sent += 1;
+                }
+                thread::yield_now();
+            }
+            sent
+        });
+        assert_eq!(receiver.recv(), Ok(1));
+        drop(receiver);
+        assert!(handle.join().unwrap() >= 1);
+
+        let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+        sender.try_send(1).unwrap();
+        drop(sender);
+        assert!(receiver.is_closed());
+        assert_eq!(receiver.try_recv(), Ok(1));
+    });
+}
+
+#[test]
+pub fn test_spsc_send_iter_all() {
+    let values = || (0..items(1000)).filter(|i| i % 3 != 0);
+    model(move || {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, u32>();
+        let handle = thread::spawn(move || {
+            let result = sender.send_iter_all(values());
+            assert_eq!(result, Ok(values().count()));
+            // Once the receiver is gone nothing is sent
+            while !sender.is_closed() {
+                thread::yield_now();
+            }
+            assert_eq!(sender.send_iter_all(0..10), Err((0, Error::Closed)));
+        });
+        for i in values() {
+            assert_eq!(receiver.recv(), Ok(i));
+        }
+        drop(receiver);
+        handle.join().unwrap();
+    });
+}
+
+#[test]
+pub fn test_spsc_push_iter() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, u32>();
+        // An endless iterator is only pulled until the channel is full
+        let mut rest = sender.push_iter(0..);
+        assert_eq!(sender.len(), 7);
+        assert_eq!(rest.next(), Some(7));
+        assert_eq!(
+            receiver.try_recv_burst(8).unwrap().collect::<Vec<_>>(),
+            (0..7).collect::<Vec<_>>()
+        );
+
+        let values = (0..items(100)).filter(|i| i % 3 != 0);
+        let mut taken = Vec::new();
+        let mut rest = sender.push_iter(values.clone()).collect::<Vec<_>>();
+        while !rest.is_empty() {
+            taken.extend(receiver.try_recv_burst(4).unwrap());
+            rest = sender.push_iter(rest).collect();
+        }
+        taken.extend(receiver.try_recv_burst(8).unwrap());
+        assert_eq!(taken, values.collect::<Vec<_>>());
+
+        // Nothing is sent once the receiver is gone
+        drop(receiver);
+        assert_eq!(sender.push_iter([1, 2]).collect::<Vec<_>>(), [1, 2]);
+    });
+}
+
+#[test]
+pub fn test_spsc_drain() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, u8>();
+        assert_eq!(receiver.drain().err(), Some(Error::Empty));
+        // Fill the ring completely, wrapping around the end
+        assert_eq!(sender.try_send_slice(&[0; 3]), Ok(3));
+        assert_eq!(receiver.drain().unwrap().count(), 3);
+        assert_eq!(sender.try_send_slice(&[1, 2, 3, 4, 5, 6, 7]), Ok(7));
+        let items = receiver.drain().unwrap().collect::<Vec<_>>();
+        assert_eq!(items, [1, 2, 3, 4, 5, 6, 7]);
+        drop(sender);
+        assert_eq!(receiver.drain().err(), Some(Error::Closed));
+    });
+}
+
+#[test]
+pub fn test_spsc_debug() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, u8>();
+        assert_eq!(sender.try_send_slice(&[1, 2]), Ok(2));
+        let debug = format!("{sender:?}");
+        assert!(debug.starts_with("Sender { capacity: 7, producer_mode: \""));
+        assert!(debug.contains("Single"));
+        assert!(debug.contains("len: 2, producers: 1, consumers: 1, poisoned: false"));
+        let debug = format!("{receiver:?}");
+        assert!(debug.starts_with("Receiver { capacity: 7, "));
+    });
+}
+
+#[test]
+pub fn test_spsc_bounded_in() {
+    use ringbeam::custom::{RingStorage, modes::Single};
+    model(|| {
+        let storage = Box::leak(Box::new(RingStorage::<8, Vec<u8>, Single, Single>::new()));
+        let (sender, receiver) = ringbeam::custom::bounded_in(storage);
+        assert_eq!(sender.try_send(vec![1, 2]), Ok(None));
+        assert_eq!(sender.try_send(vec![3]), Ok(None));
+        assert_eq!(receiver.try_recv(), Ok(vec![1, 2]));
+        drop(sender);
+        assert_eq!(receiver.try_recv(), Ok(vec![3]));
+        assert_eq!(receiver.try_recv(), Err(Error::Closed));
+    });
+}
+
+#[test]
+#[cfg(not(any(feature = "_loom", feature = "_shuttle")))]
+pub fn test_spsc_static_channel() {
+    use ringbeam::custom::modes::Single;
+    ringbeam::static_channel! {
+        fn channel: <8, Vec<u8>, Single, Single>;
+    }
+    let (sender, receiver) = channel().unwrap();
+    assert!(channel().is_none());
+    assert_eq!(sender.try_send(vec![1, 2]), Ok(None));
+    assert_eq!(receiver.try_recv(), Ok(vec![1, 2]));
+    drop(sender);
+    assert_eq!(receiver.try_recv(), Err(Error::Closed));
+    drop(receiver);
+    // The storage stays taken after the channel is dropped
+    assert!(channel().is_none());
+}
+
+#[test]
+#[cfg(not(any(feature = "_loom", feature = "_shuttle")))]
+pub fn test_spsc_const_modes() {
+    use core::num::NonZeroU32;
+    use ringbeam::custom::modes::{Fairness, HeadTailSync, Multi, RelaxedTailSync, Single};
+    let _modes = const {
+        (
+            Single::new(),
+            Multi::new(0),
+            HeadTailSync::new(Fairness::Fifo),
+            RelaxedTailSync::new(NonZeroU32::MAX),
+        )
+    };
+}
+
+/// A value that is aligned to more than a cache line, on every architecture.
+#[repr(align(512))]
+#[derive(Debug, PartialEq, Eq)]
+struct OverAligned(String);
+
+#[test]
+pub fn test_spsc_over_aligned() {
+    use ringbeam::custom::{RingStorage, modes::Single};
+    model(|| {
+        let storage = Box::leak(Box::new(
+            RingStorage::<4, OverAligned, Single, Single>::new(),
+        ));
+        let channels = [
+            ringbeam::spsc::bounded::<4, OverAligned>(),
+            ringbeam::custom::bounded_in(storage),
+        ];
+        for (sender, receiver) in channels {
+            // Wrap around the end of the ring a few times
+            for round in 0..3 {
+                let expected = (0..3)
+                    .map(|i| OverAligned(format!("{round}-{i}")))
+                    .collect::<Vec<_>>();
+                for value in &expected {
+                    assert_eq!(sender.try_send(OverAligned(value.0.clone())), Ok(None));
+                }
+                let values = receiver.try_recv_bulk(3).unwrap();
+                #[cfg(not(feature = "_safe_maybeuninit"))]
+                {
+                    let (first, second) = values.as_slices();
+                    for value in first.iter().chain(second) {
+                        assert_eq!(core::ptr::from_ref(value).addr() % 512, 0);
+                    }
+                }
+                assert_eq!(values.collect::<Vec<_>>(), expected);
+            }
+        }
+    });
+}
+
+#[test]
+pub fn test_spsc_send_burst_report() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, u8>();
+        assert_eq!(
+            sender.try_send_burst_report(&mut [1, 2, 3].into_iter()),
+            Ok((3, 4))
+        );
+        let mut values = 4..10;
+        assert_eq!(sender.try_send_burst_report(&mut values), Ok((4, 0)));
+        assert_eq!(values.len(), 2);
+        assert_eq!(
+            sender.try_send_burst_report(&mut values).err(),
+            Some(Error::Full)
+        );
+        assert_eq!(receiver.try_recv(), Ok(1));
+        assert_eq!(sender.try_send_burst_report(&mut values), Ok((1, 0)));
+        assert_eq!(
+            sender.try_send_burst_report(&mut core::iter::empty()),
+            Ok((0, 0))
+        );
+    });
+}
+
+#[test]
+pub fn test_spsc_recv_array() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, u8>();
+        assert_eq!(receiver.try_recv_array::<4>(), Err(Error::Empty));
+        assert_eq!(sender.try_send_slice(&[1, 2, 3, 4, 5, 6]), Ok(6));
+        assert_eq!(receiver.try_recv_array::<4>(), Ok([1, 2, 3, 4]));
+        assert_eq!(receiver.try_recv_array::<4>(), Err(Error::NotEnoughItems));
+        // Wrap around the end of the ring
+        assert_eq!(sender.try_send_slice(&[7, 8]), Ok(2));
+        assert_eq!(receiver.try_recv_array::<4>(), Ok([5, 6, 7, 8]));
+        assert_eq!(receiver.try_recv_array::<0>(), Ok([]));
+        drop(sender);
+        assert_eq!(receiver.try_recv_array::<1>(), Err(Error::Closed));
+    });
+}
+
+#[test]
+pub fn test_spsc_reserve() {
+    model(|| {
+        let (mut sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+        // Dropping a permit sends nothing
+        drop(sender.reserve().unwrap());
+        assert_eq!(receiver.try_recv(), Err(Error::Empty));
+        for i in 0..3 {
+            sender.reserve().unwrap().send(i).unwrap();
+        }
+        assert_eq!(sender.reserve().err(), Some(Error::Full));
+        assert_eq!(receiver.try_recv(), Ok(0));
+        let permit = sender.reserve().unwrap();
+        assert_eq!(receiver.try_recv(), Ok(1));
+        permit.send(3).unwrap();
+        assert_eq!(receiver.try_recv(), Ok(2));
+        assert_eq!(receiver.try_recv(), Ok(3));
+        drop(receiver);
+        assert_eq!(sender.reserve().err(), Some(Error::Closed));
+    });
+}
+
+#[test]
+#[cfg(feature = "metrics")]
+pub fn test_spsc_metrics() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, u8>();
+        assert_eq!(sender.try_send_slice(&[1, 2, 3]), Ok(3));
+        assert_eq!(sender.try_send(4), Ok(None));
+        assert_eq!(sender.sent_total(), 4);
+        assert_eq!(receiver.try_recv(), Ok(1));
+        assert_eq!(receiver.try_recv_bulk(2).unwrap().count(), 2);
+        assert_eq!(receiver.recv_total(), 3);
+    });
+}
+
+#[test]
+pub fn test_spsc_try_send_or_len() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+        for i in 0..3 {
+            assert_eq!(sender.try_send_or_len(i), Ok(Ok(())));
+        }
+        assert_eq!(sender.try_send_or_len(3), Ok(Err((3, 0))));
+        assert_eq!(receiver.try_recv(), Ok(0));
+        assert_eq!(sender.try_send_or_len(3), Ok(Ok(())));
+        drop(receiver);
+        assert_eq!(sender.try_send_or_len(4), Err(Error::Closed));
+    });
+}
+
+#[test]
+#[cfg(feature = "debug")]
+pub fn test_spsc_ring_stats() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, u8>();
+        assert_eq!(sender.try_send_slice(&[1, 2, 3]), Ok(3));
+        assert_eq!(receiver.try_recv(), Ok(1));
+        let stats = receiver.ring_stats();
+        assert_eq!((stats.prod_head, stats.prod_tail), (3, 3));
+        assert_eq!((stats.cons_head, stats.cons_tail), (1, 1));
+        assert_eq!((stats.producers, stats.consumers), (1, 1));
+        assert!(!stats.prod_finished && !stats.cons_finished && !stats.poisoned);
+        drop(sender);
+        let stats = receiver.ring_stats();
+        assert_eq!(stats.producers, 0);
+        assert!(stats.prod_finished);
+        assert_eq!(stats.prod_tail, 3);
+    });
+}
+
+#[test]
+pub fn test_spsc_bounded_prefilled() {
+    model(|| {
+        let (sender, receiver, stored) = ringbeam::spsc::bounded_prefilled::<8, _, _>(1..4);
+        assert_eq!(stored, 3);
+        assert_eq!(sender.len(), 3);
+        assert_eq!(receiver.try_recv(), Ok(1));
+        // Only the capacity is taken from the iterator
+        let mut values = 0..10;
+        let (sender, receiver, stored) =
+            ringbeam::spsc::bounded_prefilled::<8, _, _>(values.by_ref());
+        assert_eq!(stored, 7);
+        assert_eq!(values.next(), Some(7));
+        assert_eq!(sender.try_send(0), Ok(Some(0)));
+        assert_eq!(
+            receiver.try_recv_bulk(7).unwrap().collect::<Vec<_>>(),
+            [0, 1, 2, 3, 4, 5, 6]
+        );
+        let (_sender, receiver, stored) =
+            ringbeam::spsc::bounded_prefilled::<8, u8, _>(core::iter::empty());
+        assert_eq!(stored, 0);
+        assert_eq!(receiver.try_recv(), Err(Error::Empty));
+    });
+}
+
+#[test]
+pub fn test_spsc_claim_ends_at_wrap() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, u8>();
+        assert_eq!(sender.try_send_slice(&[0, 1, 2, 3]), Ok(4));
+        assert_eq!(receiver.try_recv_bulk(4).unwrap().count(), 4);
+        // The claim ends exactly at the end of the ring, so the tails wrap to 0
+        assert_eq!(sender.try_send_bulk(&mut (4..8)), Ok(4));
+        assert_eq!(
+            receiver.try_recv_bulk(4).unwrap().collect::<Vec<_>>(),
+            [4, 5, 6, 7]
+        );
+        assert_eq!(receiver.try_recv(), Err(Error::Empty));
+        assert_eq!(sender.try_send_slice(&[8, 9, 10, 11, 12, 13, 14]), Ok(7));
+        assert_eq!(sender.try_send(15), Ok(Some(15)));
+        assert_eq!(
+            receiver.try_recv_bulk(7).unwrap().collect::<Vec<_>>(),
+            [8, 9, 10, 11, 12, 13, 14]
+        );
+    });
+}
+
+#[test]
+pub fn test_spsc_recv_values_drop_wrapped() {
+    model(|| {
+        let value = std::sync::Arc::new(0_u8);
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, std::sync::Arc<u8>>();
+        assert_eq!(
+            sender.try_send_bulk(&mut core::iter::repeat_n(value.clone(), 5)),
+            Ok(5)
+        );
+        drop(receiver.try_recv_bulk(5).unwrap());
+        assert_eq!(std::sync::Arc::strong_count(&value), 1);
+        // The next claim wraps around the end of the ring
+        assert_eq!(
+            sender.try_send_bulk(&mut core::iter::repeat_n(value.clone(), 6)),
+            Ok(6)
+        );
+        let mut values = receiver.try_recv_bulk(6).unwrap();
+        drop(values.next());
+        drop(values);
+        assert_eq!(std::sync::Arc::strong_count(&value), 1);
+        assert_eq!(sender.len(), 0);
+    });
+}
+
+#[test]
+pub fn test_spsc_drop_values_in_channel() {
+    model(|| {
+        let value = std::sync::Arc::new(0_u8);
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, std::sync::Arc<u8>>();
+        assert_eq!(
+            sender.try_send_bulk(&mut core::iter::repeat_n(value.clone(), 3)),
+            Ok(3)
+        );
+        receiver.close();
+        drop((sender, receiver));
+        assert_eq!(std::sync::Arc::strong_count(&value), 1);
+
+        let (sender, receiver) = ringbeam::spsc::bounded_dyn::<std::sync::Arc<u8>>(7);
+        assert_eq!(
+            sender.try_send_bulk(&mut core::iter::repeat_n(value.clone(), 5)),
+            Ok(5)
+        );
+        drop(receiver.try_recv_bulk(4).unwrap());
+        // The values wrap around the end of the ring
+        assert_eq!(
+            sender.try_send_bulk(&mut core::iter::repeat_n(value.clone(), 6)),
+            Ok(6)
+        );
+        assert_eq!(std::sync::Arc::strong_count(&value), 8);
+        drop(receiver);
+        drop(sender);
+        assert_eq!(std::sync::Arc::strong_count(&value), 1);
+    });
+}
+
+#[test]
+pub fn test_spsc_recv_values_into_rev() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, String>();
+        // Move the tail to 5, so the claimed region wraps around the end of the ring
+        assert_eq!(
+            sender.try_send_bulk(&mut (0..5).map(|i| i.to_string())),
+            Ok(5)
+        );
+        assert_eq!(receiver.try_recv_bulk(5).unwrap().count(), 5);
+
+        assert_eq!(
+            sender.try_send_bulk(&mut (0..6).map(|i| i.to_string())),
+            Ok(6)
+        );
+        let mut values = receiver.try_recv_bulk(6).unwrap();
+        assert_eq!(values.next().as_deref(), Some("0"));
+        let mut values = values.into_rev();
+        assert_eq!(values.len(), 5);
+        assert_eq!(
+            values.by_ref().collect::<Vec<_>>(),
+            ["5", "4", "3", "2", "1"]
+        );
+        assert_eq!(values.next(), None);
+        assert_eq!(receiver.len(), 0);
+
+        // The whole claim is given back, so the channel can be filled again
+        assert_eq!(
+            sender.try_send_bulk(&mut (0..7).map(|i| i.to_string())),
+            Ok(7)
+        );
+        assert_eq!(receiver.try_recv_bulk(7).unwrap().into_rev().count(), 7);
+        assert_eq!(receiver.try_recv().err(), Some(Error::Empty));
+    });
+}
+
+#[test]
+pub fn test_spsc_recv_values_into_rev_drop() {
+    model(|| {
+        let value = std::sync::Arc::new(0_u8);
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, std::sync::Arc<u8>>();
+        assert_eq!(
+            sender.try_send_bulk(&mut core::iter::repeat_n(value.clone(), 5)),
+            Ok(5)
+        );
+        drop(receiver.try_recv_bulk(5).unwrap());
+        // The next claim wraps around the end of the ring
+        assert_eq!(
+            sender.try_send_bulk(&mut core::iter::repeat_n(value.clone(), 6)),
+            Ok(6)
+        );
+        let mut values = receiver.try_recv_bulk(6).unwrap();
+        drop(values.next());
+        let mut values = values.into_rev();
+        drop(values.next());
+        drop(values.next());
+        assert_eq!(std::sync::Arc::strong_count(&value), 4);
+        drop(values);
+        assert_eq!(std::sync::Arc::strong_count(&value), 1);
+        assert_eq!(sender.len(), 0);
+    });
+}
+
+#[test]
+pub fn test_spsc_try_send_ref() {
+    model(|| {
+        let value = std::sync::Arc::new(0_u8);
+        let (sender, receiver) = ringbeam::spsc::bounded::<4, std::sync::Arc<u8>>();
+        for _ in 0..3 {
+            assert_eq!(sender.try_send_ref(&value), Ok(true));
+        }
+        // A full channel doesn't clone the value
+        assert_eq!(sender.try_send_ref(&value), Ok(false));
+        assert_eq!(std::sync::Arc::strong_count(&value), 4);
+        assert!(receiver.try_recv().is_ok());
+        assert_eq!(sender.try_send_ref(&value), Ok(true));
+        drop(receiver);
+        assert_eq!(sender.try_send_ref(&value), Err(Error::Closed));
+        assert_eq!(std::sync::Arc::strong_count(&value), 4);
+    });
+}
+
+#[test]
+pub fn test_spsc_iter() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, u8>();
+        let handle = thread::spawn(move || {
+            for i in 0..items(100) {
+                sender.send(i).unwrap();
+            }
+        });
+        let mut values = Vec::new();
+        for value in &receiver {
+            values.push(value);
+        }
+        handle.join().unwrap();
+        assert_eq!(values, (0..items(100)).collect::<Vec<_>>());
+        assert_eq!(receiver.iter().next(), None);
+    });
+}
+
+#[test]
+pub fn test_spsc_fill_to_capacity() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, u8>();
+        assert_eq!(sender.capacity(), 7);
+        for i in 0..7 {
+            assert_eq!(sender.try_send(i), Ok(None));
+        }
+        assert!(sender.is_full());
+        assert_eq!(receiver.len(), 7);
+        assert_eq!(sender.try_send(7), Ok(Some(7)));
+        assert_eq!(receiver.try_recv(), Ok(0));
+        assert_eq!(sender.try_send(7), Ok(None));
+        assert_eq!(
+            receiver.try_recv_bulk(7).unwrap().collect::<Vec<_>>(),
+            (1..8).collect::<Vec<_>>()
+        );
+    });
+}
+
+#[test]
+pub fn test_spsc_try_recv_if() {
+    model(|| {
+        let (sender, mut receiver) = ringbeam::spsc::bounded::<4, u8>();
+        assert_eq!(receiver.try_recv_if(|_| true), Err(Error::Empty));
+        sender.try_send_slice(&[1, 2, 3]).unwrap();
+        assert_eq!(receiver.try_recv_if(|v| *v == 1), Ok(Some(1)));
+        assert_eq!(receiver.try_recv_if(|v| *v == 1), Ok(None));
+        assert_eq!(receiver.len(), 2);
+        assert_eq!(receiver.try_recv_if(|v| *v == 2), Ok(Some(2)));
+        drop(sender);
+        assert_eq!(receiver.try_recv_if(|v| *v == 3), Ok(Some(3)));
+        assert_eq!(receiver.try_recv_if(|_| true), Err(Error::Closed));
+    });
+}
+
+#[test]
+#[cfg(not(any(feature = "_loom", feature = "_shuttle")))]
+pub fn test_spsc_try_recv_if_panic() {
+    let (sender, mut receiver) = ringbeam::spsc::bounded::<4, String>();
+    sender.try_send(String::from("a")).unwrap();
+    let res = std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| {
+        receiver.try_recv_if(|_| panic!("Predicate panicked"))
+    }));
+    assert!(res.is_err());
+    assert_eq!(receiver.try_recv(), Ok(String::from("a")));
+}
+
+#[test]
+pub fn test_spsc_recv_values_fused() {
+    fn assert_fused<I: core::iter::FusedIterator>(iter: I) -> I {
+        iter
+    }
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+        let mut values = assert_fused(receiver.try_recv_bulk(0).unwrap());
+        assert_eq!(values.next(), None);
+        assert_eq!(values.next(), None);
+        sender.try_send_slice(&[1, 2]).unwrap();
+        let mut values = assert_fused(receiver.try_recv_bulk(2).unwrap());
+        assert_eq!(values.next(), Some(1));
+        assert_eq!(values.next(), Some(2));
+        assert_eq!(values.next(), None);
+        // The claim is given back, so another receive doesn't show up in the old iterator
+        sender.try_send(3).unwrap();
+        assert_eq!(values.next(), None);
+        assert_eq!(values.nth(1), None);
+        assert_eq!(receiver.try_recv(), Ok(3));
+    });
+}
+
+#[test]
+pub fn test_spsc_recv_ack() {
+    model(|| {
+        let (sender, mut receiver) = ringbeam::spsc::bounded::<4, u8>();
+        assert_eq!(receiver.recv_ack().err(), Some(Error::Empty));
+        sender.try_send_slice(&[1, 2]).unwrap();
+        let ack = receiver.recv_ack().unwrap();
+        assert_eq!(*ack, 1);
+        drop(ack);
+        assert_eq!(receiver.len(), 2);
+        assert_eq!(receiver.recv_ack().unwrap().ack(), 1);
+        assert_eq!(receiver.recv_ack().unwrap().ack(), 2);
+        drop(sender);
+        assert_eq!(receiver.recv_ack().err(), Some(Error::Closed));
+    });
+}
+
+#[test]
+#[cfg(not(any(feature = "_loom", feature = "_shuttle")))]
+pub fn test_spsc_recv_ack_panic() {
+    let (sender, mut receiver) = ringbeam::spsc::bounded::<4, String>();
+    sender.try_send(String::from("a")).unwrap();
+    let res = std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| {
+        let ack = receiver.recv_ack().unwrap();
+        assert_eq!(*ack, "b", "Handling failed");
+        ack.ack()
+    }));
+    assert!(res.is_err());
+    assert_eq!(receiver.try_recv(), Ok(String::from("a")));
+}
+
+#[test]
+pub fn test_spsc_try_send_prefix() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+        assert_eq!(sender.try_send_prefix(Vec::new()), Ok(()));
+        assert_eq!(sender.try_send_prefix(vec![1, 2]), Ok(()));
+        assert_eq!(
+            sender.try_send_prefix(vec![3, 4, 5]),
+            Err((1, vec![4, 5], Error::Full))
+        );
+        assert_eq!(
+            sender.try_send_prefix(vec![4, 5]),
+            Err((0, vec![4, 5], Error::Full))
+        );
+        assert_eq!(
+            receiver.try_recv_bulk(3).unwrap().collect::<Vec<_>>(),
+            [1, 2, 3]
+        );
+        assert_eq!(sender.try_send_prefix(vec![4, 5]), Ok(()));
+        drop(receiver);
+        assert_eq!(
+            sender.try_send_prefix(vec![6]),
+            Err((0, vec![6], Error::Closed))
+        );
+    });
+}
+
+#[test]
+pub fn test_spsc_try_send_prefix_drop() {
+    model(|| {
+        let value = std::sync::Arc::new(());
+        let (sender, receiver) = ringbeam::spsc::bounded::<4, std::sync::Arc<()>>();
+        let values = vec![value.clone(), value.clone(), value.clone(), value.clone()];
+        let (sent, rest, _) = sender.try_send_prefix(values).unwrap_err();
+        assert_eq!((sent, rest.len()), (3, 1));
+        // Nothing is dropped or duplicated at the boundary
+        assert_eq!(std::sync::Arc::strong_count(&value), 5);
+        drop(rest);
+        drop(receiver.try_recv_bulk(3).unwrap());
+        assert_eq!(std::sync::Arc::strong_count(&value), 1);
+    });
+}
+
+#[test]
+pub fn test_spsc_try_recv_each() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, u8>();
+        let mut values = Vec::new();
+        assert_eq!(
+            receiver.try_recv_each(8, |v| values.push(v)),
+            Err(Error::Empty)
+        );
+        // Move the tail to 6, so the items wrap around the end of the ring
+        sender.try_send_slice(&[0; 6]).unwrap();
+        assert_eq!(receiver.try_recv_each(6, drop), Ok(6));
+        sender.try_send_slice(&[1, 2, 3, 4, 5]).unwrap();
+        assert_eq!(receiver.try_recv_each(4, |v| values.push(v)), Ok(4));
+        assert_eq!(receiver.try_recv_each(4, |v| values.push(v)), Ok(1));
+        assert_eq!(values, [1, 2, 3, 4, 5]);
+    });
+}
+
+#[test]
+#[cfg(not(any(feature = "_loom", feature = "_shuttle")))]
+pub fn test_spsc_try_recv_each_panic() {
+    let value = std::sync::Arc::new(());
+    let (sender, receiver) = ringbeam::spsc::bounded::<8, std::sync::Arc<()>>();
+    for _ in 0..3 {
+        sender.try_send(value.clone()).unwrap();
+    }
+    let res = std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| {
+        receiver.try_recv_each(3, |_| panic!("Handling failed"))
+    }));
+    assert!(res.is_err());
+    // The other items are dropped and the channel can still be used
+    assert_eq!(std::sync::Arc::strong_count(&value), 1);
+    assert!(!receiver.is_poisoned());
+    sender.try_send(value).unwrap();
+    assert_eq!(receiver.try_recv_each(3, drop), Ok(1));
+}
+
+#[test]
+pub fn test_spsc_try_recv_boxed() {
+    model(|| {
+        let value = std::sync::Arc::new(());
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, std::sync::Arc<()>>();
+        assert_eq!(receiver.try_recv_boxed(2).err(), Some(Error::Empty));
+        sender.try_send(value.clone()).unwrap();
+        assert_eq!(
+            receiver.try_recv_boxed(2).err(),
+            Some(Error::NotEnoughItems)
+        );
+        // The item stays in the channel when there are not enough items
+        assert_eq!(std::sync::Arc::strong_count(&value), 2);
+        sender.try_send(value.clone()).unwrap();
+        let batch = receiver.try_recv_boxed(2).unwrap();
+        assert_eq!(batch.len(), 2);
+        assert!(receiver.is_empty());
+        drop(batch);
+        assert_eq!(std::sync::Arc::strong_count(&value), 1);
+        assert!(receiver.try_recv_boxed(0).unwrap().is_empty());
+    });
+}
+
+#[test]
+// The model checkers don't know about time
+#[cfg(all(feature = "std", not(any(feature = "_loom", feature = "_shuttle"))))]
+pub fn test_spsc_send_with_progress_timeout() {
+    let idle = core::time::Duration::from_millis(20);
+    let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+    sender.try_send_slice(&[0, 1, 2]).unwrap();
+    let start = std::time::Instant::now();
+    assert_eq!(
+        sender.send_with_progress_timeout(3, idle),
+        Err((Error::Stalled, 3))
+    );
+    assert!(start.elapsed() >= idle);
+    // A consumer that keeps receiving keeps the sender waiting
+    let handle = std::thread::spawn(move || {
+        for i in 0..6 {
+            std::thread::sleep(idle / 4);
+            assert_eq!(receiver.recv(), Ok(i));
+        }
+        receiver
+    });
+    for i in 3..6 {
+        assert_eq!(sender.send_with_progress_timeout(i, idle), Ok(()));
+    }
+    let receiver = handle.join().unwrap();
+    drop(receiver);
+    assert_eq!(
+        sender.send_with_progress_timeout(6, idle),
+        Err((Error::Closed, 6))
+    );
+}
+
+#[test]
+pub fn test_spsc_flush() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+        assert_eq!(sender.flush(), Ok(()));
+        drop(receiver);
+        assert_eq!(sender.flush(), Err(Error::Closed));
+    });
+}
+
+#[test]
+pub fn test_spsc_notify() {
+    use core::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+    use ringbeam::custom::{bounded_with_notify, modes::Single};
+    use std::sync::Arc;
+
+    model(|| {
+        let sent = Arc::new(AtomicUsize::new(0));
+        let counter = sent.clone();
+        let (mut sender, receiver) = bounded_with_notify::<4, u8, Single, Single>(move |n| {
+            counter.fetch_add(n, Relaxed);
+        });
+        sender.try_send(1).unwrap();
+        sender.try_send_slice(&[2, 3]).unwrap();
+        assert_eq!(sent.load(Relaxed), 3);
+        // A failed send doesn't notify
+        assert_eq!(sender.try_send(4), Ok(Some(4)));
+        assert_eq!(sent.load(Relaxed), 3);
+
+        assert_eq!(receiver.try_recv(), Ok(1));
+        // Dropping a permit sends nothing
+        drop(sender.reserve().unwrap());
+        assert_eq!(sent.load(Relaxed), 3);
+        sender.reserve().unwrap().send(4).unwrap();
+        assert_eq!(sent.load(Relaxed), 4);
+    });
+}
+
+#[test]
+pub fn test_spsc_try_send_from() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<4, u8>();
+        assert_eq!(sender.try_send_from(&mut core::iter::empty(), 3), Ok(0));
+        assert_eq!(sender.try_send_from(&mut (0..10), 0), Ok(0));
+        // The iterator ends before the claim is filled, the rest of the room is given back
+        let mut values = (0..4).filter(|v| v % 2 == 0);
+        assert_eq!(sender.try_send_from(&mut values, 3), Ok(2));
+        assert_eq!(receiver.len(), 2);
+        // Only takes the values that fit
+        let mut values = 10..;
+        assert_eq!(sender.try_send_from(&mut values, 3), Ok(1));
+        assert_eq!(values.next(), Some(11));
+        assert_eq!(sender.try_send_from(&mut values, 3), Err(Error::Full));
+        assert_eq!(
+            receiver.try_recv_burst(4).unwrap().collect::<Vec<_>>(),
+            [0, 2, 10]
+        );
+    });
+}
+
+#[test]
+#[cfg(not(feature = "_safe_maybeuninit"))]
+pub fn test_spsc_peek_until_wrap() {
+    model(|| {
+        let (sender, mut receiver) = ringbeam::spsc::bounded::<8, u8>();
+        assert_eq!(receiver.peek_until(b'\n', 8), Err(Error::Empty));
+        // Move the head to 6, so the scan crosses the end of the ring
+        sender.try_send_slice(&[0; 6]).unwrap();
+        assert_eq!(receiver.try_recv_burst(6).unwrap().count(), 6);
+
+        sender.try_send_slice(b"ab\ncd\n").unwrap();
+        assert_eq!(receiver.peek_until(b'\n', 8), Ok(Some(2)));
+        assert_eq!(receiver.peek_until(b'd', 8), Ok(Some(4)));
+        // Only the first `max` bytes are scanned
+        assert_eq!(receiver.peek_until(b'd', 4), Ok(None));
+        assert_eq!(receiver.peek_until(b'x', 8), Ok(None));
+        assert_eq!(receiver.peek_until(b'a', 0), Ok(None));
+        // Nothing was received
+        assert_eq!(receiver.len(), 6);
+    });
+}
+
+#[test]
+pub fn test_spsc_next_capacity() {
+    // Checked at compile time
+    const {
+        assert!(ringbeam::next_capacity(0) == 2);
+        assert!(ringbeam::next_capacity(1) == 2);
+        assert!(ringbeam::next_capacity(2) == 4);
+        assert!(ringbeam::next_capacity(3) == 4);
+        assert!(ringbeam::next_capacity(4) == 8);
+        assert!(ringbeam::next_capacity((1 << 31) - 1) == 1 << 31);
+    }
+    model(|| {
+        let (sender, _receiver) = ringbeam::spsc::bounded::<{ ringbeam::next_capacity(5) }, u8>();
+        assert_eq!(sender.capacity(), 7);
+    });
+}
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+pub fn test_spsc_send_more_than_u32_max() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded::<8, u8>();
+        // The length doesn't fit in a `u32`, and would be 1 if it was truncated
+        let len = (1 << 32) + 1;
+        let mut values = core::iter::repeat_n(1, len);
+        assert_eq!(
+            sender.try_send_bulk(&mut values),
+            Err(Error::NotEnoughSpace)
+        );
+        assert_eq!(values.len(), len);
+        assert_eq!(sender.try_send_burst(&mut values), Ok(7));
+        assert_eq!(values.len(), len - 7);
+        assert_eq!(
+            receiver.try_recv_bulk(len).err(),
+            Some(Error::NotEnoughItems)
+        );
+        assert_eq!(receiver.try_recv_burst(len).unwrap().count(), 7);
+    });
+}
+
+#[test]
+pub fn test_spsc_bounded_dyn() {
+    model(|| {
+        let (sender, receiver) = ringbeam::spsc::bounded_dyn::<u8>(5);
+        assert_eq!(sender.capacity(), 7);
+        assert_eq!(receiver.capacity(), 7);
+        assert_eq!(sender.try_send_slice_burst(&[0; 8]), Ok(7));
+        assert_eq!(sender.try_send(0), Ok(Some(0)));
+        assert_eq!(receiver.try_recv_bulk(5).unwrap().count(), 5);
+
+        // The head is at 7, so the values wrap around the end of the ring
+        assert_eq!(sender.try_send_slice(&[1, 2, 3, 4, 5]), Ok(5));
+        let values = receiver.try_recv_bulk(7).unwrap();
+        #[cfg(not(feature = "_safe_maybeuninit"))]
+        assert_eq!(values.as_slices(), (&[0, 0, 1][..], &[2, 3, 4, 5][..]));
+        assert_eq!(values.collect::<Vec<_>>(), [0, 0, 1, 2, 3, 4, 5]);
+
+        assert_eq!(sender.try_send_slice(&[6, 7, 8]), Ok(3));
+        let mut out = [0; 3];
+        assert_eq!(receiver.try_recv_slice(&mut out), Ok(3));
+        assert_eq!(out, [6, 7, 8]);
+        assert_eq!(receiver.try_recv(), Err(Error::Empty));
+    });
+}
+
+#[test]
+pub fn test_spsc_bounded_dyn_drop() {
+    model(|| {
+        let value = std::sync::Arc::new(0_u8);
+        let (sender, receiver) = ringbeam::spsc::bounded_dyn::<std::sync::Arc<u8>>(100);
+        assert_eq!(sender.capacity(), 127);
+        for _ in 0..3 {
+            assert_eq!(sender.try_send(value.clone()), Ok(None));
+        }
+        assert_eq!(std::sync::Arc::strong_count(&value), 4);
+        drop(receiver.try_recv_burst(3).unwrap());
+        assert_eq!(std::sync::Arc::strong_count(&value), 1);
+        drop((sender, receiver));
+    });
+}
+
+#[test]
+pub fn test_spsc_bounded_dyn_drop_wrapped() {
+    model(|| {
+        let values: Vec<_> = (0..12).map(std::sync::Arc::new).collect();
+        let (sender, receiver) = ringbeam::spsc::bounded_dyn::<std::sync::Arc<i32>>(7);
+        for value in &values[..6] {
+            assert_eq!(sender.try_send(value.clone()), Ok(None));
+        }
+        assert_eq!(receiver.try_recv_bulk(5).unwrap().count(), 5);
+        for value in &values[6..] {
+            assert_eq!(sender.try_send(value.clone()), Ok(None));
+        }
+
+        // The claim starts at 5 and wraps around the end of the ring
+        drop(receiver.try_recv_bulk(4).unwrap());
+        for value in &values[..9] {
+            assert_eq!(std::sync::Arc::strong_count(value), 1);
+        }
+        for value in &values[9..] {
+            assert_eq!(std::sync::Arc::strong_count(value), 2);
+        }
+        assert_eq!(
+            receiver.try_recv_bulk(3).unwrap().collect::<Vec<_>>(),
+            values[9..]
+        );
+    });
+}
+
+#[test]
+#[cfg(not(any(feature = "_loom", feature = "_shuttle")))]
+#[should_panic(expected = "The array is larger than the capacity of the ring")]
+pub fn test_spsc_bounded_dyn_array_too_large() {
+    let (_sender, receiver) = ringbeam::spsc::bounded_dyn::<u8>(3);
+    let _ = receiver.try_recv_array::<4>();
+}